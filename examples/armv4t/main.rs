@@ -5,6 +5,7 @@
 use gdbstub::common::Signal;
 use gdbstub::conn::Connection;
 use gdbstub::conn::ConnectionExt;
+use gdbstub::conn::PipeConnection;
 use gdbstub::stub::run_blocking;
 use gdbstub::stub::DisconnectReason;
 use gdbstub::stub::GdbStub;
@@ -151,7 +152,9 @@ fn main() -> DynResult<()> {
     let mut emu = emu::Emu::new(TEST_PROGRAM_ELF)?;
 
     let connection: Box<dyn ConnectionExt<Error = std::io::Error>> = {
-        if std::env::args().nth(1) == Some("--uds".to_string()) {
+        if std::env::args().nth(1) == Some("--stdio".to_string()) {
+            Box::new(PipeConnection::stdio()?)
+        } else if std::env::args().nth(1) == Some("--uds".to_string()) {
             #[cfg(not(unix))]
             {
                 return Err("Unix Domain Sockets can only be used on Unix".into());
@@ -170,34 +173,34 @@ fn main() -> DynResult<()> {
     match gdb.run_blocking::<EmuGdbEventLoop>(&mut emu) {
         Ok(disconnect_reason) => match disconnect_reason {
             DisconnectReason::Disconnect => {
-                println!("GDB client has disconnected. Running to completion...");
+                eprintln!("GDB client has disconnected. Running to completion...");
                 while emu.step() != Some(emu::Event::Halted) {}
             }
             DisconnectReason::TargetExited(code) => {
-                println!("Target exited with code {}!", code)
+                eprintln!("Target exited with code {}!", code)
             }
             DisconnectReason::TargetTerminated(sig) => {
-                println!("Target terminated with signal {}!", sig)
+                eprintln!("Target terminated with signal {}!", sig)
             }
-            DisconnectReason::Kill => println!("GDB sent a kill command!"),
+            DisconnectReason::Kill => eprintln!("GDB sent a kill command!"),
         },
         Err(e) => {
             if e.is_target_error() {
-                println!(
+                eprintln!(
                     "target encountered a fatal error: {}",
                     e.into_target_error().unwrap()
                 )
             } else if e.is_connection_error() {
                 let (e, kind) = e.into_connection_error().unwrap();
-                println!("connection error: {:?} - {}", kind, e,)
+                eprintln!("connection error: {:?} - {}", kind, e,)
             } else {
-                println!("gdbstub encountered a fatal error: {}", e)
+                eprintln!("gdbstub encountered a fatal error: {}", e)
             }
         }
     }
 
     let ret = emu.cpu.reg_get(armv4t_emu::Mode::User, 0);
-    println!("Program completed. Return value: {}", ret);
+    eprintln!("Program completed. Return value: {}", ret);
 
     Ok(())
 }
@@ -0,0 +1,45 @@
+//! Register metadata for AArch64.
+
+use crate::meta::reg_index;
+use crate::meta::RegMeta;
+use crate::xml_util::XmlReg;
+use gdbstub::arch::lldb::Generic;
+
+/// The `v0`-`v31` registers occupy regnums 34-65 when SVE is enabled, but are
+/// not part of the "g" packet (as the GDB client synthesizes them from the
+/// `z0`-`z31` registers).
+pub(crate) const SVE_GAPS_XML: &str = concat!(
+    r#"<reg name="v0" bitsize="128" type="aarch64v" regnum="34"/>"#,
+    regs_xml!(128, "aarch64v":
+        "v1", "v2", "v3", "v4", "v5", "v6", "v7", "v8", "v9", "v10", "v11",
+        "v12", "v13", "v14", "v15", "v16", "v17", "v18", "v19", "v20", "v21",
+        "v22", "v23", "v24", "v25", "v26", "v27", "v28", "v29", "v30", "v31",
+    ),
+);
+
+/// DWARF register numbers and generic registers, as per the "DWARF for the
+/// Arm 64-bit Architecture" ABI document.
+pub(crate) fn aarch64_meta(reg: &XmlReg<'_>) -> RegMeta {
+    match reg.name {
+        "x29" => RegMeta::dwarf(29).generic(Generic::Fp).alt_name("fp"),
+        "x30" => RegMeta::dwarf(30).generic(Generic::Ra).alt_name("lr"),
+        "sp" => RegMeta::dwarf(31).generic(Generic::Sp),
+        "pc" => RegMeta::dwarf(32).generic(Generic::Pc),
+        "cpsr" => RegMeta::default().generic(Generic::Flags),
+        "vg" => RegMeta::dwarf(46),
+        "ffr" => RegMeta::dwarf(47),
+        name => {
+            if let Some(n @ 0..=28) = reg_index(name, "x") {
+                RegMeta::dwarf(n).arg(n)
+            } else if let Some(n) = reg_index(name, "p") {
+                RegMeta::dwarf(48 + n)
+            } else if let Some(n) = reg_index(name, "v") {
+                RegMeta::dwarf(64 + n)
+            } else if let Some(n) = reg_index(name, "z") {
+                RegMeta::dwarf(96 + n)
+            } else {
+                RegMeta::default()
+            }
+        }
+    }
+}
@@ -6,14 +6,37 @@
 //! *Note*: doesn't support the AArch32 execution mode.
 //! *Note*: the target XML currently advertises all system registers to the GDB
 //! client.
+//!
+//! Targets implementing pointer authentication (Armv8.3-PAuth) should use the
+//! [`AArch64Pauth`] or [`AArch64PauthMte`] arches, which allow GDB to strip
+//! PAC bits from return addresses when unwinding the stack.
+//!
+//! Targets implementing the Scalable Vector Extension (SVE) should use the
+//! [`AArch64Sve`] arch instead, alongside [`AArch64SveXml`].
 
+use crate::meta::RegTable;
 use gdbstub::arch::Arch;
 
 pub mod reg;
 
+mod meta;
+mod sve;
+
+pub use sve::AArch64SveXml;
+
 /// Implements `Arch` for ARM AArch64.
 pub struct AArch64 {}
 
+const AARCH64_XML: &str = concat!(
+    r#"<target version="1.0">"#,
+    "<architecture>aarch64</architecture>",
+    include_str!("core.xml"), // feature "org.gnu.gdb.aarch64.core"
+    include_str!("fpu.xml"),  // feature "org.gnu.gdb.aarch64.fpu"
+    include_str!("sysregs.xml"),
+    "</target>",
+);
+static AARCH64_REGS: RegTable = reg_table!(AARCH64_XML, "");
+
 impl Arch for AArch64 {
     type Usize = u64;
     type Registers = reg::AArch64CoreRegs;
@@ -21,15 +44,113 @@ impl Arch for AArch64 {
     type BreakpointKind = usize;
 
     fn target_description_xml() -> Option<&'static str> {
-        static DESCRIPTION_XML: &str = concat!(
-            r#"<target version="1.0">"#,
-            "<architecture>aarch64</architecture>",
-            include_str!("core.xml"), // feature "org.gnu.gdb.aarch64.core"
-            include_str!("fpu.xml"),  // feature "org.gnu.gdb.aarch64.fpu"
-            include_str!("sysregs.xml"),
-            "</target>",
-        );
-
-        Some(DESCRIPTION_XML)
+        Some(AARCH64_XML)
+    }
+
+    impl_reg_meta!(AARCH64_REGS, meta::aarch64_meta);
+}
+
+/// Implements `Arch` for ARM AArch64 with pointer authentication.
+///
+/// In addition to the registers provided by [`AArch64`], this arch advertises
+/// the `org.gnu.gdb.aarch64.pauth` feature, which exposes the
+/// `pauth_dmask` / `pauth_cmask` registers. These registers are not included in
+/// the "g" packet, and are accessed individually via the
+/// [`SingleRegisterAccess`](gdbstub::target::ext::base::single_register_access::SingleRegisterAccess)
+/// IDET, using [`AArch64RegId::PauthDmask`](reg::id::AArch64RegId::PauthDmask)
+/// and [`AArch64RegId::PauthCmask`](reg::id::AArch64RegId::PauthCmask).
+pub enum AArch64Pauth {}
+
+const AARCH64_PAUTH_XML: &str = concat!(
+    r#"<target version="1.0">"#,
+    "<architecture>aarch64</architecture>",
+    include_str!("core.xml"),  // feature "org.gnu.gdb.aarch64.core"
+    include_str!("fpu.xml"),   // feature "org.gnu.gdb.aarch64.fpu"
+    include_str!("pauth.xml"), // feature "org.gnu.gdb.aarch64.pauth"
+    include_str!("sysregs.xml"),
+    "</target>",
+);
+static AARCH64_PAUTH_REGS: RegTable = reg_table!(AARCH64_PAUTH_XML, "");
+
+impl Arch for AArch64Pauth {
+    type Usize = u64;
+    type Registers = reg::AArch64CoreRegs;
+    type RegId = reg::id::AArch64RegId;
+    type BreakpointKind = usize;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(AARCH64_PAUTH_XML)
+    }
+
+    impl_reg_meta!(AARCH64_PAUTH_REGS, meta::aarch64_meta);
+}
+
+/// Implements `Arch` for ARM AArch64 with pointer authentication and the
+/// Memory Tagging Extension (MTE).
+///
+/// In addition to the registers provided by [`AArch64Pauth`], this arch
+/// advertises the `org.gnu.gdb.aarch64.mte` feature, which exposes the
+/// `tag_ctl` register (accessed via
+/// [`AArch64RegId::TagCtl`](reg::id::AArch64RegId::TagCtl)).
+pub enum AArch64PauthMte {}
+
+const AARCH64_PAUTH_MTE_XML: &str = concat!(
+    r#"<target version="1.0">"#,
+    "<architecture>aarch64</architecture>",
+    include_str!("core.xml"),  // feature "org.gnu.gdb.aarch64.core"
+    include_str!("fpu.xml"),   // feature "org.gnu.gdb.aarch64.fpu"
+    include_str!("pauth.xml"), // feature "org.gnu.gdb.aarch64.pauth"
+    include_str!("mte.xml"),   // feature "org.gnu.gdb.aarch64.mte"
+    include_str!("sysregs.xml"),
+    "</target>",
+);
+static AARCH64_PAUTH_MTE_REGS: RegTable = reg_table!(AARCH64_PAUTH_MTE_XML, "");
+
+impl Arch for AArch64PauthMte {
+    type Usize = u64;
+    type Registers = reg::AArch64CoreRegs;
+    type RegId = reg::id::AArch64RegId;
+    type BreakpointKind = usize;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(AARCH64_PAUTH_MTE_XML)
+    }
+
+    impl_reg_meta!(AARCH64_PAUTH_MTE_REGS, meta::aarch64_meta);
+}
+
+/// Implements `Arch` for ARM AArch64 with the Scalable Vector Extension (SVE),
+/// and optionally, the Scalable Matrix Extension (SME).
+///
+/// As the SVE vector length is only known at runtime, this arch does not
+/// provide a static target description. Instead, targets must implement the
+/// [`TargetDescriptionXmlOverride`] IDET using [`AArch64SveXml`], making sure
+/// that the advertised vector length matches the `vg` register reported via
+/// [`AArch64SveRegs`](reg::AArch64SveRegs).
+///
+/// Changing the vector length mid-session is not supported, as the GDB client
+/// only reads the target description once.
+///
+/// Similarly, LLDB register info must be provided at runtime, by implementing
+/// the [`LldbRegisterInfoOverride`] IDET using
+/// [`TargetXml::lldb_register_info`], and register metadata is available via
+/// [`TargetXml::for_each_reg_info`] (rather than [`Arch::reg_info`]).
+///
+/// [`TargetDescriptionXmlOverride`]:
+///     gdbstub::target::ext::target_description_xml_override::TargetDescriptionXmlOverride
+/// [`TargetXml::lldb_register_info`]: crate::target_xml::TargetXml::lldb_register_info
+/// [`TargetXml::for_each_reg_info`]: crate::target_xml::TargetXml::for_each_reg_info
+/// [`LldbRegisterInfoOverride`]:
+///     gdbstub::target::ext::lldb_register_info_override::LldbRegisterInfoOverride
+pub enum AArch64Sve {}
+
+impl Arch for AArch64Sve {
+    type Usize = u64;
+    type Registers = reg::AArch64SveRegs;
+    type RegId = reg::id::AArch64RegId;
+    type BreakpointKind = usize;
+
+    fn target_description_xml() -> Option<&'static str> {
+        None
     }
 }
@@ -6,6 +6,7 @@ use gdbstub::arch::Registers;
 /// Registers from the `org.gnu.gdb.aarch64.core` and `org.gnu.gdb.aarch64.fpu`
 /// [AArch64 Standard GDB Target Features](https://sourceware.org/gdb/onlinedocs/gdb/AArch64-Features.html).
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AArch64CoreRegs {
     /// General Purpose Registers (X0-X30)
     pub x: [u64; 31],
@@ -0,0 +1,264 @@
+use core::convert::TryInto;
+use gdbstub::arch::Registers;
+
+/// Maximum number of 128-bit quadwords in an SVE vector register (i.e: the
+/// maximum architectural vector length of 2048 bits).
+pub const SVE_VQ_MAX: usize = 16;
+
+/// AArch64 core registers + Scalable Vector Extension (SVE) registers.
+///
+/// Registers from the `org.gnu.gdb.aarch64.core` and `org.gnu.gdb.aarch64.sve`
+/// [AArch64 Standard GDB Target Features](https://sourceware.org/gdb/onlinedocs/gdb/AArch64-Features.html).
+///
+/// The SVE vector length is only known at runtime, and is determined by the
+/// value of the `vg` register (the number of 64-bit granules in a vector
+/// register). Storage is reserved for the maximum architectural vector length
+/// (2048 bits), but only the low `vg * 64` bits of each `z` register (and the
+/// corresponding low `vg * 8` bits of each predicate register) are sent to /
+/// received from the GDB client. The value of `vg` _must_ match the vector
+/// length advertised via [`AArch64SveXml`](crate::aarch64::AArch64SveXml).
+///
+/// When SVE is in use, the FP & SIMD `v0`-`v31` registers are
+/// pseudo-registers synthesized by the GDB client from the low 128 bits of
+/// the corresponding `z` registers (i.e: `z[n][0]`).
+///
+/// Note that this struct is ~9KiB in size.
+///
+/// Source: <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/aarch64-sve.c>
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AArch64SveRegs {
+    /// General Purpose Registers (X0-X30)
+    pub x: [u64; 31],
+    /// Stack Pointer
+    pub sp: u64,
+    /// Program Counter
+    pub pc: u64,
+    /// Process State (GDB uses the AArch32 CPSR name)
+    pub cpsr: u32,
+    /// Floating-point Status Register
+    pub fpsr: u32,
+    /// Floating-point Control Register
+    pub fpcr: u32,
+    /// Scalable Vector Registers (Z0-Z31), as a list of 128-bit quadwords
+    /// (least significant quadword first)
+    pub z: [[u128; SVE_VQ_MAX]; 32],
+    /// Scalable Predicate Registers (P0-P15), as a list of 16-bit chunks
+    /// (one per quadword of vector length, least significant chunk first)
+    pub p: [[u16; SVE_VQ_MAX]; 16],
+    /// First Fault Register (same layout as the predicate registers)
+    pub ffr: [u16; SVE_VQ_MAX],
+    /// Vector Granule: the current vector length, in units of 64 bits
+    pub vg: u64,
+}
+
+impl AArch64SveRegs {
+    /// Returns the current vector length in units of 128-bit quadwords, as
+    /// determined by the value of `vg`.
+    ///
+    /// Returns `None` if `vg` does not encode a valid vector length.
+    pub fn vq(&self) -> Option<usize> {
+        match self.vg {
+            vg if vg % 2 == 0 && (2..=(SVE_VQ_MAX as u64 * 2)).contains(&vg) => {
+                Some(vg as usize / 2)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Registers for AArch64SveRegs {
+    type ProgramCounter = u64;
+
+    fn pc(&self) -> Self::ProgramCounter {
+        self.pc
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        macro_rules! write_bytes {
+            ($var: expr) => {
+                for b in $var.to_le_bytes() {
+                    write_byte(Some(b))
+                }
+            };
+        }
+
+        // an invalid `vg` results in truncated `z`, `p`, and `ffr` registers,
+        // which will be rejected by the GDB client.
+        let vq = self.vq().unwrap_or(0);
+
+        for reg in self.x.iter() {
+            write_bytes!(reg);
+        }
+        write_bytes!(self.sp);
+        write_bytes!(self.pc);
+        write_bytes!(self.cpsr);
+        // regnums 34-65 (the fixed-width `v` registers) are not described by
+        // the SVE target XML, and therefore take up no space in the "g" packet.
+        write_bytes!(self.fpsr);
+        write_bytes!(self.fpcr);
+        for reg in self.z.iter() {
+            for q in reg[..vq].iter() {
+                write_bytes!(q);
+            }
+        }
+        for reg in self.p.iter() {
+            for chunk in reg[..vq].iter() {
+                write_bytes!(chunk);
+            }
+        }
+        for chunk in self.ffr[..vq].iter() {
+            write_bytes!(chunk);
+        }
+        write_bytes!(self.vg);
+    }
+
+    fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        const CPSR_OFF: usize = core::mem::size_of::<u64>() * 33;
+        const FPSR_OFF: usize = CPSR_OFF + core::mem::size_of::<u32>();
+        const Z_OFF: usize = FPSR_OFF + core::mem::size_of::<u32>() * 2;
+
+        // `vg` is always the last register in the packet, and determines the
+        // size of all the preceding scalable registers.
+        if bytes.len() < Z_OFF + core::mem::size_of::<u64>() {
+            return Err(());
+        }
+        let (bytes, vg) = bytes.split_at(bytes.len() - core::mem::size_of::<u64>());
+        let vg = u64::from_le_bytes(vg.try_into().unwrap());
+
+        let mut new = AArch64SveRegs {
+            vg,
+            ..Default::default()
+        };
+        let vq = new.vq().ok_or(())?;
+
+        let p_off = Z_OFF + core::mem::size_of::<u128>() * vq * 32;
+        let ffr_off = p_off + core::mem::size_of::<u16>() * vq * 16;
+        let end = ffr_off + core::mem::size_of::<u16>() * vq;
+
+        if bytes.len() != end {
+            return Err(());
+        }
+
+        let mut regs = bytes[0..CPSR_OFF]
+            .chunks_exact(core::mem::size_of::<u64>())
+            .map(|c| u64::from_le_bytes(c.try_into().unwrap()));
+
+        for reg in new.x.iter_mut() {
+            *reg = regs.next().ok_or(())?
+        }
+        new.sp = regs.next().ok_or(())?;
+        new.pc = regs.next().ok_or(())?;
+
+        let mut regs = bytes[CPSR_OFF..Z_OFF]
+            .chunks_exact(core::mem::size_of::<u32>())
+            .map(|c| u32::from_le_bytes(c.try_into().unwrap()));
+
+        new.cpsr = regs.next().ok_or(())?;
+        new.fpsr = regs.next().ok_or(())?;
+        new.fpcr = regs.next().ok_or(())?;
+
+        let mut regs = bytes[Z_OFF..p_off]
+            .chunks_exact(core::mem::size_of::<u128>())
+            .map(|c| u128::from_le_bytes(c.try_into().unwrap()));
+
+        for reg in new.z.iter_mut() {
+            for q in reg[..vq].iter_mut() {
+                *q = regs.next().ok_or(())?
+            }
+        }
+
+        let mut regs = bytes[p_off..end]
+            .chunks_exact(core::mem::size_of::<u16>())
+            .map(|c| u16::from_le_bytes(c.try_into().unwrap()));
+
+        for reg in new.p.iter_mut() {
+            for chunk in reg[..vq].iter_mut() {
+                *chunk = regs.next().ok_or(())?
+            }
+        }
+        for chunk in new.ffr[..vq].iter_mut() {
+            *chunk = regs.next().ok_or(())?
+        }
+
+        *self = new;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aarch64_sve_round_trip() {
+        for vq in [1, 3, 16] {
+            let mut regs_before = AArch64SveRegs {
+                pc: 0x1000,
+                cpsr: 0x6000_03c5,
+                fpsr: 1,
+                fpcr: 2,
+                vg: vq as u64 * 2,
+                ..Default::default()
+            };
+            for (i, reg) in regs_before.z.iter_mut().enumerate() {
+                for (j, q) in reg[..vq].iter_mut().enumerate() {
+                    *q = (i as u128) << 64 | j as u128;
+                }
+            }
+            for (i, reg) in regs_before.p.iter_mut().enumerate() {
+                reg[vq - 1] = 0x100 | i as u16;
+            }
+            regs_before.ffr[0] = 0xffff;
+
+            let mut data = vec![];
+            regs_before.gdb_serialize(|x| {
+                data.push(x.unwrap_or(b'x'));
+            });
+
+            assert_eq!(
+                data.len(),
+                33 * 8 + 3 * 4 + 32 * 16 * vq + 16 * 2 * vq + 2 * vq + 8
+            );
+
+            let mut regs_after = AArch64SveRegs::default();
+            regs_after.gdb_deserialize(&data).unwrap();
+
+            assert_eq!(regs_before, regs_after);
+
+            // mismatched length
+            assert!(regs_after.gdb_deserialize(&data[1..]).is_err());
+        }
+    }
+
+    #[test]
+    fn aarch64_sve_invalid_vg() {
+        let regs = AArch64SveRegs {
+            vg: 3,
+            ..Default::default()
+        };
+        assert_eq!(regs.vq(), None);
+
+        let mut data = vec![];
+        regs.gdb_serialize(|x| data.push(x.unwrap_or(b'x')));
+
+        let mut regs_after = AArch64SveRegs::default();
+        assert!(regs_after.gdb_deserialize(&data).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let mut z = AArch64SveRegs::default().z;
+        z[31][1] = u128::MAX;
+        let regs = AArch64SveRegs {
+            vg: 4,
+            z,
+            ..Default::default()
+        };
+
+        let json = serde_json::to_string(&regs).unwrap();
+        assert_eq!(serde_json::from_str::<AArch64SveRegs>(&json).unwrap(), regs);
+    }
+}
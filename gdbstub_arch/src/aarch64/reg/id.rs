@@ -28,7 +28,13 @@ use gdbstub::arch::RegId;
 /// must encode system registers by using their 16-bit encoding as the `regnum`
 /// property; no clash with architectural registers is possible as the top bit
 /// of the 16-bit value is guaranteed to be set.
+///
+/// The scalable SVE / SME registers use fixed `regnum`s (see
+/// [`AArch64SveXml`](crate::aarch64::AArch64SveXml)), and their size is only
+/// known at runtime. As such, [`RegId::from_raw_id`] does not report a size
+/// for the `Z`, `P`, `Ffr`, and `Za` variants.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum AArch64RegId {
     /// General-purpose Register File (X0 - X30)
@@ -43,6 +49,28 @@ pub enum AArch64RegId {
     V(u8),
     /// System Registers encoded as (Op0:2, Op1:3, CRn:4, CRm:4, Op2:2)
     System(u16),
+    /// SVE Scalable Vector Register File (Z0 - Z31)
+    Z(u8),
+    /// SVE Predicate Register File (P0 - P15)
+    P(u8),
+    /// SVE First Fault Register
+    Ffr,
+    /// SVE Vector Granule (current vector length, in units of 64 bits)
+    Vg,
+    /// SME Streaming Vector Granule (current streaming vector length, in units
+    /// of 64 bits)
+    Svg,
+    /// SME Streaming Vector Control Register
+    Svcr,
+    /// SME ZA Array
+    Za,
+    /// Pointer Authentication data address mask
+    PauthDmask,
+    /// Pointer Authentication code address mask
+    PauthCmask,
+    /// Memory Tagging Extension tag control (Linux `PR_SET_TAGGED_ADDR_CTRL`
+    /// value)
+    TagCtl,
 }
 
 impl RegId for AArch64RegId {
@@ -55,6 +83,16 @@ impl RegId for AArch64RegId {
             34..=65 => Self::V((id - 34) as u8),
             66 => Self::FPSR,
             67 => Self::FPCR,
+            68..=99 => Self::Z((id - 68) as u8),
+            100..=115 => Self::P((id - 100) as u8),
+            116 => Self::Ffr,
+            117 => Self::Vg,
+            118 => Self::Svg,
+            119 => Self::Svcr,
+            120 => Self::Za,
+            121 => Self::PauthDmask,
+            122 => Self::PauthCmask,
+            123 => Self::TagCtl,
             #[allow(clippy::unusual_byte_groupings)]
             // We configure GDB to use regnums that correspond to the architectural u16 opcode
             // and avoid clashes with core registers thanks to op0==0b00 and op0==0b01 not being
@@ -63,7 +101,7 @@ impl RegId for AArch64RegId {
             _ => return None,
         };
 
-        Some((reg, Some(NonZeroUsize::new(reg.len()?)?)))
+        Some((reg, reg.len().and_then(NonZeroUsize::new)))
     }
 }
 
@@ -71,12 +109,20 @@ impl RegId for AArch64RegId {
 impl AArch64RegId {
     #[allow(clippy::len_without_is_empty)]
     /// Gives the size of the register.
+    ///
+    /// Returns `None` for invalid registers, and for scalable registers whose
+    /// size is only known at runtime (i.e: `Z`, `P`, `Ffr`, and `Za`).
     pub fn len(&self) -> Option<usize> {
         match self {
             Self::Pstate => Some(core::mem::size_of::<u32>()),
             Self::X(_n @ 0..=30) => Some(core::mem::size_of::<u64>()),
             Self::V(_n @ 0..=31) => Some(core::mem::size_of::<u128>()),
+            Self::System(_) if *self == Self::FPSR || *self == Self::FPCR => {
+                Some(core::mem::size_of::<u32>())
+            }
             Self::Pc | Self::Sp | Self::System(_) => Some(core::mem::size_of::<u64>()),
+            Self::Vg | Self::Svg | Self::Svcr => Some(core::mem::size_of::<u64>()),
+            Self::PauthDmask | Self::PauthCmask | Self::TagCtl => Some(core::mem::size_of::<u64>()),
             _ => None,
         }
     }
@@ -1830,3 +1876,20 @@ impl AArch64RegId {
     /// Debug Vector Catch Register
     pub const DBGVCR32_EL2: Self = Self::System(0b10_100_0000_0111_000);
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_round_trip() {
+        for reg in [
+            AArch64RegId::X(3),
+            AArch64RegId::FPCR,
+            AArch64RegId::MDSCR_EL1,
+        ] {
+            let json = serde_json::to_string(&reg).unwrap();
+            assert_eq!(serde_json::from_str::<AArch64RegId>(&json).unwrap(), reg);
+        }
+    }
+}
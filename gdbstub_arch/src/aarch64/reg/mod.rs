@@ -4,5 +4,8 @@
 pub mod id;
 
 mod aarch64_core;
+mod aarch64_sve;
 
 pub use aarch64_core::AArch64CoreRegs;
+pub use aarch64_sve::AArch64SveRegs;
+pub use aarch64_sve::SVE_VQ_MAX;
@@ -0,0 +1,449 @@
+//! Runtime-generated target description XML for the AArch64 Scalable Vector
+//! Extension (SVE) and Scalable Matrix Extension (SME).
+
+use super::meta;
+use super::reg::SVE_VQ_MAX;
+use crate::meta::RegMeta;
+use crate::target_xml::sealed::RegSource;
+use crate::target_xml::TargetXml;
+use crate::xml_util::indexed_name;
+use crate::xml_util::write_features;
+use crate::xml_util::XmlReg;
+use core::fmt;
+use gdbstub::arch::tdesc::Field;
+use gdbstub::arch::tdesc::Reg;
+use gdbstub::arch::tdesc::RegLayout;
+use gdbstub::arch::tdesc::TdescError;
+use gdbstub::arch::tdesc::TdescWriter;
+use gdbstub::arch::tdesc::Type;
+
+/// Target description XML for AArch64 targets implementing SVE (and
+/// optionally SME), with a runtime-specified vector length.
+///
+/// The generated XML contains the `org.gnu.gdb.aarch64.core` feature, the
+/// `org.gnu.gdb.aarch64.sve` feature with the scalable vector registers
+/// (`z0`-`z31`, `p0`-`p15`, `ffr`, `vg`) along with `fpsr` and `fpcr`, and
+/// (if enabled) the `org.gnu.gdb.aarch64.sme` feature with the `svg`, `svcr`,
+/// and `za` registers. The `org.gnu.gdb.aarch64.pauth` and
+/// `org.gnu.gdb.aarch64.mte` features can also be enabled (see
+/// [`AArch64Pauth`](super::AArch64Pauth) and
+/// [`AArch64PauthMte`](super::AArch64PauthMte)). System registers are
+/// advertised in the same manner as the [`AArch64`](super::AArch64) arch.
+///
+/// SME registers are not a part of the "g" packet, and must be accessed via
+/// [`AArch64RegId::Svg`], [`AArch64RegId::Svcr`], and [`AArch64RegId::Za`]
+/// (i.e: by implementing the `SingleRegisterAccess` IDET). Note that the `za`
+/// register can be quite large (up to 64KiB), and reading it may require a
+/// correspondingly large packet buffer.
+///
+/// [`AArch64RegId::Svg`]: super::reg::id::AArch64RegId::Svg
+/// [`AArch64RegId::Svcr`]: super::reg::id::AArch64RegId::Svcr
+/// [`AArch64RegId::Za`]: super::reg::id::AArch64RegId::Za
+///
+/// # Example
+///
+/// ```
+/// use gdbstub_arch::aarch64::AArch64SveXml;
+/// use gdbstub_arch::target_xml::TargetXml;
+///
+/// // in `TargetDescriptionXmlOverride::target_description_xml`
+/// # let (annex, offset, length, mut buf) = (b"target.xml", 0, 0x1000, [0; 0x1000]);
+/// // 512-bit SVE vectors + 256-bit SME streaming vectors
+/// let xml = AArch64SveXml::new(4).unwrap().with_sme(2).unwrap();
+/// let n = match annex {
+///     b"target.xml" => xml.read_range(offset, length, &mut buf),
+///     _ => 0, // return Err(TargetError::NonFatal)
+/// };
+/// assert!(n > 0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AArch64SveXml {
+    vq: usize,
+    svq: Option<usize>,
+    pauth: bool,
+    mte: bool,
+}
+
+impl AArch64SveXml {
+    /// Create a new target description with the specified SVE vector length,
+    /// in units of 128-bit quadwords (i.e: `VL / 128`).
+    ///
+    /// Returns `None` if `vq` is not in the range `1..=16`.
+    pub fn new(vq: usize) -> Option<Self> {
+        if !(1..=SVE_VQ_MAX).contains(&vq) {
+            return None;
+        }
+
+        Some(AArch64SveXml {
+            vq,
+            svq: None,
+            pauth: false,
+            mte: false,
+        })
+    }
+
+    /// Include the SME feature in the target description, with the specified
+    /// streaming vector length, in units of 128-bit quadwords (i.e: `SVL /
+    /// 128`).
+    ///
+    /// Returns `None` if `svq` is not a power of two in the range `1..=16`,
+    /// as required by the SME specification.
+    pub fn with_sme(self, svq: usize) -> Option<Self> {
+        if !svq.is_power_of_two() || svq > SVE_VQ_MAX {
+            return None;
+        }
+
+        Some(AArch64SveXml {
+            svq: Some(svq),
+            ..self
+        })
+    }
+
+    /// Include the `org.gnu.gdb.aarch64.pauth` feature in the target
+    /// description.
+    pub fn with_pauth(self) -> Self {
+        AArch64SveXml {
+            pauth: true,
+            ..self
+        }
+    }
+
+    /// Include the `org.gnu.gdb.aarch64.mte` feature in the target
+    /// description.
+    pub fn with_mte(self) -> Self {
+        AArch64SveXml { mte: true, ..self }
+    }
+
+    /// The SVE vector length, in units of 128-bit quadwords.
+    pub fn vq(&self) -> usize {
+        self.vq
+    }
+
+    /// The SME streaming vector length, in units of 128-bit quadwords (if SME
+    /// is enabled).
+    pub fn svq(&self) -> Option<usize> {
+        self.svq
+    }
+
+    fn write_tdesc(&self, mut xml: TdescWriter<'_, '_>) -> Result<RegLayout, TdescError> {
+        xml.architecture("aarch64")?;
+        // feature "org.gnu.gdb.aarch64.core"
+        write_features(&mut xml, include_str!("core.xml"), |_| true)?;
+
+        self.write_sve_feature(&mut xml)?;
+        if let Some(svq) = self.svq {
+            self.write_sme_feature(&mut xml, svq)?;
+        }
+        if self.pauth {
+            write_features(&mut xml, include_str!("pauth.xml"), |_| false)?;
+        }
+        if self.mte {
+            write_features(&mut xml, include_str!("mte.xml"), |_| false)?;
+        }
+        write_features(&mut xml, include_str!("sysregs.xml"), |_| false)?;
+
+        xml.finish().map(|(_, layout)| layout)
+    }
+
+    fn write_sve_feature(&self, xml: &mut TdescWriter<'_, '_>) -> Result<(), TdescError> {
+        let vq = self.vq;
+
+        xml.feature("org.gnu.gdb.aarch64.sve")?;
+
+        for (id, ty, count) in [
+            ("svevdf", "ieee_double", 2 * vq),
+            ("svevdu", "uint64", 2 * vq),
+            ("svevds", "int64", 2 * vq),
+            ("svevsf", "ieee_single", 4 * vq),
+            ("svevsu", "uint32", 4 * vq),
+            ("svevss", "int32", 4 * vq),
+            ("svevhf", "ieee_half", 8 * vq),
+            ("svevhu", "uint16", 8 * vq),
+            ("svevhs", "int16", 8 * vq),
+            ("svevbu", "uint8", 16 * vq),
+            ("svevbs", "int8", 16 * vq),
+            ("svevqu", "uint128", vq),
+            ("svevqs", "int128", vq),
+            ("svep", "uint8", 2 * vq),
+        ] {
+            xml.ty(Type::Vector { id, ty, count })?;
+        }
+
+        for (id, fields) in [
+            (
+                "svevnq",
+                &[Field::new("u", "svevqu"), Field::new("s", "svevqs")][..],
+            ),
+            (
+                "svevnd",
+                &[
+                    Field::new("f", "svevdf"),
+                    Field::new("u", "svevdu"),
+                    Field::new("s", "svevds"),
+                ],
+            ),
+            (
+                "svevns",
+                &[
+                    Field::new("f", "svevsf"),
+                    Field::new("u", "svevsu"),
+                    Field::new("s", "svevss"),
+                ],
+            ),
+            (
+                "svevnh",
+                &[
+                    Field::new("f", "svevhf"),
+                    Field::new("u", "svevhu"),
+                    Field::new("s", "svevhs"),
+                ],
+            ),
+            (
+                "svevnb",
+                &[Field::new("u", "svevbu"), Field::new("s", "svevbs")],
+            ),
+            (
+                "svev",
+                &[
+                    Field::new("q", "svevnq"),
+                    Field::new("d", "svevnd"),
+                    Field::new("s", "svevns"),
+                    Field::new("h", "svevnh"),
+                    Field::new("b", "svevnb"),
+                ],
+            ),
+        ] {
+            xml.ty(Type::Union { id, fields })?;
+        }
+
+        // NOTE: explicit regnums are used such that the `fpsr` and `fpcr`
+        // registers retain their `org.gnu.gdb.aarch64.fpu` regnums, and such
+        // that the fixed-width `v` registers and the scalable `z` registers
+        // have distinct regnums.
+        xml.reg(Reg::new("fpsr", 32).regnum(66).ty("int"))?;
+        xml.reg(Reg::new("fpcr", 32).regnum(67).ty("int"))?;
+
+        let mut name = [0; 4];
+        for i in 0..32 {
+            let reg = Reg::new(indexed_name(&mut name, "z", i), 128 * vq)
+                .regnum(68 + i)
+                .ty("svev");
+            xml.reg(reg)?;
+        }
+        for i in 0..16 {
+            let reg = Reg::new(indexed_name(&mut name, "p", i), 16 * vq)
+                .regnum(100 + i)
+                .ty("svep");
+            xml.reg(reg)?;
+        }
+        xml.reg(Reg::new("ffr", 16 * vq).regnum(116).ty("svep"))?;
+        xml.reg(Reg::new("vg", 64).regnum(117).ty("int"))?;
+
+        Ok(())
+    }
+
+    fn write_sme_feature(
+        &self,
+        xml: &mut TdescWriter<'_, '_>,
+        svq: usize,
+    ) -> Result<(), TdescError> {
+        // streaming vector length, in bytes
+        let svl = svq * 16;
+
+        xml.feature("org.gnu.gdb.aarch64.sme")?;
+        xml.ty(Type::Flags {
+            id: "svcr_flags",
+            size: 8,
+            fields: &[Field::bit("SM", 0), Field::bit("ZA", 1)],
+        })?;
+        xml.ty(Type::Vector {
+            id: "sme_bv",
+            ty: "uint8",
+            count: svl,
+        })?;
+        xml.ty(Type::Vector {
+            id: "sme_bvv",
+            ty: "sme_bv",
+            count: svl,
+        })?;
+
+        // SME registers are not a part of the "g" packet
+        for reg in [
+            Reg::new("svg", 64).regnum(118).ty("int"),
+            Reg::new("svcr", 64).regnum(119).ty("svcr_flags"),
+            Reg::new("za", svl * svl * 8).regnum(120).ty("sme_bvv"),
+        ] {
+            xml.reg(reg.exclude_from_g_packet())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl TargetXml for AArch64SveXml {}
+
+impl RegSource for AArch64SveXml {
+    fn gaps_xml(&self) -> &'static str {
+        meta::SVE_GAPS_XML
+    }
+
+    fn reg_meta(&self, reg: &XmlReg<'_>) -> RegMeta {
+        meta::aarch64_meta(reg)
+    }
+}
+
+impl fmt::Display for AArch64SveXml {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_tdesc(TdescWriter::target_fmt(f))
+            .map(drop)
+            .map_err(|_| fmt::Error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aarch64::reg::id::AArch64RegId;
+    use crate::aarch64::reg::AArch64SveRegs;
+    use gdbstub::arch::RegId;
+    use gdbstub::arch::Registers;
+
+    #[test]
+    fn aarch64_sve_xml() {
+        assert!(AArch64SveXml::new(0).is_none());
+        assert!(AArch64SveXml::new(17).is_none());
+        assert!(AArch64SveXml::new(1).unwrap().with_sme(3).is_none());
+        assert!(AArch64SveXml::new(1).unwrap().with_sme(32).is_none());
+
+        let xml = AArch64SveXml::new(2).unwrap().to_string();
+        assert!(xml.ends_with("</feature>\n</target>"));
+        assert!(xml.contains(concat!(
+            r#"<reg name="fpcr" bitsize="32" regnum="67" type="int"/>"#,
+            r#"<reg name="z0" bitsize="256" regnum="68" type="svev"/>"#,
+        )));
+        assert!(xml.contains(r#"<reg name="p15" bitsize="32" regnum="115" type="svep"/>"#));
+        assert!(xml.contains(r#"<vector id="svevqu" type="uint128" count="2"/>"#));
+        assert!(!xml.contains("org.gnu.gdb.aarch64.fpu"));
+        assert!(!xml.contains("org.gnu.gdb.aarch64.sme"));
+
+        let xml = AArch64SveXml::new(2).unwrap().with_sme(4).unwrap();
+        assert_eq!(xml.svq(), Some(4));
+        let xml = xml.to_string();
+        assert!(xml.contains(r#"<vector id="sme_bvv" type="sme_bv" count="64"/>"#));
+        assert!(xml.contains(r#"<reg name="za" bitsize="32768" regnum="120" type="sme_bvv"/>"#));
+        assert!(!xml.contains("org.gnu.gdb.aarch64.pauth"));
+
+        let xml = AArch64SveXml::new(1)
+            .unwrap()
+            .with_pauth()
+            .with_mte()
+            .to_string();
+        assert!(xml.contains(r#"<reg name="pauth_cmask" bitsize="64" regnum="122"/>"#));
+        assert!(xml.contains(r#"<feature name="org.gnu.gdb.aarch64.mte">"#));
+    }
+
+    #[test]
+    fn aarch64_sve_lldb_register_info() {
+        let xml = AArch64SveXml::new(2).unwrap();
+        let reg = |reg_id| {
+            crate::lldb::with_register_dyn(
+                &xml,
+                meta::SVE_GAPS_XML,
+                reg_id,
+                meta::aarch64_meta,
+                |reg| reg.map(|reg| (reg.name.to_string(), reg.offset, reg.dwarf)),
+            )
+        };
+
+        assert_eq!(reg(32), Some(("pc".into(), 256, Some(32))));
+        assert_eq!(reg(66), Some(("fpsr".into(), 268, None)));
+        assert_eq!(reg(68), Some(("z0".into(), 276, Some(96))));
+        assert_eq!(reg(117).unwrap().0, "vg");
+        assert_eq!(reg(118), None);
+
+        // `v0`-`v31` are not part of the "g" packet
+        let (name, offset, dwarf) = reg(34).unwrap();
+        assert_eq!(name, "v0");
+        assert!(offset >= 276 + 32 * 32);
+        assert_eq!(dwarf, Some(64));
+    }
+
+    #[test]
+    fn aarch64_sve_xml_read_range() {
+        let xml = AArch64SveXml::new(16).unwrap().with_sme(16).unwrap();
+        let expected = xml.to_string();
+
+        let mut buf = [0; 0x100];
+        let mut offset = 0;
+        let mut data = Vec::new();
+        loop {
+            let n = xml.read_range(offset, 0xff, &mut buf);
+            if n == 0 {
+                break;
+            }
+            data.extend_from_slice(&buf[..n]);
+            offset += n as u64;
+        }
+
+        assert_eq!(data, expected.as_bytes());
+    }
+
+    /// The "g" packet contains the core registers, `fpsr`, `fpcr`, and all the
+    /// SVE registers (regnums 0-33 and 66-117).
+    #[test]
+    fn aarch64_sve_reg_ids() {
+        let vq = 3;
+        let regs = AArch64SveRegs {
+            vg: vq as u64 * 2,
+            ..Default::default()
+        };
+
+        let mut serialized_data_len = 0;
+        regs.gdb_serialize(|b| {
+            if b.is_some() {
+                serialized_data_len += 1;
+            }
+        });
+
+        let mut sum_reg_sizes = 0;
+        for i in (0..=33).chain(66..=117) {
+            let (reg, size) = AArch64RegId::from_raw_id(i).unwrap();
+            sum_reg_sizes += match reg {
+                AArch64RegId::Z(_) => 16 * vq,
+                AArch64RegId::P(_) | AArch64RegId::Ffr => 2 * vq,
+                // `fpsr` and `fpcr` are 32-bit registers in the target XML
+                AArch64RegId::FPSR | AArch64RegId::FPCR => 4,
+                _ => size.unwrap().get(),
+            };
+        }
+
+        assert_eq!(serialized_data_len, sum_reg_sizes);
+
+        let mut xml = String::new();
+        let layout = AArch64SveXml::new(vq)
+            .unwrap()
+            .with_sme(1)
+            .unwrap()
+            .with_pauth()
+            .write_tdesc(TdescWriter::target_fmt(&mut xml))
+            .unwrap();
+        assert_eq!(layout.check_registers(&regs), Ok(()));
+
+        assert!(matches!(
+            AArch64RegId::from_raw_id(120),
+            Some((AArch64RegId::Za, None))
+        ));
+        assert!(matches!(
+            AArch64RegId::from_raw_id(119),
+            Some((AArch64RegId::Svcr, Some(_)))
+        ));
+        assert!(matches!(
+            AArch64RegId::from_raw_id(121),
+            Some((AArch64RegId::PauthDmask, Some(_)))
+        ));
+        assert!(matches!(
+            AArch64RegId::from_raw_id(123),
+            Some((AArch64RegId::TagCtl, Some(_)))
+        ));
+    }
+}
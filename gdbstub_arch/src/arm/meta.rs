@@ -0,0 +1,52 @@
+//! Register metadata for 32-bit ARM.
+
+use crate::meta::reg_index;
+use crate::meta::RegMeta;
+use crate::xml_util::XmlReg;
+use gdbstub::arch::lldb::Generic;
+
+/// The legacy FPA registers (regnums 16-24).
+macro_rules! legacy_fpa_xml {
+    () => {
+        concat!(
+            r#"<reg name="f0" bitsize="96" type="arm_fpa_ext" regnum="16"/>"#,
+            regs_xml!(96, "arm_fpa_ext": "f1", "f2", "f3", "f4", "f5", "f6", "f7"),
+            regs_xml!(32, "uint32": "fps"),
+        )
+    };
+}
+
+/// Registers in the ARMv4T "g" packet, including the (unused) legacy FPA
+/// registers.
+pub(crate) const ARMV4T_REGS_XML: &str = concat!(
+    regs_xml!(32, "uint32":
+        "r0", "r1", "r2", "r3", "r4", "r5", "r6", "r7", "r8", "r9", "r10",
+        "r11", "r12",
+    ),
+    regs_xml!(32, "data_ptr": "sp"),
+    regs_xml!(32, "uint32": "lr"),
+    regs_xml!(32, "code_ptr": "pc"),
+    legacy_fpa_xml!(),
+    regs_xml!(32, "uint32": "cpsr"),
+);
+
+/// The legacy FPA registers occupy regnums 16-24 in the ARMv7-A target
+/// description, but are not part of the "g" packet.
+pub(crate) const ARMV7A_GAPS_XML: &str = legacy_fpa_xml!();
+
+/// DWARF register numbers and generic registers, as per the "DWARF for the
+/// Arm Architecture" ABI document.
+pub(crate) fn arm_meta(reg: &XmlReg<'_>) -> RegMeta {
+    match reg.name {
+        "sp" => RegMeta::dwarf(13).generic(Generic::Sp).alt_name("r13"),
+        "lr" => RegMeta::dwarf(14).generic(Generic::Ra).alt_name("r14"),
+        "pc" => RegMeta::dwarf(15).generic(Generic::Pc).alt_name("r15"),
+        "cpsr" => RegMeta::default().generic(Generic::Flags),
+        name => match (reg_index(name, "r"), reg_index(name, "d")) {
+            (Some(n @ 0..=3), _) => RegMeta::dwarf(n).arg(n),
+            (Some(n @ 4..=12), _) => RegMeta::dwarf(n),
+            (_, Some(n)) => RegMeta::dwarf(256 + n),
+            _ => RegMeta::default(),
+        },
+    }
+}
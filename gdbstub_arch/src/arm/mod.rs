@@ -1,9 +1,12 @@
 //! Implementations for various ARM architectures.
 
+use crate::meta::RegTable;
 use gdbstub::arch::Arch;
 
 pub mod reg;
 
+mod meta;
+
 /// ARM-specific breakpoint kinds.
 ///
 /// Extracted from the GDB documentation at
@@ -33,6 +36,8 @@ impl gdbstub::arch::BreakpointKind for ArmBreakpointKind {
 /// Implements `Arch` for the ARMv4T architecture
 pub enum Armv4t {}
 
+static ARMV4T_REGS: RegTable = reg_table!(meta::ARMV4T_REGS_XML, "");
+
 impl Arch for Armv4t {
     type Usize = u32;
     type Registers = reg::ArmCoreRegs;
@@ -42,4 +47,47 @@ impl Arch for Armv4t {
     fn target_description_xml() -> Option<&'static str> {
         Some(r#"<target version="1.0"><architecture>armv4t</architecture></target>"#)
     }
+
+    impl_reg_meta!(ARMV4T_REGS, meta::arm_meta);
+}
+
+/// Implements `Arch` for the A-profile ARMv7 architecture, with VFPv3-D32 and
+/// Advanced SIMD (NEON) extensions.
+///
+/// This arch is also suitable for debugging code running in the AArch32
+/// execution state of ARMv8-A cores.
+///
+/// In addition to the standard `org.gnu.gdb.arm.core`, `org.gnu.gdb.arm.vfp`,
+/// and `org.gnu.gdb.arm.neon` features, the target XML advertises a custom
+/// `org.rust.gdb.arm.banked` feature, which exposes the banked copies of the
+/// core registers (e.g: `sp_svc`, `spsr_irq`, `elr_hyp`) to the GDB client.
+/// These registers are not a part of [`Armv7aRegs`](reg::Armv7aRegs), and are
+/// instead accessed via [`Armv7aRegId::Banked`](reg::id::Armv7aRegId::Banked).
+///
+/// *Note*: the NEON `q0`-`q15` (and VFP `s0`-`s31`) registers are
+/// pseudo-registers which the GDB client synthesizes from `d0`-`d31`.
+pub enum Armv7a {}
+
+const ARMV7A_XML: &str = concat!(
+    r#"<target version="1.0">"#,
+    "<architecture>arm</architecture>",
+    include_str!("core.xml"),  // feature "org.gnu.gdb.arm.core"
+    include_str!("vfpv3.xml"), // feature "org.gnu.gdb.arm.vfp"
+    r#"<feature name="org.gnu.gdb.arm.neon"/>"#,
+    include_str!("banked.xml"), // feature "org.rust.gdb.arm.banked"
+    "</target>",
+);
+static ARMV7A_REGS: RegTable = reg_table!(ARMV7A_XML, meta::ARMV7A_GAPS_XML);
+
+impl Arch for Armv7a {
+    type Usize = u32;
+    type Registers = reg::Armv7aRegs;
+    type RegId = reg::id::Armv7aRegId;
+    type BreakpointKind = ArmBreakpointKind;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(ARMV7A_XML)
+    }
+
+    impl_reg_meta!(ARMV7A_REGS, meta::arm_meta);
 }
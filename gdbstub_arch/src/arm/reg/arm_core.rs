@@ -4,6 +4,7 @@ use gdbstub::arch::Registers;
 ///
 /// Source: <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/arm/arm-core.xml>
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ArmCoreRegs {
     /// General purpose registers (R0-R12)
     pub r: [u32; 13],
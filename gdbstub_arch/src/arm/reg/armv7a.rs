@@ -0,0 +1,202 @@
+use core::convert::TryInto;
+use gdbstub::arch::Registers;
+
+/// 32-bit A-profile ARM core registers + VFPv3-D32 / Advanced SIMD registers.
+///
+/// Registers from the `org.gnu.gdb.arm.core` and `org.gnu.gdb.arm.vfp`
+/// [ARM Standard GDB Target Features](https://sourceware.org/gdb/current/onlinedocs/gdb/ARM-Features.html).
+///
+/// The single-precision `s0`-`s31` and quad-word NEON `q0`-`q15` registers
+/// are pseudo-registers synthesized by the GDB client from the underlying
+/// `d0`-`d31` registers, and are therefore not stored separately. See
+/// [`Armv7aRegs::s`] and [`Armv7aRegs::q`] for equivalent accessors.
+///
+/// Sources:
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/arm/arm-core.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/arm/arm-vfpv3.xml>
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Armv7aRegs {
+    /// General purpose registers (R0-R12)
+    pub r: [u32; 13],
+    /// Stack Pointer (R13)
+    pub sp: u32,
+    /// Link Register (R14)
+    pub lr: u32,
+    /// Program Counter (R15)
+    pub pc: u32,
+    /// Current Program Status Register (cpsr)
+    pub cpsr: u32,
+    /// Double-precision Floating Point / SIMD registers (D0-D31)
+    pub d: [u64; 32],
+    /// Floating-point Status and Control Register
+    pub fpscr: u32,
+}
+
+impl Armv7aRegs {
+    /// Return the value of single-precision register `Sn` (S0-S31), which
+    /// aliases the low / high half of `D(n/2)`.
+    ///
+    /// Returns `None` if `n` is out of range.
+    pub fn s(&self, n: usize) -> Option<u32> {
+        if n >= 32 {
+            return None;
+        }
+        let d = self.d[n / 2];
+        Some((d >> ((n % 2) * 32)) as u32)
+    }
+
+    /// Set the value of single-precision register `Sn` (S0-S31).
+    ///
+    /// Returns `None` if `n` is out of range.
+    pub fn set_s(&mut self, n: usize, val: u32) -> Option<()> {
+        if n >= 32 {
+            return None;
+        }
+        let d = &mut self.d[n / 2];
+        let shift = (n % 2) * 32;
+        *d = (*d & !(0xffff_ffff << shift)) | ((val as u64) << shift);
+        Some(())
+    }
+
+    /// Return the value of NEON quad-word register `Qn` (Q0-Q15), which
+    /// aliases `D(2n)` (low half) and `D(2n+1)` (high half).
+    ///
+    /// Returns `None` if `n` is out of range.
+    pub fn q(&self, n: usize) -> Option<u128> {
+        if n >= 16 {
+            return None;
+        }
+        Some(((self.d[n * 2 + 1] as u128) << 64) | self.d[n * 2] as u128)
+    }
+
+    /// Set the value of NEON quad-word register `Qn` (Q0-Q15).
+    ///
+    /// Returns `None` if `n` is out of range.
+    pub fn set_q(&mut self, n: usize, val: u128) -> Option<()> {
+        if n >= 16 {
+            return None;
+        }
+        self.d[n * 2] = val as u64;
+        self.d[n * 2 + 1] = (val >> 64) as u64;
+        Some(())
+    }
+}
+
+impl Registers for Armv7aRegs {
+    type ProgramCounter = u32;
+
+    fn pc(&self) -> Self::ProgramCounter {
+        self.pc
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        macro_rules! write_bytes {
+            ($var: expr) => {
+                for b in $var.to_le_bytes() {
+                    write_byte(Some(b))
+                }
+            };
+        }
+
+        for reg in self.r.iter() {
+            write_bytes!(reg);
+        }
+        write_bytes!(self.sp);
+        write_bytes!(self.lr);
+        write_bytes!(self.pc);
+        // regnums 16-24 (legacy FPA registers) are not described by the target
+        // XML, and therefore take up no space in the "g" packet.
+        write_bytes!(self.cpsr);
+        for reg in self.d.iter() {
+            write_bytes!(reg);
+        }
+        write_bytes!(self.fpscr);
+    }
+
+    fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        const D_OFF: usize = core::mem::size_of::<u32>() * 17;
+        const FPSCR_OFF: usize = D_OFF + core::mem::size_of::<u64>() * 32;
+        const END: usize = FPSCR_OFF + core::mem::size_of::<u32>();
+
+        if bytes.len() != END {
+            return Err(());
+        }
+
+        let mut regs = bytes[0..D_OFF]
+            .chunks_exact(core::mem::size_of::<u32>())
+            .map(|c| u32::from_le_bytes(c.try_into().unwrap()));
+
+        for reg in self.r.iter_mut() {
+            *reg = regs.next().ok_or(())?
+        }
+        self.sp = regs.next().ok_or(())?;
+        self.lr = regs.next().ok_or(())?;
+        self.pc = regs.next().ok_or(())?;
+        self.cpsr = regs.next().ok_or(())?;
+
+        let mut regs = bytes[D_OFF..FPSCR_OFF]
+            .chunks_exact(core::mem::size_of::<u64>())
+            .map(|c| u64::from_le_bytes(c.try_into().unwrap()));
+
+        for reg in self.d.iter_mut() {
+            *reg = regs.next().ok_or(())?
+        }
+
+        self.fpscr = u32::from_le_bytes(bytes[FPSCR_OFF..END].try_into().unwrap());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn armv7a_round_trip() {
+        let mut regs_before = Armv7aRegs {
+            r: [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13],
+            sp: 14,
+            lr: 15,
+            pc: 16,
+            cpsr: 17,
+            d: Default::default(),
+            fpscr: 18,
+        };
+        for (i, d) in regs_before.d.iter_mut().enumerate() {
+            *d = 0x0101_0101_0101_0101 * i as u64;
+        }
+
+        let mut data = vec![];
+
+        regs_before.gdb_serialize(|x| {
+            data.push(x.unwrap_or(b'x'));
+        });
+
+        assert_eq!(data.len(), 17 * 4 + 32 * 8 + 4);
+
+        let mut regs_after = Armv7aRegs::default();
+        regs_after.gdb_deserialize(&data).unwrap();
+
+        assert_eq!(regs_before, regs_after);
+    }
+
+    #[test]
+    fn armv7a_pseudo_regs() {
+        let mut regs = Armv7aRegs::default();
+
+        regs.set_q(1, 0x0123_4567_89ab_cdef_fedc_ba98_7654_3210)
+            .unwrap();
+        assert_eq!(regs.d[2], 0xfedc_ba98_7654_3210);
+        assert_eq!(regs.d[3], 0x0123_4567_89ab_cdef);
+        assert_eq!(regs.s(4), Some(0x7654_3210));
+        assert_eq!(regs.s(5), Some(0xfedc_ba98));
+
+        regs.set_s(5, 0xdead_beef).unwrap();
+        assert_eq!(regs.q(1), Some(0x0123_4567_89ab_cdef_dead_beef_7654_3210));
+
+        assert_eq!(regs.q(16), None);
+        assert_eq!(regs.s(32), None);
+    }
+}
@@ -3,6 +3,7 @@ use gdbstub::arch::RegId;
 
 /// 32-bit ARM core register identifier.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum ArmCoreRegId {
     /// General purpose registers (R0-R12)
@@ -33,6 +34,216 @@ impl RegId for ArmCoreRegId {
             25 => Self::Cpsr,
             _ => return None,
         };
-        Some((reg, Some(NonZeroUsize::new(4)?)))
+        // the legacy FPA registers are 96 bits wide
+        let size = match reg {
+            Self::Fpr(_) => 12,
+            _ => 4,
+        };
+        Some((reg, Some(NonZeroUsize::new(size)?)))
+    }
+}
+
+/// Banked ARM core register identifier.
+///
+/// These registers are only accessible via single register accesses (i.e: they
+/// are not included in the "g" packet).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum ArmBankedRegId {
+    /// User / System mode registers (R8-R14)
+    Usr(u8),
+    /// FIQ mode registers (R8-R14)
+    Fiq(u8),
+    /// FIQ mode Saved Program Status Register
+    SpsrFiq,
+    /// IRQ mode Stack Pointer
+    SpIrq,
+    /// IRQ mode Link Register
+    LrIrq,
+    /// IRQ mode Saved Program Status Register
+    SpsrIrq,
+    /// Supervisor mode Stack Pointer
+    SpSvc,
+    /// Supervisor mode Link Register
+    LrSvc,
+    /// Supervisor mode Saved Program Status Register
+    SpsrSvc,
+    /// Abort mode Stack Pointer
+    SpAbt,
+    /// Abort mode Link Register
+    LrAbt,
+    /// Abort mode Saved Program Status Register
+    SpsrAbt,
+    /// Undefined mode Stack Pointer
+    SpUnd,
+    /// Undefined mode Link Register
+    LrUnd,
+    /// Undefined mode Saved Program Status Register
+    SpsrUnd,
+    /// Monitor mode Stack Pointer (Security Extensions)
+    SpMon,
+    /// Monitor mode Link Register (Security Extensions)
+    LrMon,
+    /// Monitor mode Saved Program Status Register (Security Extensions)
+    SpsrMon,
+    /// Hyp mode Stack Pointer (Virtualization Extensions)
+    SpHyp,
+    /// Hyp mode Exception Link Register (Virtualization Extensions)
+    ElrHyp,
+    /// Hyp mode Saved Program Status Register (Virtualization Extensions)
+    SpsrHyp,
+}
+
+impl ArmBankedRegId {
+    fn from_u8(val: u8) -> Option<Self> {
+        use self::ArmBankedRegId::*;
+
+        let r = match val {
+            0..=6 => Usr(val + 8),
+            7..=13 => Fiq(val + 1),
+            14 => SpsrFiq,
+            15 => SpIrq,
+            16 => LrIrq,
+            17 => SpsrIrq,
+            18 => SpSvc,
+            19 => LrSvc,
+            20 => SpsrSvc,
+            21 => SpAbt,
+            22 => LrAbt,
+            23 => SpsrAbt,
+            24 => SpUnd,
+            25 => LrUnd,
+            26 => SpsrUnd,
+            27 => SpMon,
+            28 => LrMon,
+            29 => SpsrMon,
+            30 => SpHyp,
+            31 => ElrHyp,
+            32 => SpsrHyp,
+            _ => return None,
+        };
+        Some(r)
+    }
+}
+
+/// 32-bit A-profile ARM register identifier (core + VFPv3-D32 + banked
+/// registers).
+///
+/// The CPSR is register 25 (rather than 16) for compatibility with older GDB
+/// clients, which expected the legacy FPA registers (regnums 16-24) to be
+/// placed between the PC and the CPSR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Armv7aRegId {
+    /// General purpose registers (R0-R12)
+    Gpr(u8),
+    /// Stack Pointer (R13)
+    Sp,
+    /// Link Register (R14)
+    Lr,
+    /// Program Counter (R15)
+    Pc,
+    /// Current Program Status Register (cpsr)
+    Cpsr,
+    /// Double-precision Floating Point / SIMD registers (D0-D31)
+    D(u8),
+    /// Floating-point Status and Control Register
+    Fpscr,
+    /// Banked core registers
+    Banked(ArmBankedRegId),
+}
+
+impl RegId for Armv7aRegId {
+    fn from_raw_id(id: usize) -> Option<(Self, Option<NonZeroUsize>)> {
+        let (reg, size) = match id {
+            0..=12 => (Self::Gpr(id as u8), 4),
+            13 => (Self::Sp, 4),
+            14 => (Self::Lr, 4),
+            15 => (Self::Pc, 4),
+            25 => (Self::Cpsr, 4),
+            26..=57 => (Self::D((id - 26) as u8), 8),
+            58 => (Self::Fpscr, 4),
+            59..=91 => (Self::Banked(ArmBankedRegId::from_u8((id - 59) as u8)?), 4),
+            _ => return None,
+        };
+        Some((reg, Some(NonZeroUsize::new(size)?)))
+    }
+
+    fn to_raw_id(&self) -> Option<usize> {
+        let id = match *self {
+            Self::Gpr(n @ 0..=12) => n as usize,
+            Self::Sp => 13,
+            Self::Lr => 14,
+            Self::Pc => 15,
+            Self::Cpsr => 25,
+            Self::D(n @ 0..=31) => 26 + n as usize,
+            Self::Fpscr => 58,
+            Self::Banked(banked) => {
+                use self::ArmBankedRegId::*;
+
+                let off = match banked {
+                    Usr(n @ 8..=14) => n - 8,
+                    Fiq(n @ 8..=14) => n - 1,
+                    SpsrFiq => 14,
+                    SpIrq => 15,
+                    LrIrq => 16,
+                    SpsrIrq => 17,
+                    SpSvc => 18,
+                    LrSvc => 19,
+                    SpsrSvc => 20,
+                    SpAbt => 21,
+                    LrAbt => 22,
+                    SpsrAbt => 23,
+                    SpUnd => 24,
+                    LrUnd => 25,
+                    SpsrUnd => 26,
+                    SpMon => 27,
+                    LrMon => 28,
+                    SpsrMon => 29,
+                    SpHyp => 30,
+                    ElrHyp => 31,
+                    SpsrHyp => 32,
+                    _ => return None,
+                };
+                59 + off as usize
+            }
+            _ => return None,
+        };
+        Some(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gdbstub::arch::RegId;
+    use gdbstub::arch::Registers;
+
+    #[test]
+    fn test_armv7a() {
+        use super::Armv7aRegId;
+
+        // Only registers which are a part of the "g" packet (i.e: everything
+        // but the banked registers) count towards the serialized length.
+        let mut serialized_data_len = 0;
+        crate::arm::reg::Armv7aRegs::default().gdb_serialize(|b| {
+            if b.is_some() {
+                serialized_data_len += 1;
+            }
+        });
+
+        let mut sum_reg_sizes = 0;
+        for i in 0..=91 {
+            if let Some((reg, size)) = Armv7aRegId::from_raw_id(i) {
+                assert_eq!(reg.to_raw_id(), Some(i));
+                if !matches!(reg, Armv7aRegId::Banked(_)) {
+                    sum_reg_sizes += size.unwrap().get();
+                }
+            }
+        }
+
+        assert_eq!(serialized_data_len, sum_reg_sizes);
+        assert!(Armv7aRegId::from_raw_id(92).is_none());
     }
 }
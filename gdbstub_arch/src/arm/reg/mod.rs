@@ -4,5 +4,7 @@
 pub mod id;
 
 mod arm_core;
+mod armv7a;
 
 pub use arm_core::ArmCoreRegs;
+pub use armv7a::Armv7aRegs;
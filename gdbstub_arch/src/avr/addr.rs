@@ -0,0 +1,138 @@
+//! Synthetic 32-bit AVR address space used by the GDB client.
+//!
+//! AVR MCUs have separate program (flash), data (SRAM + memory-mapped I/O),
+//! and EEPROM address spaces, all of which start at address zero. As the GDB
+//! RSP assumes a single flat address space, the GDB client distinguishes
+//! between the different address spaces by offsetting addresses into a single
+//! 32-bit address space:
+//!
+//! | Address space | Range                   |
+//! | ------------- | ----------------------- |
+//! | Program       | `0x000000` - `0x7fffff` |
+//! | Data          | `0x800000` - `0x80ffff` |
+//! | EEPROM        | `0x810000` - `0x81ffff` |
+//!
+//! e.g: a `m800100,2` packet is a request to read 2 bytes from data address
+//! `0x100`, _not_ from program memory.
+//!
+//! The [avr-tdep.c] file in the GDB source contains the definitions of these
+//! offsets: see the `AVR_IMEM_START`, `AVR_SMEM_START`, and `AVR_EMEM_START`
+//! definitions.
+//!
+//! [avr-tdep.c]: https://github.com/bminor/binutils-gdb/blob/master/gdb/avr-tdep.c
+
+/// The address space of an address in the synthetic address space used by the
+/// AVR target.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AvrAddrSpace {
+    /// Program memory (flash).
+    Program,
+    /// Data memory (registers, I/O, and SRAM).
+    Data,
+    /// EEPROM.
+    Eeprom,
+}
+
+/// An address in the synthetic address space used by the AVR target.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AvrAddr(u32);
+
+impl AvrAddr {
+    /// Offset of the data address space.
+    pub const DATA_OFFSET: u32 = 0x80_0000;
+    /// Offset of the EEPROM address space.
+    pub const EEPROM_OFFSET: u32 = 0x81_0000;
+
+    const EEPROM_END: u32 = 0x82_0000;
+
+    /// Construct an `AvrAddr` from a raw 32-bit encoded address.
+    ///
+    /// Returns `None` if the address does not fall within any of the known
+    /// address spaces.
+    pub fn from_raw(raw: u32) -> Option<Self> {
+        if raw >= Self::EEPROM_END {
+            return None;
+        }
+        Some(AvrAddr(raw))
+    }
+
+    /// Provide the raw 32-bit encoding of this `AvrAddr`.
+    pub fn as_raw(self) -> u32 {
+        self.0
+    }
+
+    /// Construct an `AvrAddr` from its constituent parts.
+    ///
+    /// Returns `None` if the `offset` is out-of-range for the given address
+    /// space (i.e: greater than `0x7fffff` for program memory, or `0xffff` for
+    /// data memory and EEPROM).
+    pub fn new(space: AvrAddrSpace, offset: u32) -> Option<Self> {
+        let (base, size) = match space {
+            AvrAddrSpace::Program => (0, Self::DATA_OFFSET),
+            AvrAddrSpace::Data => (Self::DATA_OFFSET, Self::EEPROM_OFFSET - Self::DATA_OFFSET),
+            AvrAddrSpace::Eeprom => (Self::EEPROM_OFFSET, Self::EEPROM_END - Self::EEPROM_OFFSET),
+        };
+        if offset >= size {
+            return None;
+        }
+        Some(AvrAddr(base + offset))
+    }
+
+    /// Get the address space of this address.
+    pub fn space(self) -> AvrAddrSpace {
+        match self.0 {
+            0..=0x7f_ffff => AvrAddrSpace::Program,
+            0x80_0000..=0x80_ffff => AvrAddrSpace::Data,
+            _ => AvrAddrSpace::Eeprom,
+        }
+    }
+
+    /// Get the offset within the address space referenced by this address.
+    pub fn offset(self) -> u32 {
+        match self.space() {
+            AvrAddrSpace::Program => self.0,
+            AvrAddrSpace::Data => self.0 - Self::DATA_OFFSET,
+            AvrAddrSpace::Eeprom => self.0 - Self::EEPROM_OFFSET,
+        }
+    }
+}
+
+impl core::fmt::Display for AvrAddr {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let space_str = match self.space() {
+            AvrAddrSpace::Program => "Program",
+            AvrAddrSpace::Data => "Data",
+            AvrAddrSpace::Eeprom => "Eeprom",
+        };
+        write!(f, "{}({:#x})", space_str, self.offset())
+    }
+}
+
+impl core::fmt::Debug for AvrAddr {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "AvrAddr({self})")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn avr_addr_round_trip() {
+        for (space, offset, raw) in [
+            (AvrAddrSpace::Program, 0x1234, 0x1234),
+            (AvrAddrSpace::Data, 0x100, 0x80_0100),
+            (AvrAddrSpace::Eeprom, 0xffff, 0x81_ffff),
+        ] {
+            let addr = AvrAddr::new(space, offset).unwrap();
+            assert_eq!(addr.as_raw(), raw);
+            assert_eq!(AvrAddr::from_raw(raw), Some(addr));
+            assert_eq!(addr.space(), space);
+            assert_eq!(addr.offset(), offset);
+        }
+
+        assert_eq!(AvrAddr::new(AvrAddrSpace::Data, 0x1_0000), None);
+        assert_eq!(AvrAddr::from_raw(0x82_0000), None);
+    }
+}
@@ -0,0 +1,31 @@
+//! Register metadata for AVR.
+
+use crate::meta::reg_index;
+use crate::meta::RegMeta;
+use crate::xml_util::XmlReg;
+use gdbstub::arch::lldb::Generic;
+
+/// Registers in the AVR "g" packet.
+pub(crate) const AVR_REGS_XML: &str = concat!(
+    regs_xml!(8, "uint8":
+        "r0", "r1", "r2", "r3", "r4", "r5", "r6", "r7", "r8", "r9", "r10",
+        "r11", "r12", "r13", "r14", "r15", "r16", "r17", "r18", "r19", "r20",
+        "r21", "r22", "r23", "r24", "r25", "r26", "r27", "r28", "r29", "r30",
+        "r31", "sreg",
+    ),
+    regs_xml!(16, "data_ptr": "sp"),
+    regs_xml!(32, "code_ptr": "pc"),
+);
+
+/// DWARF register numbers and generic registers, matching avr-gcc.
+pub(crate) fn avr_meta(reg: &XmlReg<'_>) -> RegMeta {
+    match reg.name {
+        "sreg" => RegMeta::default().generic(Generic::Flags),
+        "sp" => RegMeta::dwarf(32).generic(Generic::Sp),
+        "pc" => RegMeta::dwarf(36).generic(Generic::Pc),
+        name => match reg_index(name, "r") {
+            Some(n) => RegMeta::dwarf(n),
+            None => RegMeta::default(),
+        },
+    }
+}
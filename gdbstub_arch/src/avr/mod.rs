@@ -0,0 +1,41 @@
+//! Implementations for the 8-bit [AVR](https://en.wikipedia.org/wiki/AVR_microcontrollers)
+//! family of MCUs.
+//!
+//! AVR is a Harvard architecture, with separate program (flash), data (SRAM),
+//! and EEPROM address spaces. The GDB client maps these address spaces into a
+//! single flat 32-bit address space, with data-space addresses offset by
+//! `0x800000`, and EEPROM addresses offset by `0x810000`. See the
+//! [`self::addr`] submodule for utilities to encode and decode these
+//! addresses.
+//!
+//! _Note:_ the GDB client represents the program counter as a _byte_ address
+//! in program memory (i.e: twice the hardware word address).
+
+use crate::meta::RegTable;
+use gdbstub::arch::Arch;
+
+pub mod addr;
+pub mod reg;
+
+mod meta;
+
+/// Implements `Arch` for 8-bit AVR MCUs.
+pub enum Avr {}
+
+static AVR_REGS: RegTable = reg_table!(meta::AVR_REGS_XML, "");
+
+impl Arch for Avr {
+    /// While AVR is an 8-bit architecture, the GDB client uses a 32-bit
+    /// address word to multiplex the program, data, and EEPROM address spaces
+    /// into a single address space.
+    type Usize = u32;
+    type Registers = reg::AvrCoreRegs;
+    type RegId = reg::id::AvrRegId;
+    type BreakpointKind = usize;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(r#"<target version="1.0"><architecture>avr</architecture></target>"#)
+    }
+
+    impl_reg_meta!(AVR_REGS, meta::avr_meta);
+}
@@ -0,0 +1,84 @@
+use core::convert::TryInto;
+use gdbstub::arch::Registers;
+
+/// AVR registers.
+///
+/// GDB does not provide a XML file for AVR.
+/// The best file to reference is [avr-tdep.c](https://github.com/bminor/binutils-gdb/blob/master/gdb/avr-tdep.c).
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AvrCoreRegs {
+    /// General Purpose Registers (R0-R31)
+    pub r: [u8; 32],
+    /// Status Register
+    pub sreg: u8,
+    /// Stack Pointer
+    pub sp: u16,
+    /// Program Counter, as a _byte_ address in program memory
+    pub pc: u32,
+}
+
+impl Registers for AvrCoreRegs {
+    type ProgramCounter = u32;
+
+    fn pc(&self) -> Self::ProgramCounter {
+        self.pc
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        for reg in self.r.iter() {
+            write_byte(Some(*reg));
+        }
+        write_byte(Some(self.sreg));
+        for b in self.sp.to_le_bytes() {
+            write_byte(Some(b));
+        }
+        for b in self.pc.to_le_bytes() {
+            write_byte(Some(b));
+        }
+    }
+
+    fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        if bytes.len() != 39 {
+            return Err(());
+        }
+
+        self.r.copy_from_slice(&bytes[0..32]);
+        self.sreg = bytes[32];
+        self.sp = u16::from_le_bytes(bytes[33..35].try_into().unwrap());
+        self.pc = u32::from_le_bytes(bytes[35..39].try_into().unwrap());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn avr_round_trip() {
+        let mut regs_before = AvrCoreRegs {
+            sreg: 0x82,
+            sp: 0x08ff,
+            pc: 0x1_0a2c,
+            ..Default::default()
+        };
+        for (i, reg) in regs_before.r.iter_mut().enumerate() {
+            *reg = i as u8;
+        }
+
+        let mut data = vec![];
+
+        regs_before.gdb_serialize(|x| {
+            data.push(x.unwrap_or(b'x'));
+        });
+
+        assert_eq!(data.len(), 39);
+
+        let mut regs_after = AvrCoreRegs::default();
+        regs_after.gdb_deserialize(&data).unwrap();
+
+        assert_eq!(regs_before, regs_after);
+    }
+}
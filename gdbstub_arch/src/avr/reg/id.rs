@@ -0,0 +1,75 @@
+use core::num::NonZeroUsize;
+use gdbstub::arch::RegId;
+
+/// AVR register identifier.
+///
+/// GDB does not provide a XML file for AVR.
+/// The best file to reference is [avr-tdep.c](https://github.com/bminor/binutils-gdb/blob/master/gdb/avr-tdep.c).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum AvrRegId {
+    /// General Purpose Registers (R0-R31)
+    Gpr(u8),
+    /// Status Register
+    Sreg,
+    /// Stack Pointer
+    Sp,
+    /// Program Counter
+    Pc,
+}
+
+impl RegId for AvrRegId {
+    fn from_raw_id(id: usize) -> Option<(Self, Option<NonZeroUsize>)> {
+        let (reg, size) = match id {
+            0..=31 => (AvrRegId::Gpr(id as u8), 1),
+            32 => (AvrRegId::Sreg, 1),
+            33 => (AvrRegId::Sp, 2),
+            34 => (AvrRegId::Pc, 4),
+            _ => return None,
+        };
+
+        Some((reg, Some(NonZeroUsize::new(size)?)))
+    }
+
+    fn to_raw_id(&self) -> Option<usize> {
+        let id = match *self {
+            AvrRegId::Gpr(n @ 0..=31) => n as usize,
+            AvrRegId::Sreg => 32,
+            AvrRegId::Sp => 33,
+            AvrRegId::Pc => 34,
+            _ => return None,
+        };
+        Some(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gdbstub::arch::RegId;
+    use gdbstub::arch::Registers;
+
+    #[test]
+    fn test_avr() {
+        // Obtain the data length written by `gdb_serialize` by passing a custom
+        // closure.
+        let mut serialized_data_len = 0;
+        let counter = |b: Option<u8>| {
+            if b.is_some() {
+                serialized_data_len += 1;
+            }
+        };
+        crate::avr::reg::AvrCoreRegs::default().gdb_serialize(counter);
+
+        // Accumulate register sizes returned by `from_raw_id`.
+        let mut i = 0;
+        let mut sum_reg_sizes = 0;
+        while let Some((reg, size)) = super::AvrRegId::from_raw_id(i) {
+            assert_eq!(reg.to_raw_id(), Some(i));
+            sum_reg_sizes += size.unwrap().get();
+            i += 1;
+        }
+
+        assert_eq!(serialized_data_len, sum_reg_sizes);
+    }
+}
@@ -0,0 +1,8 @@
+//! `Register` structs for AVR MCUs.
+
+/// `RegId` definitions for AVR MCUs.
+pub mod id;
+
+mod avr;
+
+pub use avr::AvrCoreRegs;
@@ -0,0 +1,129 @@
+//! Flat 64-bit address space for eBPF VMs with multiple memory regions.
+//!
+//! eBPF VMs typically keep the program bytecode, the stack, and the heap in
+//! separate memory regions, each of which is addressed starting from zero.
+//! As the GDB RSP assumes a single flat address space, these regions must be
+//! multiplexed into a single 64-bit address space.
+//!
+//! This module uses the same layout as [`solana_rbpf`], where the upper 32
+//! bits of an address select the memory region, and the lower 32 bits are the
+//! offset within that region:
+//!
+//! | Region  | Start           |
+//! | ------- | --------------- |
+//! | Program | `0x1_0000_0000` |
+//! | Stack   | `0x2_0000_0000` |
+//! | Heap    | `0x3_0000_0000` |
+//! | Input   | `0x4_0000_0000` |
+//!
+//! [`solana_rbpf`]: https://github.com/solana-labs/rbpf
+
+/// A memory region in the flat address space used by the eBPF target.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BpfRegion {
+    /// Program bytecode (and read-only data).
+    Program,
+    /// Stack.
+    Stack,
+    /// Heap.
+    Heap,
+    /// Program input (e.g: packet / account data).
+    Input,
+}
+
+/// An address in the flat address space used by the eBPF target.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BpfAddr(u64);
+
+impl BpfAddr {
+    const REGION_SHIFT: u32 = 32;
+    const OFFSET_MASK: u64 = (1u64 << Self::REGION_SHIFT) - 1;
+
+    /// Construct a `BpfAddr` from a raw 64-bit encoded address.
+    ///
+    /// Returns `None` if the address does not fall within any of the known
+    /// memory regions.
+    pub fn from_raw(raw: u64) -> Option<Self> {
+        match raw >> Self::REGION_SHIFT {
+            1..=4 => Some(BpfAddr(raw)),
+            _ => None,
+        }
+    }
+
+    /// Provide the raw 64-bit encoding of this `BpfAddr`.
+    pub fn as_raw(self) -> u64 {
+        self.0
+    }
+
+    /// Construct a `BpfAddr` from its constituent parts.
+    pub fn new(region: BpfRegion, offset: u32) -> Self {
+        let region_bits: u64 = match region {
+            BpfRegion::Program => 1,
+            BpfRegion::Stack => 2,
+            BpfRegion::Heap => 3,
+            BpfRegion::Input => 4,
+        };
+        BpfAddr((region_bits << Self::REGION_SHIFT) | u64::from(offset))
+    }
+
+    /// Get the memory region referenced by this address.
+    pub fn region(self) -> BpfRegion {
+        match self.0 >> Self::REGION_SHIFT {
+            1 => BpfRegion::Program,
+            2 => BpfRegion::Stack,
+            3 => BpfRegion::Heap,
+            4 => BpfRegion::Input,
+            // The raw bits are fully encapsulated and checked in `from_raw`,
+            // so this is unreachable in practice and should never panic.
+            #[allow(clippy::unreachable)]
+            _ => unreachable!(),
+        }
+    }
+
+    /// Get the offset within the memory region referenced by this address.
+    pub fn offset(self) -> u32 {
+        (self.0 & Self::OFFSET_MASK) as u32
+    }
+}
+
+impl core::fmt::Display for BpfAddr {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let region_str = match self.region() {
+            BpfRegion::Program => "Program",
+            BpfRegion::Stack => "Stack",
+            BpfRegion::Heap => "Heap",
+            BpfRegion::Input => "Input",
+        };
+        write!(f, "{}({:#x})", region_str, self.offset())
+    }
+}
+
+impl core::fmt::Debug for BpfAddr {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "BpfAddr({self})")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bpf_addr_round_trip() {
+        for (region, offset, raw) in [
+            (BpfRegion::Program, 0x120, 0x1_0000_0120),
+            (BpfRegion::Stack, 0xfff8, 0x2_0000_fff8),
+            (BpfRegion::Heap, 0, 0x3_0000_0000),
+            (BpfRegion::Input, u32::MAX, 0x4_ffff_ffff),
+        ] {
+            let addr = BpfAddr::new(region, offset);
+            assert_eq!(addr.as_raw(), raw);
+            assert_eq!(BpfAddr::from_raw(raw), Some(addr));
+            assert_eq!(addr.region(), region);
+            assert_eq!(addr.offset(), offset);
+        }
+
+        assert_eq!(BpfAddr::from_raw(0x1234), None);
+        assert_eq!(BpfAddr::from_raw(0x5_0000_0000), None);
+    }
+}
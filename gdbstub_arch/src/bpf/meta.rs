@@ -0,0 +1,28 @@
+//! Register metadata for eBPF.
+
+use crate::meta::reg_index;
+use crate::meta::RegMeta;
+use crate::xml_util::XmlReg;
+use gdbstub::arch::lldb::Generic;
+
+/// Registers in the eBPF "g" packet.
+pub(crate) const BPF_REGS_XML: &str = concat!(
+    regs_xml!(64, "int":
+        "r0", "r1", "r2", "r3", "r4", "r5", "r6", "r7", "r8", "r9",
+    ),
+    regs_xml!(64, "data_ptr": "r10"),
+    regs_xml!(64, "code_ptr": "pc"),
+);
+
+/// DWARF register numbers and generic registers, as per the eBPF calling
+/// convention (`r1`-`r5` hold arguments, and `r10` is the read-only frame
+/// pointer).
+pub(crate) fn bpf_meta(reg: &XmlReg<'_>) -> RegMeta {
+    match (reg.name, reg_index(reg.name, "r")) {
+        ("pc", _) => RegMeta::default().generic(Generic::Pc),
+        (_, Some(10)) => RegMeta::dwarf(10).generic(Generic::Fp).alt_name("fp"),
+        (_, Some(n @ 1..=5)) => RegMeta::dwarf(n).arg(n - 1),
+        (_, Some(n)) => RegMeta::dwarf(n),
+        _ => RegMeta::default(),
+    }
+}
@@ -0,0 +1,53 @@
+//! Implementations for the [eBPF](https://ebpf.io/) virtual machine
+//! architecture.
+//!
+//! eBPF VMs typically keep the program, stack, and heap in separate memory
+//! regions, which must be mapped into a single flat address space for use
+//! with the GDB RSP. See the [`self::addr`] submodule for utilities to encode
+//! and decode these addresses.
+
+use crate::meta::RegTable;
+use gdbstub::arch::Arch;
+
+pub mod addr;
+pub mod reg;
+
+mod meta;
+
+/// eBPF-specific breakpoint kinds.
+///
+/// All eBPF instructions occupy one or more 8-byte instruction slots, so the
+/// GDB client always requests breakpoints covering a single slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BpfBreakpointKind {
+    /// 8-byte instruction slot.
+    Slot,
+}
+
+impl gdbstub::arch::BreakpointKind for BpfBreakpointKind {
+    fn from_usize(kind: usize) -> Option<Self> {
+        let kind = match kind {
+            8 => BpfBreakpointKind::Slot,
+            _ => return None,
+        };
+        Some(kind)
+    }
+}
+
+/// Implements `Arch` for the eBPF virtual machine.
+pub enum Bpf {}
+
+static BPF_REGS: RegTable = reg_table!(meta::BPF_REGS_XML, "");
+
+impl Arch for Bpf {
+    type Usize = u64;
+    type Registers = reg::BpfRegs;
+    type RegId = reg::id::BpfRegId;
+    type BreakpointKind = BpfBreakpointKind;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(r#"<target version="1.0"><architecture>bpf</architecture></target>"#)
+    }
+
+    impl_reg_meta!(BPF_REGS, meta::bpf_meta);
+}
@@ -0,0 +1,80 @@
+use core::convert::TryInto;
+use gdbstub::arch::Registers;
+
+/// eBPF registers.
+///
+/// GDB does not provide a XML file for eBPF.
+/// The best file to reference is [bpf-tdep.c](https://github.com/bminor/binutils-gdb/blob/master/gdb/bpf-tdep.c).
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BpfRegs {
+    /// General Purpose Registers (R0-R10)
+    ///
+    /// `r0` holds return values, `r1`-`r5` hold function arguments, and
+    /// `r10` is the read-only frame pointer.
+    pub r: [u64; 11],
+    /// Program Counter
+    pub pc: u64,
+}
+
+impl Registers for BpfRegs {
+    type ProgramCounter = u64;
+
+    fn pc(&self) -> Self::ProgramCounter {
+        self.pc
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        for reg in self.r.iter().chain(core::iter::once(&self.pc)) {
+            for b in reg.to_le_bytes() {
+                write_byte(Some(b));
+            }
+        }
+    }
+
+    fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        if bytes.len() != 12 * 8 {
+            return Err(());
+        }
+
+        let mut regs = bytes
+            .chunks_exact(8)
+            .map(|c| u64::from_le_bytes(c.try_into().unwrap()));
+
+        for reg in self.r.iter_mut() {
+            *reg = regs.next().ok_or(())?
+        }
+        self.pc = regs.next().ok_or(())?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bpf_round_trip() {
+        let mut regs_before = BpfRegs {
+            pc: 0x1_0000_0120,
+            ..Default::default()
+        };
+        for (i, reg) in regs_before.r.iter_mut().enumerate() {
+            *reg = 0x1000 + i as u64;
+        }
+
+        let mut data = vec![];
+
+        regs_before.gdb_serialize(|x| {
+            data.push(x.unwrap_or(b'x'));
+        });
+
+        assert_eq!(data.len(), 96);
+
+        let mut regs_after = BpfRegs::default();
+        regs_after.gdb_deserialize(&data).unwrap();
+
+        assert_eq!(regs_before, regs_after);
+    }
+}
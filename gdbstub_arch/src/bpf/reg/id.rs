@@ -0,0 +1,67 @@
+use core::num::NonZeroUsize;
+use gdbstub::arch::RegId;
+
+/// eBPF register identifier.
+///
+/// GDB does not provide a XML file for eBPF.
+/// The best file to reference is [bpf-tdep.c](https://github.com/bminor/binutils-gdb/blob/master/gdb/bpf-tdep.c).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum BpfRegId {
+    /// General Purpose Registers (R0-R10)
+    Gpr(u8),
+    /// Program Counter
+    Pc,
+}
+
+impl RegId for BpfRegId {
+    fn from_raw_id(id: usize) -> Option<(Self, Option<NonZeroUsize>)> {
+        let reg = match id {
+            0..=10 => BpfRegId::Gpr(id as u8),
+            11 => BpfRegId::Pc,
+            _ => return None,
+        };
+
+        Some((reg, Some(NonZeroUsize::new(8)?)))
+    }
+
+    fn to_raw_id(&self) -> Option<usize> {
+        let id = match *self {
+            BpfRegId::Gpr(n @ 0..=10) => n as usize,
+            BpfRegId::Pc => 11,
+            _ => return None,
+        };
+        Some(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gdbstub::arch::RegId;
+    use gdbstub::arch::Registers;
+
+    #[test]
+    fn test_bpf() {
+        // Obtain the data length written by `gdb_serialize` by passing a custom
+        // closure.
+        let mut serialized_data_len = 0;
+        let counter = |b: Option<u8>| {
+            if b.is_some() {
+                serialized_data_len += 1;
+            }
+        };
+        crate::bpf::reg::BpfRegs::default().gdb_serialize(counter);
+
+        // Accumulate register sizes returned by `from_raw_id`.
+        let mut i = 0;
+        let mut sum_reg_sizes = 0;
+        while let Some((reg, size)) = super::BpfRegId::from_raw_id(i) {
+            assert_eq!(reg.to_raw_id(), Some(i));
+            sum_reg_sizes += size.unwrap().get();
+            i += 1;
+        }
+
+        assert_eq!(serialized_data_len, sum_reg_sizes);
+    }
+}
@@ -0,0 +1,8 @@
+//! `Register` structs for the eBPF virtual machine.
+
+/// `RegId` definitions for the eBPF virtual machine.
+pub mod id;
+
+mod bpf;
+
+pub use bpf::BpfRegs;
@@ -0,0 +1,716 @@
+//! Conformance checks for [`Arch`] implementations.
+//!
+//! Most bugs in `Arch` implementations boil down to a mismatch between the
+//! arch's [`Registers`] serialization code and the register layout described
+//! by its target description XML. [`Conformance`] cross-checks the two,
+//! verifying that:
+//!
+//! - [`Registers::gdb_serialize`] and [`Registers::gdb_deserialize`]
+//!   round-trip.
+//! - The size of the serialized "g" packet lines up with the register
+//!   bitsizes declared in the target description XML.
+//! - Every register size reported by [`RegId::from_raw_id`] agrees with the
+//!   XML, and every register number round-trips through
+//!   [`RegId::to_raw_id`].
+//! - [`Registers::pc`] returns the value found at the XML's PC offset.
+//!
+//! These checks are intended to be run as part of an arch's test suite, and
+//! work equally well with out-of-tree `Arch` implementations:
+//!
+//! ```rust,ignore
+//! use gdbstub_arch::conformance::Conformance;
+//!
+//! #[test]
+//! fn my_arch_conformance() {
+//!     Conformance::<MyArch>::new().check().unwrap();
+//! }
+//! ```
+//!
+//! Registers are laid out in the order they appear in the target description
+//! XML. If the arch's target description doesn't describe any registers, the
+//! layout reported by [`Arch::reg_info`] is used instead.
+//!
+//! This module is only available when the `conformance` feature is enabled.
+
+use crate::xml_util::RegScanner;
+use crate::xml_util::XmlReg;
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Range;
+use gdbstub::arch::lldb::Generic;
+use gdbstub::arch::Arch;
+use gdbstub::arch::RegId;
+use gdbstub::arch::Registers;
+use num_traits::ToPrimitive;
+
+/// Errors reported by [`Conformance::check`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConformanceError {
+    /// Neither the target description XML nor [`Arch::reg_info`] describe any
+    /// registers.
+    NoRegisters,
+    /// `gdb_deserialize` rejected a buffer of the same length as the output of
+    /// `gdb_serialize`.
+    DeserializeFailed,
+    /// Serializing the deserialized registers produced a different number of
+    /// bytes.
+    RoundTripLength {
+        /// Length of the originally serialized registers.
+        expected: usize,
+        /// Length of the re-serialized registers.
+        actual: usize,
+    },
+    /// Serializing the deserialized registers produced a different byte at
+    /// `offset`.
+    RoundTripMismatch {
+        /// Offset of the mismatched byte in the "g" packet.
+        offset: usize,
+    },
+    /// The serialized registers don't end on a register boundary in the
+    /// target description XML.
+    SizeMismatch {
+        /// Length of the serialized registers.
+        serialized: usize,
+        /// Total size of all registers described by the XML.
+        xml: usize,
+    },
+    /// The XML declares a register whose size isn't a whole number of bytes.
+    InvalidBitsize {
+        /// The register's number.
+        regnum: usize,
+    },
+    /// The XML declares multiple registers in the "g" packet with the same
+    /// register number.
+    DuplicateRegnum {
+        /// The duplicated register number.
+        regnum: usize,
+    },
+    /// The size reported by [`RegId::from_raw_id`] disagrees with the XML.
+    RegIdSizeMismatch {
+        /// The register's number.
+        regnum: usize,
+        /// Size reported by `from_raw_id`, in bytes.
+        reg_id: usize,
+        /// Size declared by the XML, in bytes.
+        xml: usize,
+    },
+    /// [`RegId::to_raw_id`] doesn't return the register number passed to
+    /// [`RegId::from_raw_id`].
+    RegIdRoundTrip {
+        /// The register's number.
+        regnum: usize,
+    },
+    /// [`RegId::from_raw_id`] doesn't describe a register in the "g" packet
+    /// (only checked when using [`Conformance::require_reg_ids`]).
+    MissingRegId {
+        /// The register's number.
+        regnum: usize,
+    },
+    /// The program counter couldn't be found in the "g" packet.
+    NoPc,
+    /// [`Registers::pc`] doesn't match the value at the XML's PC offset.
+    PcMismatch {
+        /// Offset of the program counter in the "g" packet.
+        offset: usize,
+    },
+}
+
+impl fmt::Display for ConformanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ConformanceError::NoRegisters => f.write_str("no registers described"),
+            ConformanceError::DeserializeFailed => {
+                f.write_str("gdb_deserialize rejected a serialized register buffer")
+            }
+            ConformanceError::RoundTripLength { expected, actual } => write!(
+                f,
+                "round-trip produced {} bytes (expected {})",
+                actual, expected
+            ),
+            ConformanceError::RoundTripMismatch { offset } => {
+                write!(f, "round-trip mismatch at offset {:#x}", offset)
+            }
+            ConformanceError::SizeMismatch { serialized, xml } => write!(
+                f,
+                "serialized size ({}) does not end on a register boundary (XML total: {})",
+                serialized, xml
+            ),
+            ConformanceError::InvalidBitsize { regnum } => {
+                write!(f, "register {} is not a whole number of bytes", regnum)
+            }
+            ConformanceError::DuplicateRegnum { regnum } => {
+                write!(f, "register {} is declared more than once", regnum)
+            }
+            ConformanceError::RegIdSizeMismatch {
+                regnum,
+                reg_id,
+                xml,
+            } => write!(
+                f,
+                "register {}: from_raw_id reports {} bytes, XML declares {}",
+                regnum, reg_id, xml
+            ),
+            ConformanceError::RegIdRoundTrip { regnum } => {
+                write!(
+                    f,
+                    "register {} does not round-trip through to_raw_id",
+                    regnum
+                )
+            }
+            ConformanceError::MissingRegId { regnum } => {
+                write!(f, "register {} has no RegId", regnum)
+            }
+            ConformanceError::NoPc => f.write_str("program counter not found"),
+            ConformanceError::PcMismatch { offset } => {
+                write!(f, "pc() does not match the value at offset {:#x}", offset)
+            }
+        }
+    }
+}
+
+/// Conformance checks for the arch `A`. See the [module level
+/// documentation](self) for details.
+pub struct Conformance<'a, A: Arch> {
+    regs: A::Registers,
+    xml: Option<&'a dyn fmt::Display>,
+    pc_regnum: Option<usize>,
+    keep: Vec<usize>,
+    derived: Vec<usize>,
+    aliases: Vec<(usize, usize)>,
+    reg_ids: bool,
+    _arch: PhantomData<A>,
+}
+
+impl<'a, A: Arch> Default for Conformance<'a, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, A: Arch> Conformance<'a, A> {
+    /// Check `A` against its [`Arch::target_description_xml`], using
+    /// `A::Registers::default()` as the register file.
+    pub fn new() -> Self {
+        Conformance {
+            regs: A::Registers::default(),
+            xml: None,
+            pc_regnum: None,
+            keep: Vec::new(),
+            derived: Vec::new(),
+            aliases: Vec::new(),
+            reg_ids: false,
+            _arch: PhantomData,
+        }
+    }
+
+    /// Use `regs` as the register file, instead of `A::Registers::default()`
+    /// (e.g: for arches whose register layout is configured at runtime).
+    pub fn regs(self, regs: A::Registers) -> Self {
+        Conformance { regs, ..self }
+    }
+
+    /// Check `A` against `xml`, instead of its
+    /// [`Arch::target_description_xml`] (e.g: for arches which generate their
+    /// target description at runtime).
+    pub fn xml(self, xml: &'a dyn fmt::Display) -> Self {
+        Conformance {
+            xml: Some(xml),
+            ..self
+        }
+    }
+
+    /// Specify the program counter's register number.
+    ///
+    /// By default, the program counter is the register reported as
+    /// [`Generic::Pc`] by [`Arch::reg_info`], or else the first register in
+    /// the XML with `type="code_ptr"` (or named `pc`).
+    pub fn pc_regnum(self, regnum: usize) -> Self {
+        Conformance {
+            pc_regnum: Some(regnum),
+            ..self
+        }
+    }
+
+    /// Preserve the value of register `regnum` from the register file when
+    /// filling the remaining registers with a test pattern.
+    ///
+    /// This is required for registers which must hold a specific value for
+    /// the register file to be deserialized (e.g: SVE's `vg` register, which
+    /// determines the size of the vector registers).
+    pub fn keep_reg(mut self, regnum: usize) -> Self {
+        self.keep.push(regnum);
+        self
+    }
+
+    /// Mark register `regnum` as derived from other registers (e.g: a view
+    /// into a subset of another register's bits).
+    ///
+    /// Derived registers are not expected to round-trip arbitrary values, but
+    /// must still serialize consistently.
+    pub fn derived_reg(mut self, regnum: usize) -> Self {
+        self.derived.push(regnum);
+        self
+    }
+
+    /// Mark register `regnum` as an alias of register `target` (e.g: a
+    /// windowed view of a physical register), such that both are filled with
+    /// the same test pattern.
+    pub fn alias_reg(mut self, regnum: usize, target: usize) -> Self {
+        self.aliases.push((regnum, target));
+        self
+    }
+
+    /// Require every register in the "g" packet to be described by
+    /// [`RegId::from_raw_id`] (including its size).
+    ///
+    /// By default, registers which `A::RegId` doesn't describe are skipped.
+    pub fn require_reg_ids(mut self) -> Self {
+        self.reg_ids = true;
+        self
+    }
+
+    /// Run all conformance checks, returning the first failure.
+    pub fn check(&self) -> Result<(), ConformanceError> {
+        let mut serialized = Vec::new();
+        self.regs.gdb_serialize(|b| serialized.push(b));
+
+        let slots = self.layout()?;
+        let g_len = serialized.len();
+
+        // the serialized registers must end on a register boundary
+        let mut boundaries = core::iter::once(0).chain(slots.iter().map(|s| s.range.end));
+        if !boundaries.any(|end| end == g_len) {
+            return Err(ConformanceError::SizeMismatch {
+                serialized: g_len,
+                xml: slots.last().map_or(0, |s| s.range.end),
+            });
+        }
+        let slots = &slots[..slots.iter().take_while(|s| s.range.end <= g_len).count()];
+        if let Some(w) = slots.windows(2).find(|w| w[0].regnum == w[1].regnum) {
+            return Err(ConformanceError::DuplicateRegnum {
+                regnum: w[0].regnum,
+            });
+        }
+
+        for slot in slots {
+            let (reg_id, size) = match A::RegId::from_raw_id(slot.regnum) {
+                Some(reg_id) => reg_id,
+                None if self.reg_ids => {
+                    return Err(ConformanceError::MissingRegId {
+                        regnum: slot.regnum,
+                    })
+                }
+                None => continue,
+            };
+            match size {
+                Some(size) if size.get() != slot.range.len() => {
+                    return Err(ConformanceError::RegIdSizeMismatch {
+                        regnum: slot.regnum,
+                        reg_id: size.get(),
+                        xml: slot.range.len(),
+                    });
+                }
+                None if self.reg_ids => {
+                    return Err(ConformanceError::MissingRegId {
+                        regnum: slot.regnum,
+                    })
+                }
+                _ => {}
+            }
+            match reg_id.to_raw_id() {
+                Some(regnum) if regnum != slot.regnum => {
+                    return Err(ConformanceError::RegIdRoundTrip {
+                        regnum: slot.regnum,
+                    })
+                }
+                None if self.reg_ids => {
+                    return Err(ConformanceError::RegIdRoundTrip {
+                        regnum: slot.regnum,
+                    })
+                }
+                _ => {}
+            }
+        }
+
+        // fill the registers with a recognizable pattern, leaving registers
+        // which are "missing" zeroed.
+        let mut bytes: Vec<u8> = serialized
+            .iter()
+            .enumerate()
+            .map(|(i, b)| b.map_or(0, |_| (i % 251) as u8 + 1))
+            .collect();
+        for slot in slots.iter().filter(|s| self.keep.contains(&s.regnum)) {
+            for i in slot.range.clone() {
+                bytes[i] = serialized[i].unwrap_or(0);
+            }
+        }
+
+        for &(alias, target) in &self.aliases {
+            let find = |regnum| slots.iter().find(|s| s.regnum == regnum);
+            if let (Some(alias), Some(target)) = (find(alias), find(target)) {
+                if alias.range.len() == target.range.len() {
+                    bytes.copy_within(target.range.clone(), alias.range.start);
+                }
+            }
+        }
+
+        let mut regs = self.regs.clone();
+        regs.gdb_deserialize(&bytes)
+            .map_err(|_| ConformanceError::DeserializeFailed)?;
+        let round_trip = serialize(&regs);
+        if round_trip.len() != g_len {
+            return Err(ConformanceError::RoundTripLength {
+                expected: g_len,
+                actual: round_trip.len(),
+            });
+        }
+
+        let derived = |i: usize| {
+            slots
+                .iter()
+                .any(|s| s.range.contains(&i) && self.derived.contains(&s.regnum))
+        };
+        // registers which serialize as "missing" can't be checked
+        let mismatch =
+            (0..g_len).find(|&i| matches!(round_trip[i], Some(b) if b != bytes[i]) && !derived(i));
+        if let Some(offset) = mismatch {
+            return Err(ConformanceError::RoundTripMismatch { offset });
+        }
+
+        // serializing the round-tripped registers must be stable
+        let round_trip_bytes: Vec<u8> = round_trip.iter().map(|b| b.unwrap_or(0)).collect();
+        let mut regs2 = self.regs.clone();
+        regs2
+            .gdb_deserialize(&round_trip_bytes)
+            .map_err(|_| ConformanceError::DeserializeFailed)?;
+        if let Some(offset) = (0..g_len).find(|&i| serialize(&regs2).get(i) != round_trip.get(i)) {
+            return Err(ConformanceError::RoundTripMismatch { offset });
+        }
+
+        let pc = self.pc_slot(slots)?;
+        let expected = regs.pc().to_u64();
+        let matches = |bytes: &[u8], le: bool| {
+            let fold = |acc: u64, b: &u8| acc << 8 | *b as u64;
+            let val = if le {
+                bytes.iter().rev().fold(0, fold)
+            } else {
+                bytes.iter().fold(0, fold)
+            };
+            Some(val) == expected
+        };
+        let n = core::mem::size_of::<A::Usize>().min(pc.len());
+        let pc_bytes = &bytes[pc.clone()];
+        if !matches(&pc_bytes[..n], true) && !matches(&pc_bytes[pc.len() - n..], false) {
+            return Err(ConformanceError::PcMismatch { offset: pc.start });
+        }
+
+        Ok(())
+    }
+
+    /// Lay out the registers described by the XML (or by [`Arch::reg_info`])
+    /// in register number order.
+    fn layout(&self) -> Result<Vec<Slot>, ConformanceError> {
+        let mut slots = Vec::new();
+        {
+            let mut scanner = RegScanner::new(|reg: &XmlReg<'_>, _| slots.push(Slot::new(reg)));
+            match (self.xml, A::target_description_xml()) {
+                (Some(xml), _) => scanner.scan(&xml),
+                (None, Some(xml)) => scanner.scan(&xml),
+                (None, None) => {}
+            }
+        }
+        if slots.is_empty() {
+            for info in (0..).map_while(A::reg_info) {
+                slots.push(Slot::new(&XmlReg {
+                    name: info.name,
+                    bitsize: info.bitsize,
+                    regnum: info.regnum,
+                    ty: None,
+                    group: Some(info.group),
+                }));
+            }
+        }
+        if slots.is_empty() {
+            return Err(ConformanceError::NoRegisters);
+        }
+        let mut slots = slots.into_iter().collect::<Result<Vec<_>, _>>()?;
+
+        // the "g" packet is laid out in register number order
+        slots.sort_by_key(|s| s.regnum);
+        let mut offset = 0;
+        for slot in slots.iter_mut() {
+            let size = slot.range.len();
+            slot.range = offset..offset + size;
+            offset += size;
+        }
+        Ok(slots)
+    }
+
+    fn pc_slot(&self, slots: &[Slot]) -> Result<Range<usize>, ConformanceError> {
+        let pc_regnum = self.pc_regnum.or_else(|| {
+            (0..)
+                .map_while(A::reg_info)
+                .find(|info| info.generic == Some(Generic::Pc))
+                .map(|info| info.regnum)
+        });
+        let pc = match pc_regnum {
+            Some(regnum) => slots.iter().find(|s| s.regnum == regnum),
+            None => slots
+                .iter()
+                .find(|s| s.code_ptr)
+                .or_else(|| slots.iter().find(|s| s.named_pc)),
+        };
+        pc.map(|s| s.range.clone()).ok_or(ConformanceError::NoPc)
+    }
+}
+
+/// A register's location in the "g" packet.
+struct Slot {
+    regnum: usize,
+    range: Range<usize>,
+    code_ptr: bool,
+    named_pc: bool,
+}
+
+impl Slot {
+    /// Create a (not yet laid out) slot for `reg`.
+    fn new(reg: &XmlReg<'_>) -> Result<Slot, ConformanceError> {
+        if !reg.bitsize.is_multiple_of(8) {
+            return Err(ConformanceError::InvalidBitsize { regnum: reg.regnum });
+        }
+        Ok(Slot {
+            regnum: reg.regnum,
+            range: 0..reg.bitsize / 8,
+            code_ptr: reg.ty == Some("code_ptr"),
+            named_pc: reg.name == "pc",
+        })
+    }
+}
+
+fn serialize(regs: &impl Registers) -> Vec<Option<u8>> {
+    let mut bytes = Vec::new();
+    regs.gdb_serialize(|b| bytes.push(b));
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! conformance_tests {
+        ($($name:ident: $arch:ty $(=> $opt:ident)?,)*) => {$(
+            #[test]
+            #[allow(deprecated)]
+            fn $name() {
+                Conformance::<$arch>::new()$(.$opt())?.check().unwrap();
+            }
+        )*};
+    }
+
+    /// An arch whose `gdb_deserialize` reads registers in the wrong order.
+    enum Broken {}
+
+    #[derive(Debug, Default, Clone, PartialEq)]
+    struct BrokenRegs {
+        r0: u32,
+        pc: u32,
+    }
+
+    impl Registers for BrokenRegs {
+        type ProgramCounter = u32;
+
+        fn pc(&self) -> u32 {
+            self.pc
+        }
+
+        fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+            for b in self.r0.to_le_bytes().iter().chain(&self.pc.to_le_bytes()) {
+                write_byte(Some(*b))
+            }
+        }
+
+        fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+            let mut regs = bytes
+                .chunks_exact(4)
+                .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]));
+            self.pc = regs.next().ok_or(())?;
+            self.r0 = regs.next().ok_or(())?;
+            Ok(())
+        }
+    }
+
+    impl Arch for Broken {
+        type Usize = u32;
+        type Registers = BrokenRegs;
+        type RegId = ();
+        type BreakpointKind = usize;
+
+        fn target_description_xml() -> Option<&'static str> {
+            Some(
+                r#"<target><feature name="broken"><reg name="r0" bitsize="32"/><reg name="pc" bitsize="32" type="code_ptr"/></feature></target>"#,
+            )
+        }
+    }
+
+    #[test]
+    fn broken_arch() {
+        assert_eq!(
+            Conformance::<Broken>::new().check(),
+            Err(ConformanceError::RoundTripMismatch { offset: 0 })
+        );
+        assert_eq!(
+            Conformance::<Broken>::new().require_reg_ids().check(),
+            Err(ConformanceError::MissingRegId { regnum: 0 })
+        );
+
+        let xml = r#"<reg name="r0" bitsize="48"/><reg name="pc" bitsize="32"/>"#;
+        assert_eq!(
+            Conformance::<Broken>::new().xml(&xml).check(),
+            Err(ConformanceError::SizeMismatch {
+                serialized: 8,
+                xml: 10
+            })
+        );
+    }
+
+    #[test]
+    fn riscv_fpu() {
+        use crate::riscv::Riscv32D;
+        use crate::riscv::Riscv32F;
+        use crate::riscv::Riscv64D;
+        use crate::riscv::Riscv64F;
+
+        // `fflags` and `frm` are views into `fcsr`, and must match its current
+        // bits for the test pattern written to `fcsr` to round-trip
+        fn riscv_fpu<A: Arch>() -> Conformance<'static, A> {
+            Conformance::new()
+                .keep_reg(66)
+                .keep_reg(67)
+                .derived_reg(66)
+                .derived_reg(67)
+        }
+        riscv_fpu::<Riscv32F>().check().unwrap();
+        riscv_fpu::<Riscv32D>().check().unwrap();
+        riscv_fpu::<Riscv64F>().check().unwrap();
+        riscv_fpu::<Riscv64D>().check().unwrap();
+    }
+
+    #[test]
+    fn runtime_xml() {
+        use crate::aarch64::reg::AArch64SveRegs;
+        use crate::aarch64::AArch64Sve;
+        use crate::aarch64::AArch64SveXml;
+        use crate::riscv::Riscv64D;
+        use crate::riscv::RiscvVectorXml;
+        use crate::xtensa::reg::XtensaCoreRegs;
+        use crate::xtensa::Xtensa;
+        use crate::xtensa::XtensaConfig;
+        use crate::xtensa::XtensaXml;
+
+        let xml = AArch64SveXml::new(2).unwrap();
+        let regs = AArch64SveRegs {
+            vg: 4,
+            ..Default::default()
+        };
+        // `vg` determines the size of the scalable registers
+        Conformance::<AArch64Sve>::new()
+            .xml(&xml)
+            .regs(regs)
+            .keep_reg(117)
+            .check()
+            .unwrap();
+
+        let xml = RiscvVectorXml::<Riscv64D>::new(128).unwrap();
+        Conformance::<Riscv64D>::new()
+            .xml(&xml)
+            .keep_reg(66)
+            .keep_reg(67)
+            .derived_reg(66)
+            .derived_reg(67)
+            .check()
+            .unwrap();
+
+        // with `windowbase` left at zero, `a0`-`a15` alias `ar0`-`ar15`
+        for config in [XtensaConfig::default(), XtensaConfig::new(16).unwrap()] {
+            let xml = XtensaXml::new(config);
+            let mut conformance = Conformance::<Xtensa>::new()
+                .xml(&xml)
+                .regs(XtensaCoreRegs::new(config))
+                .keep_reg(69)
+                .require_reg_ids();
+            for n in 0..16 {
+                conformance = conformance.alias_reg(72 + n, 1 + n);
+            }
+            conformance.check().unwrap();
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn dynamic() {
+        use crate::dynamic::DynamicArch;
+        use crate::dynamic::DynamicLayout;
+        use crate::dynamic::DynamicReg;
+        use crate::dynamic::DynamicRegs;
+
+        let mut layout = DynamicLayout::new().architecture("riscv:rv32");
+        layout.add_feature("org.gnu.gdb.riscv.cpu");
+        for i in 0..32 {
+            layout
+                .add_reg(DynamicReg::new(format!("x{}", i), 32))
+                .unwrap();
+        }
+        let pc = layout
+            .add_reg(DynamicReg::new("pc", 32).ty("code_ptr"))
+            .unwrap();
+        layout.set_pc_regnum(pc);
+
+        let mut regs = DynamicRegs::default();
+        layout.init_regs(&mut regs);
+        Conformance::<DynamicArch<u32>>::new()
+            .xml(&layout)
+            .regs(regs)
+            .check()
+            .unwrap();
+    }
+
+    conformance_tests! {
+        aarch64: crate::aarch64::AArch64,
+        aarch64_pauth: crate::aarch64::AArch64Pauth,
+        aarch64_pauth_mte: crate::aarch64::AArch64PauthMte,
+        armv4t: crate::arm::Armv4t,
+        armv7a: crate::arm::Armv7a,
+        avr: crate::avr::Avr,
+        bpf: crate::bpf::Bpf,
+        loongarch64: crate::loongarch::LoongArch64 => require_reg_ids,
+        loongarch64_lsx: crate::loongarch::LoongArch64Lsx => require_reg_ids,
+        loongarch64_lasx: crate::loongarch::LoongArch64Lasx => require_reg_ids,
+        m68k: crate::m68k::M68k => require_reg_ids,
+        m68k_fpu: crate::m68k::M68kFpu => require_reg_ids,
+        coldfire: crate::m68k::ColdFire => require_reg_ids,
+        coldfire_fpu: crate::m68k::ColdFireFpu => require_reg_ids,
+        mips: crate::mips::Mips,
+        mips_dsp: crate::mips::MipsWithDsp,
+        mips64: crate::mips::Mips64,
+        mips64_dsp: crate::mips::Mips64WithDsp,
+        msp430: crate::msp430::Msp430,
+        msp430x: crate::msp430::Msp430X,
+        ppc_altivec32: crate::ppc::PowerPcAltivec32 => require_reg_ids,
+        ppc64_vsx: crate::ppc::PowerPc64Vsx => require_reg_ids,
+        riscv32: crate::riscv::Riscv32,
+        riscv64: crate::riscv::Riscv64,
+        s390x: crate::s390x::S390x => require_reg_ids,
+        s390x_vx: crate::s390x::S390xVx => require_reg_ids,
+        wasm: crate::wasm::Wasm,
+        x86: crate::x86::X86_SSE,
+        x86_64: crate::x86::X86_64_SSE,
+        x86_64_avx: crate::x86::X86_64_AVX,
+        x86_64_avx512: crate::x86::X86_64_AVX512,
+        x86_64_linux_avx: crate::x86::X86_64_Linux_AVX,
+        x86_64_linux_avx512: crate::x86::X86_64_Linux_AVX512,
+        x86_64_avx_system: crate::x86::X86_64_AVX_System,
+        x86_64_avx512_system: crate::x86::X86_64_AVX512_System,
+    }
+}
@@ -0,0 +1,520 @@
+use super::DynamicRegs;
+use super::PcInfo;
+use crate::meta::RegMeta;
+use crate::target_xml::sealed::RegSource;
+use crate::target_xml::TargetXml;
+use crate::xml_util::XmlReg;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+use gdbstub::arch::lldb::Generic;
+use gdbstub::arch::tdesc::Reg;
+use gdbstub::arch::tdesc::RegLayout;
+use gdbstub::arch::tdesc::TdescError;
+use gdbstub::arch::tdesc::TdescWriter;
+
+/// A single register in a [`DynamicLayout`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DynamicReg {
+    name: String,
+    bitsize: usize,
+    regnum: Option<usize>,
+    ty: Option<String>,
+    group: Option<String>,
+    dwarf: Option<usize>,
+    in_g_packet: bool,
+}
+
+impl DynamicReg {
+    /// Create a new register with the specified name and size (in bits).
+    ///
+    /// By default, the register is assigned the register number following
+    /// the previously added register, and is included in the "g" packet.
+    pub fn new(name: impl Into<String>, bitsize: usize) -> Self {
+        DynamicReg {
+            name: name.into(),
+            bitsize,
+            regnum: None,
+            ty: None,
+            group: None,
+            dwarf: None,
+            in_g_packet: true,
+        }
+    }
+
+    /// Set an explicit register number.
+    pub fn regnum(self, regnum: usize) -> Self {
+        DynamicReg {
+            regnum: Some(regnum),
+            ..self
+        }
+    }
+
+    /// Set the register's type (e.g: `"int"`, `"code_ptr"`, `"ieee_double"`,
+    /// or the `id` of a type defined via [`DynamicLayout::add_types_xml`]).
+    pub fn ty(self, ty: impl Into<String>) -> Self {
+        DynamicReg {
+            ty: Some(ty.into()),
+            ..self
+        }
+    }
+
+    /// Set the register group the register belongs to (e.g: `"general"`,
+    /// `"float"`, `"vector"`).
+    pub fn group(self, group: impl Into<String>) -> Self {
+        DynamicReg {
+            group: Some(group.into()),
+            ..self
+        }
+    }
+
+    /// Set the register's DWARF register number, which is reported to LLDB
+    /// via [`TargetXml::lldb_register_info`].
+    pub fn dwarf(self, dwarf: usize) -> Self {
+        DynamicReg {
+            dwarf: Some(dwarf),
+            ..self
+        }
+    }
+
+    /// Exclude the register from the "g" packet, requiring the GDB client to
+    /// access it individually via `read_register` / `write_register`.
+    ///
+    /// Registers excluded from the "g" packet must have higher register
+    /// numbers than all registers included in the "g" packet.
+    pub fn exclude_from_g_packet(self) -> Self {
+        DynamicReg {
+            in_g_packet: false,
+            ..self
+        }
+    }
+
+    /// The register's name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The register's size (in bits).
+    pub fn bitsize(&self) -> usize {
+        self.bitsize
+    }
+
+    /// The register's size (in bytes).
+    pub fn size(&self) -> usize {
+        self.bitsize / 8
+    }
+
+    /// Whether the register is included in the "g" packet.
+    pub fn in_g_packet(&self) -> bool {
+        self.in_g_packet
+    }
+
+    fn tdesc_reg(&self, regnum: Option<usize>) -> Reg<'_> {
+        let mut reg = Reg::new(&self.name, self.bitsize);
+        if let Some(regnum) = regnum {
+            reg = reg.regnum(regnum);
+        }
+        if let Some(ty) = &self.ty {
+            reg = reg.ty(ty);
+        }
+        if let Some(group) = &self.group {
+            reg = reg.group(group);
+        }
+        if !self.in_g_packet {
+            reg = reg.exclude_from_g_packet();
+        }
+        reg
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Feature {
+    name: String,
+    types_xml: String,
+    // index of the feature's first register in `DynamicLayout::regs`
+    first_reg: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LaidOutReg {
+    reg: DynamicReg,
+    regnum: usize,
+    // offset in the "g" packet (if included)
+    offset: Option<usize>,
+}
+
+/// A runtime description of a target's registers, grouped into target
+/// description features.
+///
+/// Registers must be added in increasing register number order, and are laid
+/// out in the "g" packet in that order.
+///
+/// The `Display` implementation of `DynamicLayout` generates the target
+/// description XML, and its [`TargetXml`] implementation reports each
+/// register's DWARF register number (see [`DynamicReg::dwarf`]) and marks the
+/// register specified via [`set_pc_regnum`](Self::set_pc_regnum) as the
+/// program counter.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DynamicLayout {
+    architecture: Option<String>,
+    osabi: Option<String>,
+    big_endian: bool,
+    pc_regnum: Option<usize>,
+    features: Vec<Feature>,
+    regs: Vec<LaidOutReg>,
+    layout: RegLayout,
+}
+
+impl DynamicLayout {
+    /// Create a new, empty layout.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the `<architecture>` reported in the target description (e.g:
+    /// `"riscv:rv64"`, `"i386:x86-64"`).
+    pub fn architecture(self, architecture: impl Into<String>) -> Self {
+        DynamicLayout {
+            architecture: Some(architecture.into()),
+            ..self
+        }
+    }
+
+    /// Set the `<osabi>` reported in the target description (e.g:
+    /// `"GNU/Linux"`).
+    pub fn osabi(self, osabi: impl Into<String>) -> Self {
+        DynamicLayout {
+            osabi: Some(osabi.into()),
+            ..self
+        }
+    }
+
+    /// Mark the target as big-endian. This only affects the interpretation of
+    /// the program counter by [`DynamicRegs`].
+    pub fn big_endian(self) -> Self {
+        DynamicLayout {
+            big_endian: true,
+            ..self
+        }
+    }
+
+    /// Set the register number of the program counter.
+    pub fn set_pc_regnum(&mut self, regnum: usize) {
+        self.pc_regnum = Some(regnum)
+    }
+
+    /// Start a new target description feature. All subsequently added
+    /// registers and types will belong to this feature.
+    pub fn add_feature(&mut self, name: impl Into<String>) {
+        self.features.push(Feature {
+            name: name.into(),
+            types_xml: String::new(),
+            first_reg: self.regs.len(),
+        })
+    }
+
+    /// Add raw XML type definitions (e.g: `<vector>`, `<union>`, or `<flags>`
+    /// elements) to the current feature.
+    pub fn add_types_xml(&mut self, xml: &str) -> Result<(), TdescError> {
+        let feature = self.features.last_mut().ok_or(TdescError::NoFeature)?;
+        feature.types_xml.push_str(xml);
+        Ok(())
+    }
+
+    /// Add a register to the current feature, returning its register number.
+    ///
+    /// Registers are validated in the same way as by
+    /// [`TdescWriter::reg`](gdbstub::arch::tdesc::TdescWriter::reg).
+    pub fn add_reg(&mut self, reg: DynamicReg) -> Result<usize, TdescError> {
+        if self.features.is_empty() {
+            return Err(TdescError::NoFeature);
+        }
+
+        let offset = self.layout.g_packet_len();
+        let regnum = self.layout.add_reg(&reg.tdesc_reg(reg.regnum))?;
+        let offset = if reg.in_g_packet { Some(offset) } else { None };
+
+        self.regs.push(LaidOutReg {
+            reg,
+            regnum,
+            offset,
+        });
+        Ok(regnum)
+    }
+
+    /// Look up the register with the specified register number.
+    pub fn reg_info(&self, regnum: usize) -> Option<&DynamicReg> {
+        self.find(regnum).map(|r| &r.reg)
+    }
+
+    /// Look up the register number of the register with the specified name.
+    pub fn regnum_by_name(&self, name: &str) -> Option<usize> {
+        self.regs
+            .iter()
+            .find(|r| r.reg.name == name)
+            .map(|r| r.regnum)
+    }
+
+    /// Iterate over all registers in the layout, alongside their register
+    /// numbers.
+    pub fn regs(&self) -> impl Iterator<Item = (usize, &DynamicReg)> {
+        self.regs.iter().map(|r| (r.regnum, &r.reg))
+    }
+
+    /// The location of the register with the specified register number within
+    /// the "g" packet, or `None` if the register is not part of the "g"
+    /// packet.
+    pub fn g_packet_range(&self, regnum: usize) -> Option<Range<usize>> {
+        let r = self.find(regnum)?;
+        let offset = r.offset?;
+        Some(offset..offset + r.reg.size())
+    }
+
+    /// The total size (in bytes) of the "g" packet.
+    pub fn g_packet_len(&self) -> usize {
+        self.layout.g_packet_len()
+    }
+
+    /// Initialize `regs` to a zeroed buffer matching this layout.
+    pub fn init_regs<U>(&self, regs: &mut DynamicRegs<U>) {
+        regs.data.clear();
+        regs.data.resize(self.layout.g_packet_len(), 0);
+        regs.pc = self
+            .pc_regnum
+            .and_then(|regnum| self.g_packet_range(regnum))
+            .map(|range| PcInfo {
+                offset: range.start,
+                len: range.len(),
+                big_endian: self.big_endian,
+            });
+    }
+
+    /// Returns the (target-endian) contents of the specified register in
+    /// `regs`.
+    ///
+    /// Returns `None` if the register is not part of the "g" packet, or if
+    /// `regs` does not match this layout (e.g: if the GDB client sent a "G"
+    /// packet of the wrong size).
+    pub fn reg<'a, U>(&self, regs: &'a DynamicRegs<U>, regnum: usize) -> Option<&'a [u8]> {
+        if regs.data.len() != self.g_packet_len() {
+            return None;
+        }
+        regs.data.get(self.g_packet_range(regnum)?)
+    }
+
+    /// Returns the (target-endian) contents of the specified register in
+    /// `regs`.
+    ///
+    /// Returns `None` if the register is not part of the "g" packet, or if
+    /// `regs` does not match this layout.
+    pub fn reg_mut<'a, U>(
+        &self,
+        regs: &'a mut DynamicRegs<U>,
+        regnum: usize,
+    ) -> Option<&'a mut [u8]> {
+        if regs.data.len() != self.g_packet_len() {
+            return None;
+        }
+        regs.data.get_mut(self.g_packet_range(regnum)?)
+    }
+
+    fn find(&self, regnum: usize) -> Option<&LaidOutReg> {
+        let idx = self.regs.binary_search_by_key(&regnum, |r| r.regnum).ok()?;
+        Some(&self.regs[idx])
+    }
+}
+
+impl TargetXml for DynamicLayout {}
+
+impl RegSource for DynamicLayout {
+    fn reg_meta(&self, reg: &XmlReg<'_>) -> RegMeta {
+        let dwarf = self.find(reg.regnum).and_then(|r| r.reg.dwarf);
+        let generic = if self.pc_regnum == Some(reg.regnum) {
+            Some(Generic::Pc)
+        } else {
+            None
+        };
+        RegMeta {
+            dwarf,
+            generic,
+            ..RegMeta::default()
+        }
+    }
+}
+
+impl DynamicLayout {
+    fn write_tdesc(&self, mut xml: TdescWriter<'_, '_>) -> Result<(), TdescError> {
+        if let Some(architecture) = &self.architecture {
+            xml.architecture(architecture)?;
+        }
+        if let Some(osabi) = &self.osabi {
+            xml.osabi(osabi)?;
+        }
+
+        for (i, feature) in self.features.iter().enumerate() {
+            let end = match self.features.get(i + 1) {
+                Some(next) => next.first_reg,
+                None => self.regs.len(),
+            };
+
+            xml.feature(&feature.name)?;
+            xml.types_xml(&feature.types_xml)?;
+            for r in &self.regs[feature.first_reg..end] {
+                xml.reg(r.reg.tdesc_reg(Some(r.regnum)))?;
+            }
+        }
+
+        xml.finish().map(drop)
+    }
+}
+
+impl fmt::Display for DynamicLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_tdesc(TdescWriter::target_fmt(f))
+            .map_err(|_| fmt::Error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use gdbstub::arch::Registers;
+
+    fn layout() -> DynamicLayout {
+        let mut layout = DynamicLayout::new().architecture("riscv:rv64");
+        layout.add_feature("org.gnu.gdb.riscv.cpu");
+        for i in 0..32 {
+            layout
+                .add_reg(DynamicReg::new(format!("x{}", i), 64))
+                .unwrap();
+        }
+        let pc = layout
+            .add_reg(DynamicReg::new("pc", 64).ty("code_ptr"))
+            .unwrap();
+        layout.set_pc_regnum(pc);
+
+        layout.add_feature("org.gnu.gdb.riscv.fpu");
+        for i in 0..32 {
+            layout
+                .add_reg(DynamicReg::new(format!("f{}", i), 32).ty("ieee_single"))
+                .unwrap();
+        }
+        layout.add_feature("org.gnu.gdb.riscv.csr");
+        layout
+            .add_reg(
+                DynamicReg::new("mstatus", 64)
+                    .regnum(0x341)
+                    .exclude_from_g_packet(),
+            )
+            .unwrap();
+        layout
+    }
+
+    #[test]
+    fn dynamic_layout() {
+        let layout = layout();
+        assert_eq!(layout.g_packet_len(), 33 * 8 + 32 * 4);
+        assert_eq!(layout.g_packet_range(32), Some(0x100..0x108));
+        assert_eq!(layout.g_packet_range(33), Some(0x108..0x10c));
+        assert_eq!(layout.g_packet_range(0x341), None);
+        assert_eq!(layout.reg_info(0x341).unwrap().size(), 8);
+        assert_eq!(layout.regnum_by_name("f31"), Some(64));
+        assert!(layout.reg_info(65).is_none());
+
+        let mut n = 0;
+        layout.for_each_reg_info(|info| {
+            if info.name == "pc" {
+                assert_eq!(info.regnum, 32);
+                assert_eq!(info.generic, Some(Generic::Pc));
+            }
+            if info.name == "f0" {
+                assert_eq!(info.group, "float");
+            }
+            n += 1;
+        });
+        assert_eq!(n, 66);
+
+        let xml = layout.to_string();
+        assert!(xml.contains("<architecture>riscv:rv64</architecture>"));
+        assert!(xml.contains(
+            r#"<feature name="org.gnu.gdb.riscv.fpu"><reg name="f0" bitsize="32" regnum="33" type="ieee_single"/>"#
+        ));
+        assert!(
+            xml.ends_with(r#"<reg name="mstatus" bitsize="64" regnum="833"/></feature></target>"#)
+        );
+    }
+
+    #[test]
+    fn dynamic_layout_lldb_register_info() {
+        let layout = layout();
+        let reg = |reg_id| {
+            crate::lldb::with_register_dyn(
+                &layout,
+                "",
+                reg_id,
+                |reg| layout.reg_meta(reg),
+                |reg| reg.map(|reg| (reg.name.to_string(), reg.offset, reg.generic)),
+            )
+        };
+
+        assert_eq!(reg(32), Some(("pc".into(), 0x100, Some(Generic::Pc))));
+        assert_eq!(reg(33), Some(("f0".into(), 0x108, None)));
+        // registers excluded from the "g" packet are laid out after it
+        assert_eq!(
+            reg(0x341),
+            Some(("mstatus".into(), layout.g_packet_len(), None))
+        );
+        assert_eq!(reg(65), None);
+    }
+
+    #[test]
+    fn dynamic_layout_errors() {
+        let mut layout = DynamicLayout::new();
+        assert_eq!(
+            layout.add_reg(DynamicReg::new("r0", 32)),
+            Err(TdescError::NoFeature)
+        );
+        layout.add_feature("test");
+        assert_eq!(
+            layout.add_reg(DynamicReg::new("r0", 12)),
+            Err(TdescError::InvalidBitsize)
+        );
+        layout.add_reg(DynamicReg::new("r0", 32).regnum(4)).unwrap();
+        assert_eq!(
+            layout.add_reg(DynamicReg::new("r1", 32).regnum(4)),
+            Err(TdescError::NonMonotonicRegnum)
+        );
+        layout
+            .add_reg(DynamicReg::new("r1", 32).exclude_from_g_packet())
+            .unwrap();
+        assert_eq!(
+            layout.add_reg(DynamicReg::new("r2", 32)),
+            Err(TdescError::GPacketAfterExcluded)
+        );
+    }
+
+    #[test]
+    fn dynamic_regs() {
+        let layout = layout();
+        let mut regs = DynamicRegs::<u64>::default();
+        layout.init_regs(&mut regs);
+        layout
+            .reg_mut(&mut regs, 32)
+            .unwrap()
+            .copy_from_slice(&0x8000_1000u64.to_le_bytes());
+        assert_eq!(regs.pc(), 0x8000_1000);
+
+        let mut data = Vec::new();
+        regs.gdb_serialize(|b| data.push(b.unwrap()));
+        assert_eq!(data.len(), layout.g_packet_len());
+
+        // "G" packet
+        let mut regs_after = DynamicRegs::<u64>::default();
+        regs_after.gdb_deserialize(&data).unwrap();
+        assert_eq!(layout.reg(&regs_after, 32), layout.reg(&regs, 32));
+        regs_after.gdb_deserialize(&data[1..]).unwrap();
+        assert_eq!(layout.reg(&regs_after, 32), None);
+    }
+}
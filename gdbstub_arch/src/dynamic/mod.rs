@@ -0,0 +1,221 @@
+//! A runtime-configurable `Arch`, whose register layout and target
+//! description are determined by a value owned by the target.
+//!
+//! Every other `Arch` in this crate fixes its register layout at compile time.
+//! This doesn't work well for emulators which only decide which CPU model to
+//! emulate at startup (e.g: whether the CPU has an FPU, vector registers, or
+//! which RISC-V extensions it supports), as each CPU model would require its
+//! own `Target` instantiation.
+//!
+//! Instead, targets using [`DynamicArch`] construct a [`DynamicLayout`] at
+//! runtime, which describes the target's registers (and the target description
+//! features they belong to). The layout is then used to:
+//!
+//! - Generate the target description XML, by implementing the
+//!   [`TargetDescriptionXmlOverride`] IDET using [`TargetXml::read_range`]
+//!   (and, if required, returning `false` from
+//!   [`Target::use_target_description_xml`] for CPU models which shouldn't
+//!   report a target description).
+//! - Read / write individual registers in the [`DynamicRegs`] buffer passed to
+//!   `read_registers` / `write_registers` (see [`DynamicLayout::reg`]).
+//! - Determine the size of registers accessed by [`DynamicRegId`] in
+//!   `read_register` / `write_register` (see [`DynamicLayout::reg_info`]).
+//! - Report register info to LLDB, by implementing the
+//!   [`LldbRegisterInfoOverride`] IDET using
+//!   [`TargetXml::lldb_register_info`].
+//!
+//! _Note:_ This module requires the `alloc` feature.
+//!
+//! # Example
+//!
+//! ```
+//! use gdbstub_arch::dynamic::DynamicLayout;
+//! use gdbstub_arch::dynamic::DynamicReg;
+//! use gdbstub_arch::dynamic::DynamicRegs;
+//!
+//! let has_fpu = true; // determined at runtime
+//!
+//! let mut layout = DynamicLayout::new().architecture("riscv:rv32");
+//! layout.add_feature("org.gnu.gdb.riscv.cpu");
+//! for i in 0..32 {
+//!     layout.add_reg(DynamicReg::new(format!("x{}", i), 32)).unwrap();
+//! }
+//! layout.add_reg(DynamicReg::new("pc", 32).ty("code_ptr")).unwrap();
+//! layout.set_pc_regnum(32);
+//!
+//! if has_fpu {
+//!     layout.add_feature("org.gnu.gdb.riscv.fpu");
+//!     for i in 0..32 {
+//!         let reg = DynamicReg::new(format!("f{}", i), 32).ty("ieee_single");
+//!         layout.add_reg(reg).unwrap();
+//!     }
+//! }
+//!
+//! // in `SingleThreadBase::read_registers`
+//! let mut regs = DynamicRegs::<u32>::default();
+//! layout.init_regs(&mut regs);
+//! layout.reg_mut(&mut regs, 32).unwrap().copy_from_slice(&0x8000_0000u32.to_le_bytes());
+//! assert_eq!(gdbstub::arch::Registers::pc(&regs), 0x8000_0000);
+//! ```
+//!
+//! [`TargetDescriptionXmlOverride`]:
+//!     gdbstub::target::ext::target_description_xml_override::TargetDescriptionXmlOverride
+//! [`Target::use_target_description_xml`]:
+//!     gdbstub::target::Target::use_target_description_xml
+//! [`LldbRegisterInfoOverride`]:
+//!     gdbstub::target::ext::lldb_register_info_override::LldbRegisterInfoOverride
+//! [`TargetXml::read_range`]: crate::target_xml::TargetXml::read_range
+//! [`TargetXml::lldb_register_info`]: crate::target_xml::TargetXml::lldb_register_info
+
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::num::NonZeroUsize;
+use gdbstub::arch::Arch;
+use gdbstub::arch::RegId;
+use gdbstub::arch::Registers;
+use gdbstub::internal::BeBytes;
+use gdbstub::internal::LeBytes;
+use num_traits::FromPrimitive;
+use num_traits::PrimInt;
+use num_traits::Unsigned;
+
+mod layout;
+
+pub use layout::DynamicLayout;
+pub use layout::DynamicReg;
+
+/// Implements `Arch` for targets whose register layout is only known at
+/// runtime.
+///
+/// The pointer size is set based on the `<U>` type (e.g: `u32` or `u64`).
+///
+/// This arch does _not_ include a built-in `target.xml` implementation.
+/// Targets should implement [`TargetDescriptionXmlOverride`] using a
+/// [`DynamicLayout`].
+///
+/// [`TargetDescriptionXmlOverride`]:
+///     gdbstub::target::ext::target_description_xml_override::TargetDescriptionXmlOverride
+pub enum DynamicArch<U> {
+    #[doc(hidden)]
+    _Marker(core::marker::PhantomData<U>),
+}
+
+impl<U> Arch for DynamicArch<U>
+where
+    U: Debug + Default + Clone + Eq + FromPrimitive + PrimInt + Unsigned + BeBytes + LeBytes,
+{
+    type Usize = U;
+    type Registers = DynamicRegs<U>;
+    type RegId = DynamicRegId;
+    type BreakpointKind = usize;
+}
+
+/// Register identifier for [`DynamicArch`], containing the raw GDB register
+/// number.
+///
+/// As register sizes are only known at runtime, `gdbstub` does not validate
+/// the size of the register data returned by `read_register`. Use
+/// [`DynamicLayout::reg_info`] to look up the register's size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DynamicRegId(pub usize);
+
+impl RegId for DynamicRegId {
+    fn from_raw_id(id: usize) -> Option<(Self, Option<NonZeroUsize>)> {
+        Some((DynamicRegId(id), None))
+    }
+
+    fn to_raw_id(&self) -> Option<usize> {
+        Some(self.0)
+    }
+}
+
+/// The "g" packet register buffer for [`DynamicArch`].
+///
+/// `DynamicRegs` stores the raw contents of the "g" packet, and must be
+/// interpreted via the [`DynamicLayout`] which describes it:
+///
+/// - When reading registers, call [`DynamicLayout::init_regs`] to size the
+///   buffer, and then fill in each register via [`DynamicLayout::reg_mut`].
+/// - When writing registers, read each register via [`DynamicLayout::reg`]
+///   (which returns `None` if the GDB client sent a buffer of the wrong
+///   size).
+///
+/// [`Registers::pc`] only returns a meaningful value once the buffer has been
+/// initialized via [`DynamicLayout::init_regs`], and returns zero otherwise.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DynamicRegs<U> {
+    data: Vec<u8>,
+    pc: Option<PcInfo>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _usize: core::marker::PhantomData<U>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct PcInfo {
+    offset: usize,
+    len: usize,
+    big_endian: bool,
+}
+
+impl<U> DynamicRegs<U> {
+    /// The raw contents of the "g" packet.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// The raw contents of the "g" packet.
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        &mut self.data
+    }
+}
+
+impl<U> Registers for DynamicRegs<U>
+where
+    U: Debug + Default + Clone + Eq + PrimInt + BeBytes + LeBytes,
+{
+    type ProgramCounter = U;
+
+    fn pc(&self) -> Self::ProgramCounter {
+        let pc = self.pc.and_then(|pc| {
+            let bytes = self.data.get(pc.offset..pc.offset + pc.len)?;
+            if pc.big_endian {
+                U::from_be_bytes(bytes)
+            } else {
+                U::from_le_bytes(bytes)
+            }
+        });
+        pc.unwrap_or_else(U::zero)
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        for b in self.data.iter() {
+            write_byte(Some(*b))
+        }
+    }
+
+    fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        self.data.clear();
+        self.data.extend_from_slice(bytes);
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_round_trip() {
+        let mut regs = DynamicRegs::<u32>::default();
+        regs.gdb_deserialize(&[1, 2, 3, 4, 5]).unwrap();
+
+        let json = serde_json::to_string(&regs).unwrap();
+        assert_eq!(
+            serde_json::from_str::<DynamicRegs<u32>>(&json).unwrap(),
+            regs
+        );
+    }
+}
@@ -16,6 +16,15 @@
 //! particularly feasible to write detailed tests for each architecture! If you
 //! spot a bug in any of the implementations, please file an issue / open a PR!
 //!
+//! The `Registers` serialization code of every arch in this crate is
+//! cross-checked against its target description XML using the
+//! [`conformance`](https://docs.rs/gdbstub_arch/latest/gdbstub_arch/conformance/) module.
+//! Out-of-tree `Arch` implementations can run the same checks by enabling the
+//! `conformance` feature.
+//!
+//! Enabling the `serde` feature implements `Serialize` / `Deserialize` for all
+//! register structs and `RegId` enums (e.g: for snapshotting vCPU state).
+//!
 //! # What's with `RegIdImpl`?
 //!
 //! Supporting the `Target::read/write_register` API required introducing a new
@@ -53,14 +62,37 @@
 #![cfg_attr(not(test), no_std)]
 #![deny(missing_docs)]
 
+#[cfg(any(test, feature = "alloc"))]
+extern crate alloc;
+
+#[macro_use]
+mod meta;
+
+mod lldb;
+
 pub mod aarch64;
 pub mod arm;
+pub mod avr;
+pub mod bpf;
+#[cfg(any(test, feature = "conformance"))]
+pub mod conformance;
+#[cfg(feature = "alloc")]
+pub mod dynamic;
+pub mod loongarch;
+pub mod m68k;
 pub mod mips;
 pub mod msp430;
 pub mod ppc;
 pub mod riscv;
+pub mod s390x;
+pub mod target_xml;
 pub mod wasm;
 pub mod x86;
+pub mod xtensa;
+
+#[cfg(feature = "serde")]
+mod serde_array;
+mod xml_util;
 
 // used as part of intra-doc link
 #[allow(unused_imports)]
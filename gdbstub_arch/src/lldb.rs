@@ -0,0 +1,395 @@
+//! Helpers for generating LLDB `qRegisterInfo` responses from target
+//! description XML.
+//!
+//! LLDB queries registers one-by-one by register number, stopping at the
+//! first register number the target doesn't describe. The register's name,
+//! size, type, and group are taken from the target description XML, and its
+//! offset in the "g" packet is computed by summing the sizes of all registers
+//! with lower register numbers (matching GDB's "g" packet layout).
+//!
+//! Information which isn't part of the target description is provided by each
+//! arch via a [`RegMeta`] callback (see [`crate::meta`]).
+
+use crate::meta::RegKind;
+use crate::meta::RegMeta;
+use crate::meta::RegTable;
+use crate::xml_util::RegScanner;
+use crate::xml_util::XmlReg;
+use core::cell::Cell;
+use core::fmt;
+use gdbstub::arch::lldb::Encoding;
+use gdbstub::arch::lldb::Format;
+use gdbstub::arch::lldb::Register;
+use gdbstub::arch::lldb::RegisterInfo;
+use gdbstub::target::ext::lldb_register_info_override::Callback;
+use gdbstub::target::ext::lldb_register_info_override::CallbackToken;
+
+const GPR_SET: &str = "General Purpose Registers";
+const FPR_SET: &str = "Floating Point Registers";
+const VEC_SET: &str = "Vector Registers";
+const SYS_SET: &str = "System Registers";
+
+/// Implementation of `Arch::lldb_register_info` for arches with a static
+/// target description.
+///
+/// The table's `xml` describes the registers in the "g" packet (alongside any
+/// registers only accessible via "p" / "P" packets), and its `gaps` describe
+/// registers which aren't part of the "g" packet, but are required to fill in
+/// gaps in the register numbers described by `xml`.
+pub(crate) fn register_info(
+    table: &RegTable,
+    reg_id: usize,
+    meta: impl Fn(&XmlReg<'_>) -> RegMeta,
+) -> Option<RegisterInfo<'static>> {
+    let mut layout = Layout::new(reg_id);
+    let mut found = None;
+    for (idx, span) in table.spans.iter().enumerate() {
+        let in_g = table.in_xml(idx);
+        if layout.add(span.regnum as usize, span.bitsize as usize, in_g) {
+            found = Some((idx, in_g));
+        }
+    }
+
+    let reg = found.and_then(|(idx, in_g)| {
+        let reg = table.reg(idx)?;
+        Some(layout.register(&reg, in_g, meta))
+    });
+
+    Some(match reg {
+        Some(reg) => RegisterInfo::Register(reg),
+        None => RegisterInfo::Done,
+    })
+}
+
+/// Write the LLDB register info for `reg_id`, as described by runtime
+/// generated target description `xml`.
+///
+/// `gaps` and `meta` are interpreted the same as in [`register_info`].
+pub(crate) fn register_info_dyn<'a>(
+    xml: &impl fmt::Display,
+    gaps: &str,
+    reg_id: usize,
+    meta: impl Fn(&XmlReg<'_>) -> RegMeta,
+    reg_info: Callback<'a>,
+) -> CallbackToken<'a> {
+    with_register_dyn(xml, gaps, reg_id, meta, |reg| match reg {
+        Some(reg) => reg_info.write(reg),
+        None => reg_info.done(),
+    })
+}
+
+/// Invoke `f` with the LLDB register info for `reg_id` (or `None`, if there
+/// is no such register).
+///
+/// See [`register_info_dyn`].
+pub(crate) fn with_register_dyn<R>(
+    xml: &impl fmt::Display,
+    gaps: &str,
+    reg_id: usize,
+    meta: impl Fn(&XmlReg<'_>) -> RegMeta,
+    f: impl FnOnce(Option<Register<'_>>) -> R,
+) -> R {
+    // runtime generated XML is scanned twice: once to compute the register
+    // layout, and once more to capture the requested register's tag.
+    let mut found = None;
+    let layout = scan(xml, gaps, reg_id, |range, in_g| found = Some((range, in_g)));
+
+    let (range, in_g) = match found {
+        Some(found) => found,
+        None => return f(None),
+    };
+
+    let mut buf = [0; 256];
+    let tag = if in_g {
+        let len = crate::xml_util::read_range(xml, range.start as u64, range.len(), &mut buf);
+        core::str::from_utf8(&buf[..len]).ok()
+    } else {
+        gaps.get(range)
+    };
+
+    match tag.and_then(|tag| XmlReg::parse(tag, reg_id)) {
+        Some(reg) => f(Some(layout.register(&reg, in_g, meta))),
+        None => f(None),
+    }
+}
+
+struct Layout {
+    /// The requested register.
+    reg_id: usize,
+    /// Offset of the requested register within the "g" packet.
+    offset: usize,
+    /// Total size of all registers described by the target XML.
+    total: usize,
+    /// Offset of the requested register within the gap registers.
+    gap_offset: usize,
+}
+
+/// Scan `xml` and `gaps` for the register `reg_id`, invoking `on_found` with
+/// the range of its tag contents, and whether it was found in `xml`.
+fn scan(
+    xml: &impl fmt::Display,
+    gaps: &str,
+    reg_id: usize,
+    mut on_found: impl FnMut(core::ops::Range<usize>, bool),
+) -> Layout {
+    let mut layout = Layout::new(reg_id);
+    let in_g = Cell::new(true);
+
+    let mut scanner = RegScanner::new(|reg: &XmlReg<'_>, range| {
+        if layout.add(reg.regnum, reg.bitsize, in_g.get()) {
+            on_found(range, in_g.get())
+        }
+    });
+    scanner.scan(xml);
+    in_g.set(false);
+    scanner.scan(&gaps);
+
+    layout
+}
+
+impl Layout {
+    fn new(reg_id: usize) -> Layout {
+        Layout {
+            reg_id,
+            offset: 0,
+            total: 0,
+            gap_offset: 0,
+        }
+    }
+
+    /// Account for the register `regnum` (found in `xml` if `in_g`, or else in
+    /// `gaps`), returning whether it's the requested register.
+    fn add(&mut self, regnum: usize, bitsize: usize, in_g: bool) -> bool {
+        let size = bitsize / 8;
+        if in_g {
+            self.total += size;
+        }
+        if regnum < self.reg_id {
+            if in_g {
+                self.offset += size;
+            } else {
+                self.gap_offset += size;
+            }
+        }
+        regnum == self.reg_id
+    }
+
+    fn register<'a>(
+        &self,
+        reg: &XmlReg<'a>,
+        in_g: bool,
+        meta: impl Fn(&XmlReg<'_>) -> RegMeta,
+    ) -> Register<'a> {
+        let offset = if in_g {
+            self.offset
+        } else {
+            self.total + self.gap_offset
+        };
+        register(reg, offset, meta(reg))
+    }
+}
+
+/// Construct the LLDB register info for `reg`, located at `offset` in the "g"
+/// packet.
+///
+/// The register's encoding, display format, and register set are derived from
+/// its XML `type` and `group` attributes.
+pub(crate) fn register<'a>(reg: &XmlReg<'a>, offset: usize, meta: RegMeta) -> Register<'a> {
+    let (encoding, format, default_set) = match RegKind::of(reg, &meta) {
+        RegKind::Float => (Encoding::IEEE754, Format::Float, FPR_SET),
+        RegKind::Vector => (Encoding::Vector, Format::VectorUInt8, VEC_SET),
+        RegKind::General => (Encoding::Uint, Format::Hex, GPR_SET),
+    };
+
+    let set = match reg.group {
+        None | Some("general") => default_set,
+        Some("float") => FPR_SET,
+        Some("vector") => VEC_SET,
+        Some("system") => SYS_SET,
+        Some(group) => group,
+    };
+
+    Register {
+        name: reg.name,
+        alt_name: meta.alt_name,
+        bitsize: reg.bitsize,
+        offset,
+        encoding,
+        format,
+        set,
+        gcc: meta.dwarf,
+        dwarf: meta.dwarf,
+        generic: meta.generic,
+        container_regs: None,
+        invalidate_regs: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gdbstub::arch::lldb::Generic;
+    use gdbstub::arch::Arch;
+
+    struct Info {
+        name: String,
+        offset: usize,
+        bitsize: usize,
+        dwarf: Option<usize>,
+        set: String,
+        is_pc: bool,
+        is_vector: bool,
+    }
+
+    fn info(reg: Option<Register<'_>>) -> Option<Info> {
+        reg.map(|reg| Info {
+            name: reg.name.to_string(),
+            offset: reg.offset,
+            bitsize: reg.bitsize,
+            dwarf: reg.dwarf,
+            set: reg.set.to_string(),
+            is_pc: matches!(reg.generic, Some(Generic::Pc)),
+            is_vector: matches!(reg.encoding, Encoding::Vector),
+        })
+    }
+
+    fn static_info<A: Arch>(reg_id: usize) -> Option<Info> {
+        match A::lldb_register_info(reg_id) {
+            Some(RegisterInfo::Register(reg)) => info(Some(reg)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn x86_64() {
+        use crate::x86::X86_64_AVX;
+        use crate::x86::X86_64_SSE;
+
+        for rip in [static_info::<X86_64_SSE>(16), static_info::<X86_64_AVX>(16)] {
+            let rip = rip.unwrap();
+            assert_eq!(rip.name, "rip");
+            assert_eq!(rip.offset, 128);
+            assert_eq!(rip.dwarf, Some(16));
+            assert!(rip.is_pc);
+        }
+
+        let xmm0 = static_info::<X86_64_AVX>(40).unwrap();
+        assert_eq!(xmm0.name, "xmm0");
+        assert_eq!(xmm0.offset, 128 + 8 + 4 + 6 * 4 + 8 * 10 + 8 * 4);
+        assert_eq!(xmm0.dwarf, Some(17));
+        assert!(xmm0.is_vector);
+
+        let fctrl = static_info::<X86_64_AVX>(32).unwrap();
+        assert_eq!(fctrl.set, FPR_SET);
+
+        // xmm0-15 + mxcsr, then the AVX registers end at regnum 74
+        assert!(static_info::<X86_64_SSE>(57).is_none());
+        assert!(static_info::<X86_64_AVX>(74).is_some());
+        assert!(matches!(
+            X86_64_AVX::lldb_register_info(75),
+            Some(RegisterInfo::Done)
+        ));
+    }
+
+    #[test]
+    fn x86() {
+        use crate::x86::X86_SSE;
+
+        let eip = static_info::<X86_SSE>(8).unwrap();
+        assert_eq!(eip.name, "eip");
+        assert_eq!(eip.offset, 32);
+        assert!(eip.is_pc);
+
+        let xmm0 = static_info::<X86_SSE>(32).unwrap();
+        assert_eq!(xmm0.name, "xmm0");
+        assert_eq!(xmm0.offset, 16 * 4 + 8 * 10 + 8 * 4);
+        assert_eq!(xmm0.dwarf, Some(21));
+        assert!(static_info::<X86_SSE>(40).is_some());
+        assert!(static_info::<X86_SSE>(41).is_none());
+    }
+
+    #[test]
+    fn aarch64() {
+        use crate::aarch64::AArch64;
+
+        let pc = static_info::<AArch64>(32).unwrap();
+        assert_eq!(pc.name, "pc");
+        assert_eq!(pc.offset, 256);
+        assert_eq!(pc.dwarf, Some(32));
+        assert!(pc.is_pc);
+
+        let v0 = static_info::<AArch64>(34).unwrap();
+        assert_eq!(v0.name, "v0");
+        assert_eq!(v0.offset, 33 * 8 + 4);
+        assert_eq!(v0.dwarf, Some(64));
+        assert!(v0.is_vector);
+
+        assert!(static_info::<AArch64>(67).is_some());
+        assert!(static_info::<AArch64>(68).is_none());
+    }
+
+    #[test]
+    fn riscv() {
+        use crate::riscv::Riscv32;
+        use crate::riscv::Riscv64D;
+
+        let pc = static_info::<Riscv32>(32).unwrap();
+        assert_eq!(pc.offset, 32 * 4);
+        assert!(pc.is_pc);
+        assert!(static_info::<Riscv32>(33).is_none());
+
+        let sp = static_info::<Riscv64D>(2).unwrap();
+        assert_eq!(sp.name, "sp");
+        assert_eq!(sp.dwarf, Some(2));
+
+        let ft0 = static_info::<Riscv64D>(33).unwrap();
+        assert_eq!(ft0.name, "ft0");
+        assert_eq!(ft0.offset, 33 * 8);
+        assert_eq!(ft0.dwarf, Some(32));
+        assert_eq!(ft0.set, FPR_SET);
+
+        // `ustatus` fills the gap between the FPRs and the FP CSRs
+        assert_eq!(static_info::<Riscv64D>(65).unwrap().name, "ustatus");
+        let fflags = static_info::<Riscv64D>(66).unwrap();
+        assert_eq!(fflags.offset, 33 * 8 + 32 * 8);
+        assert!(static_info::<Riscv64D>(69).is_none());
+    }
+
+    #[test]
+    fn arm() {
+        use crate::arm::Armv4t;
+        use crate::arm::Armv7a;
+
+        let cpsr = static_info::<Armv4t>(25).unwrap();
+        assert_eq!(cpsr.name, "cpsr");
+        assert_eq!(cpsr.offset, 16 * 4 + 8 * 12 + 4);
+
+        let cpsr = static_info::<Armv7a>(25).unwrap();
+        assert_eq!(cpsr.name, "cpsr");
+        assert_eq!(cpsr.offset, 16 * 4);
+        let d0 = static_info::<Armv7a>(26).unwrap();
+        assert_eq!(d0.offset, 17 * 4);
+        assert_eq!(d0.dwarf, Some(256));
+
+        // the legacy FPA registers aren't part of the ARMv7-A "g" packet
+        let f0 = static_info::<Armv7a>(16).unwrap();
+        assert_eq!(f0.name, "f0");
+        assert!(f0.offset >= 17 * 4 + 32 * 8 + 4);
+    }
+
+    #[test]
+    fn mips() {
+        use crate::mips::Mips64WithDsp;
+
+        let pc = static_info::<Mips64WithDsp>(37).unwrap();
+        assert_eq!(pc.name, "pc");
+        assert_eq!(pc.offset, 37 * 8);
+        assert!(pc.is_pc);
+
+        let dspctl = static_info::<Mips64WithDsp>(78).unwrap();
+        assert_eq!(dspctl.name, "dspctl");
+        assert_eq!(dspctl.bitsize, 32);
+        let restart = static_info::<Mips64WithDsp>(79).unwrap();
+        assert_eq!(restart.offset, 78 * 8 + 4);
+    }
+}
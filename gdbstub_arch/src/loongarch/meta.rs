@@ -0,0 +1,37 @@
+//! Register metadata for LoongArch.
+
+use crate::meta::reg_index;
+use crate::meta::RegMeta;
+use crate::xml_util::XmlReg;
+use gdbstub::arch::lldb::Generic;
+
+/// DWARF register numbers and generic registers, as per the LoongArch ELF
+/// psABI.
+///
+/// GPRs are described using their architectural names (`r0`-`r31`), with the
+/// ABI names used as alternate names.
+pub(crate) fn loongarch_meta(reg: &XmlReg<'_>) -> RegMeta {
+    const ABI_NAMES: [&str; 32] = [
+        "zero", "ra", "tp", "sp", "a0", "a1", "a2", "a3", "a4", "a5", "a6", "a7", "t0", "t1", "t2",
+        "t3", "t4", "t5", "t6", "t7", "t8", "r21", "fp", "s0", "s1", "s2", "s3", "s4", "s5", "s6",
+        "s7", "s8",
+    ];
+
+    match reg.name {
+        "pc" => RegMeta::default().generic(Generic::Pc),
+        name => match (reg_index(name, "r"), reg_index(name, "f")) {
+            (Some(n @ 0..=31), _) => {
+                let meta = RegMeta::dwarf(n).alt_name(ABI_NAMES[n]);
+                match n {
+                    1 => meta.generic(Generic::Ra),
+                    3 => meta.generic(Generic::Sp),
+                    22 => meta.generic(Generic::Fp),
+                    4..=11 => meta.arg(n - 4),
+                    _ => meta,
+                }
+            }
+            (_, Some(n @ 0..=31)) => RegMeta::dwarf(32 + n).float(),
+            _ => RegMeta::default(),
+        },
+    }
+}
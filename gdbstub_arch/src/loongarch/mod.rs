@@ -0,0 +1,123 @@
+//! Implementations for the [LoongArch](https://www.loongson.cn/) architecture.
+//!
+//! All LoongArch arches include the `org.gnu.gdb.loongarch.fpu` feature, as
+//! the GDB client requires it to be present.
+
+use crate::meta::RegTable;
+use gdbstub::arch::Arch;
+
+pub mod reg;
+
+mod meta;
+
+/// LoongArch-specific breakpoint kinds.
+///
+/// The GDB client implements software breakpoints by replacing the target
+/// instruction with a 4-byte `break` instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoongArchBreakpointKind {
+    /// 32-bit `break` instruction.
+    Break,
+}
+
+impl LoongArchBreakpointKind {
+    /// The `break 5` instruction GDB uses for software breakpoints.
+    pub const BREAK_INSN: u32 = 0x002a_0005;
+
+    /// Returns the (little-endian) instruction bytes used to implement a
+    /// software breakpoint of this kind.
+    pub fn insn_bytes(&self) -> [u8; 4] {
+        match self {
+            LoongArchBreakpointKind::Break => Self::BREAK_INSN.to_le_bytes(),
+        }
+    }
+}
+
+impl gdbstub::arch::BreakpointKind for LoongArchBreakpointKind {
+    fn from_usize(kind: usize) -> Option<Self> {
+        let kind = match kind {
+            4 => LoongArchBreakpointKind::Break,
+            _ => return None,
+        };
+        Some(kind)
+    }
+}
+
+macro_rules! loongarch64_target_xml {
+    ($($feature:literal),*) => {
+        concat!(
+            r#"<?xml version="1.0"?>"#,
+            r#"<!DOCTYPE target SYSTEM "gdb-target.dtd">"#,
+            r#"<target version="1.0">"#,
+            "<architecture>loongarch64</architecture>",
+            include_str!("base64.xml"), // feature "org.gnu.gdb.loongarch.base"
+            include_str!("fpu.xml"),    // feature "org.gnu.gdb.loongarch.fpu"
+            $(include_str!($feature),)*
+            "</target>",
+        )
+    };
+}
+
+/// Implements `Arch` for 64-bit LoongArch + floating point.
+pub enum LoongArch64 {}
+
+/// Implements `Arch` for 64-bit LoongArch + floating point + LSX (128-bit
+/// SIMD) extensions.
+pub enum LoongArch64Lsx {}
+
+/// Implements `Arch` for 64-bit LoongArch + floating point + LSX (128-bit
+/// SIMD) and LASX (256-bit SIMD) extensions.
+pub enum LoongArch64Lasx {}
+
+const LOONGARCH64_XML: &str = loongarch64_target_xml!();
+static LOONGARCH64_REGS: RegTable = reg_table!(LOONGARCH64_XML, "");
+
+impl Arch for LoongArch64 {
+    type Usize = u64;
+    type Registers = reg::LoongArch64CoreRegs;
+    type RegId = reg::id::LoongArch64RegId;
+    type BreakpointKind = LoongArchBreakpointKind;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(LOONGARCH64_XML)
+    }
+
+    impl_reg_meta!(LOONGARCH64_REGS, meta::loongarch_meta);
+}
+
+const LOONGARCH64_LSX_XML: &str = loongarch64_target_xml!(
+    "lsx.xml" // feature "org.gnu.gdb.loongarch.lsx"
+);
+static LOONGARCH64_LSX_REGS: RegTable = reg_table!(LOONGARCH64_LSX_XML, "");
+
+impl Arch for LoongArch64Lsx {
+    type Usize = u64;
+    type Registers = reg::LoongArch64LsxRegs;
+    type RegId = reg::id::LoongArch64RegId;
+    type BreakpointKind = LoongArchBreakpointKind;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(LOONGARCH64_LSX_XML)
+    }
+
+    impl_reg_meta!(LOONGARCH64_LSX_REGS, meta::loongarch_meta);
+}
+
+const LOONGARCH64_LASX_XML: &str = loongarch64_target_xml!(
+    "lsx.xml",  // feature "org.gnu.gdb.loongarch.lsx"
+    "lasx.xml"  // feature "org.gnu.gdb.loongarch.lasx"
+);
+static LOONGARCH64_LASX_REGS: RegTable = reg_table!(LOONGARCH64_LASX_XML, "");
+
+impl Arch for LoongArch64Lasx {
+    type Usize = u64;
+    type Registers = reg::LoongArch64LasxRegs;
+    type RegId = reg::id::LoongArch64RegId;
+    type BreakpointKind = LoongArchBreakpointKind;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(LOONGARCH64_LASX_XML)
+    }
+
+    impl_reg_meta!(LOONGARCH64_LASX_REGS, meta::loongarch_meta);
+}
@@ -0,0 +1,78 @@
+use core::num::NonZeroUsize;
+use gdbstub::arch::RegId;
+
+/// 64-bit LoongArch register identifier.
+///
+/// Sources:
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/loongarch/base64.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/loongarch/fpu.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/loongarch/lsx.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/loongarch/lasx.xml>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum LoongArch64RegId {
+    /// General purpose registers (r0-r31)
+    Gpr(u8),
+    /// Original value of `a0` on syscall entry
+    OrigA0,
+    /// Program counter
+    Pc,
+    /// Bad virtual address
+    Badv,
+    /// Floating-point registers (f0-f31)
+    Fpr(u8),
+    /// Floating-point condition flags (fcc0-fcc7)
+    Fcc(u8),
+    /// Floating-point control and status register
+    Fcsr,
+    /// LSX vector registers (vr0-vr31)
+    Vr(u8),
+    /// LASX vector registers (xr0-xr31)
+    Xr(u8),
+}
+
+impl RegId for LoongArch64RegId {
+    fn from_raw_id(id: usize) -> Option<(Self, Option<NonZeroUsize>)> {
+        let (reg, size) = match id {
+            0..=31 => (Self::Gpr(id as u8), 8),
+            32 => (Self::OrigA0, 8),
+            33 => (Self::Pc, 8),
+            34 => (Self::Badv, 8),
+            35..=66 => (Self::Fpr((id - 35) as u8), 8),
+            67..=74 => (Self::Fcc((id - 67) as u8), 1),
+            75 => (Self::Fcsr, 4),
+            76..=107 => (Self::Vr((id - 76) as u8), 16),
+            108..=139 => (Self::Xr((id - 108) as u8), 32),
+            _ => return None,
+        };
+
+        Some((reg, Some(NonZeroUsize::new(size)?)))
+    }
+
+    fn to_raw_id(&self) -> Option<usize> {
+        let id = match *self {
+            Self::Gpr(n @ 0..=31) => n as usize,
+            Self::OrigA0 => 32,
+            Self::Pc => 33,
+            Self::Badv => 34,
+            Self::Fpr(n @ 0..=31) => 35 + n as usize,
+            Self::Fcc(n @ 0..=7) => 67 + n as usize,
+            Self::Fcsr => 75,
+            Self::Vr(n @ 0..=31) => 76 + n as usize,
+            Self::Xr(n @ 0..=31) => 108 + n as usize,
+            _ => return None,
+        };
+        Some(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gdbstub::arch::RegId;
+
+    #[test]
+    fn test_loongarch64_lasx_out_of_range() {
+        assert!(super::LoongArch64RegId::from_raw_id(140).is_none());
+    }
+}
@@ -0,0 +1,273 @@
+use core::convert::TryInto;
+use gdbstub::arch::Registers;
+
+/// Size (in bytes) of the serialized `LoongArch64CoreRegs`.
+const CORE_LEN: usize = 35 * 8 + 32 * 8 + 8 + 4;
+
+/// 64-bit LoongArch core registers + floating-point registers.
+///
+/// Registers from the `org.gnu.gdb.loongarch.base` and
+/// `org.gnu.gdb.loongarch.fpu` [LoongArch Standard GDB Target Features](https://sourceware.org/gdb/onlinedocs/gdb/LoongArch-Features.html).
+///
+/// Sources:
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/loongarch/base64.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/loongarch/fpu.xml>
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LoongArch64CoreRegs {
+    /// General purpose registers (r0-r31)
+    pub r: [u64; 32],
+    /// Original value of `a0` (`r4`) on syscall entry
+    pub orig_a0: u64,
+    /// Program counter
+    pub pc: u64,
+    /// Bad virtual address
+    pub badv: u64,
+    /// Floating-point registers (f0-f31), as raw bits
+    pub f: [u64; 32],
+    /// Floating-point condition flags (fcc0-fcc7)
+    pub fcc: [u8; 8],
+    /// Floating-point control and status register
+    pub fcsr: u32,
+}
+
+impl Registers for LoongArch64CoreRegs {
+    type ProgramCounter = u64;
+
+    fn pc(&self) -> Self::ProgramCounter {
+        self.pc
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        macro_rules! write_bytes {
+            ($bytes:expr) => {
+                for b in $bytes {
+                    write_byte(Some(*b))
+                }
+            };
+        }
+
+        for reg in &self.r {
+            write_bytes!(&reg.to_le_bytes());
+        }
+        write_bytes!(&self.orig_a0.to_le_bytes());
+        write_bytes!(&self.pc.to_le_bytes());
+        write_bytes!(&self.badv.to_le_bytes());
+
+        for reg in &self.f {
+            write_bytes!(&reg.to_le_bytes());
+        }
+        write_bytes!(&self.fcc);
+        write_bytes!(&self.fcsr.to_le_bytes());
+    }
+
+    fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        if bytes.len() != CORE_LEN {
+            return Err(());
+        }
+
+        let mut regs = bytes[0..0x118]
+            .chunks_exact(8)
+            .map(|x| u64::from_le_bytes(x.try_into().unwrap()));
+
+        for reg in self.r.iter_mut() {
+            *reg = regs.next().ok_or(())?;
+        }
+        self.orig_a0 = regs.next().ok_or(())?;
+        self.pc = regs.next().ok_or(())?;
+        self.badv = regs.next().ok_or(())?;
+
+        let mut regs = bytes[0x118..0x218]
+            .chunks_exact(8)
+            .map(|x| u64::from_le_bytes(x.try_into().unwrap()));
+
+        for reg in self.f.iter_mut() {
+            *reg = regs.next().ok_or(())?;
+        }
+
+        self.fcc.copy_from_slice(&bytes[0x218..0x220]);
+        self.fcsr = u32::from_le_bytes(bytes[0x220..0x224].try_into().unwrap());
+
+        Ok(())
+    }
+}
+
+/// 64-bit LoongArch core registers + floating-point registers + 128-bit
+/// LSX vector registers.
+///
+/// While the LSX `vr` registers architecturally alias the low bits of the
+/// floating-point registers, the GDB client treats them as independent
+/// registers. Targets are responsible for keeping the overlapping views
+/// consistent.
+///
+/// Source: <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/loongarch/lsx.xml>
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LoongArch64LsxRegs {
+    /// Core registers (+ floating-point registers)
+    pub core: LoongArch64CoreRegs,
+    /// LSX vector registers (vr0-vr31)
+    pub vr: [u128; 32],
+}
+
+impl Registers for LoongArch64LsxRegs {
+    type ProgramCounter = u64;
+
+    fn pc(&self) -> Self::ProgramCounter {
+        self.core.pc
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        self.core.gdb_serialize(&mut write_byte);
+
+        for reg in &self.vr {
+            for b in reg.to_le_bytes() {
+                write_byte(Some(b))
+            }
+        }
+    }
+
+    fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        if bytes.len() != CORE_LEN + 0x200 {
+            return Err(());
+        }
+
+        self.core.gdb_deserialize(&bytes[..CORE_LEN])?;
+
+        let mut regs = bytes[CORE_LEN..]
+            .chunks_exact(0x10)
+            .map(|x| u128::from_le_bytes(x.try_into().unwrap()));
+
+        for reg in self.vr.iter_mut() {
+            *reg = regs.next().ok_or(())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// 64-bit LoongArch core registers + floating-point registers + 128-bit LSX
+/// and 256-bit LASX vector registers.
+///
+/// As with [`LoongArch64LsxRegs`], the GDB client treats the overlapping
+/// `f`, `vr`, and `xr` registers as independent registers.
+///
+/// Source: <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/loongarch/lasx.xml>
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LoongArch64LasxRegs {
+    /// Core registers (+ floating-point registers)
+    pub core: LoongArch64CoreRegs,
+    /// LSX vector registers (vr0-vr31)
+    pub vr: [u128; 32],
+    /// LASX vector registers (xr0-xr31), as `[bits 127:0, bits 255:128]`
+    pub xr: [[u128; 2]; 32],
+}
+
+impl Registers for LoongArch64LasxRegs {
+    type ProgramCounter = u64;
+
+    fn pc(&self) -> Self::ProgramCounter {
+        self.core.pc
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        macro_rules! write_bytes {
+            ($bytes:expr) => {
+                for b in $bytes {
+                    write_byte(Some(*b))
+                }
+            };
+        }
+
+        self.core.gdb_serialize(&mut write_byte);
+
+        for reg in &self.vr {
+            write_bytes!(&reg.to_le_bytes());
+        }
+        for reg in &self.xr {
+            write_bytes!(&reg[0].to_le_bytes());
+            write_bytes!(&reg[1].to_le_bytes());
+        }
+    }
+
+    fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        if bytes.len() != CORE_LEN + 0x600 {
+            return Err(());
+        }
+
+        self.core.gdb_deserialize(&bytes[..CORE_LEN])?;
+
+        let mut regs = bytes[CORE_LEN..]
+            .chunks_exact(0x10)
+            .map(|x| u128::from_le_bytes(x.try_into().unwrap()));
+
+        for reg in self.vr.iter_mut() {
+            *reg = regs.next().ok_or(())?;
+        }
+        for reg in self.xr.iter_mut() {
+            reg[0] = regs.next().ok_or(())?;
+            reg[1] = regs.next().ok_or(())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<R: Registers>(regs_before: R, expected_len: usize) {
+        let mut data = vec![];
+
+        regs_before.gdb_serialize(|x| {
+            data.push(x.unwrap_or(b'x'));
+        });
+
+        assert_eq!(data.len(), expected_len);
+
+        let mut regs_after = R::default();
+        regs_after.gdb_deserialize(&data).unwrap();
+
+        assert_eq!(regs_before, regs_after);
+
+        // mismatched length
+        assert!(regs_after.gdb_deserialize(&data[1..]).is_err());
+    }
+
+    fn core_regs() -> LoongArch64CoreRegs {
+        let mut regs = LoongArch64CoreRegs {
+            orig_a0: u64::MAX,
+            pc: 0x9000_0000_0020_0000,
+            badv: 0xdead_beef,
+            fcc: [0, 1, 0, 1, 1, 0, 0, 1],
+            fcsr: 0x0300_0000,
+            ..Default::default()
+        };
+        for i in 0..32 {
+            regs.r[i] = 0x100 + i as u64;
+            regs.f[i] = (i as f64).to_bits();
+        }
+        regs
+    }
+
+    #[test]
+    fn loongarch64_core_round_trip() {
+        round_trip(core_regs(), 0x224);
+    }
+
+    #[test]
+    fn loongarch64_lasx_round_trip() {
+        let mut regs = LoongArch64LasxRegs {
+            core: core_regs(),
+            ..Default::default()
+        };
+        for i in 0..32 {
+            regs.vr[i] = (i as u128) << 64;
+            regs.xr[i] = [0x200 + i as u128, 0x300 + i as u128];
+        }
+
+        round_trip(regs, 0x224 + 0x600);
+    }
+}
@@ -0,0 +1,10 @@
+//! `Register` structs for LoongArch architectures.
+
+/// `RegId` definitions for LoongArch architectures.
+pub mod id;
+
+mod loongarch64;
+
+pub use loongarch64::LoongArch64CoreRegs;
+pub use loongarch64::LoongArch64LasxRegs;
+pub use loongarch64::LoongArch64LsxRegs;
@@ -0,0 +1,26 @@
+//! Register metadata for m68k / ColdFire.
+
+use crate::meta::reg_index;
+use crate::meta::RegMeta;
+use crate::xml_util::XmlReg;
+use gdbstub::arch::lldb::Generic;
+
+/// DWARF register numbers and generic registers, matching m68k GCC.
+pub(crate) fn m68k_meta(reg: &XmlReg<'_>) -> RegMeta {
+    match reg.name {
+        "fp" => RegMeta::dwarf(14).generic(Generic::Fp).alt_name("a6"),
+        "sp" => RegMeta::dwarf(15).generic(Generic::Sp).alt_name("a7"),
+        "ps" => RegMeta::default().generic(Generic::Flags),
+        "pc" => RegMeta::dwarf(24).generic(Generic::Pc),
+        name => match (
+            reg_index(name, "d"),
+            reg_index(name, "a"),
+            reg_index(name, "fp"),
+        ) {
+            (Some(n), _, _) => RegMeta::dwarf(n),
+            (_, Some(n), _) => RegMeta::dwarf(8 + n),
+            (_, _, Some(n)) => RegMeta::dwarf(16 + n),
+            _ => RegMeta::default(),
+        },
+    }
+}
@@ -0,0 +1,124 @@
+//! Implementations for the Motorola 68000 (m68k) and Freescale ColdFire
+//! family of processors.
+//!
+//! All registers are serialized in big-endian byte order.
+
+use crate::meta::RegTable;
+use gdbstub::arch::Arch;
+
+pub mod reg;
+
+mod meta;
+
+macro_rules! m68k_target_xml {
+    ($core:literal $(, $fpu:literal)?) => {
+        concat!(
+            r#"<?xml version="1.0"?>"#,
+            r#"<!DOCTYPE target SYSTEM "gdb-target.dtd">"#,
+            r#"<target version="1.0">"#,
+            "<architecture>m68k</architecture>",
+            include_str!($core),
+            $(include_str!($fpu),)?
+            "</target>",
+        )
+    };
+}
+
+/// Implements `Arch` for the Motorola 68000 family (integer registers only).
+pub enum M68k {}
+
+/// Implements `Arch` for the Motorola 68000 family + 68881 / 68882 FPU.
+pub enum M68kFpu {}
+
+/// Implements `Arch` for Freescale ColdFire cores (integer registers only).
+pub enum ColdFire {}
+
+/// Implements `Arch` for Freescale ColdFire cores + FPU.
+pub enum ColdFireFpu {}
+
+const M68K_XML: &str = m68k_target_xml!(
+    "m68k-core.xml" // feature "org.gnu.gdb.m68k.core"
+);
+static M68K_REGS: RegTable = reg_table!(M68K_XML, "");
+
+impl Arch for M68k {
+    type Usize = u32;
+    type Registers = reg::M68kCoreRegs;
+    type RegId = reg::id::M68kRegId<reg::M68881Ext>;
+    type BreakpointKind = usize;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(M68K_XML)
+    }
+
+    impl_reg_meta!(M68K_REGS, meta::m68k_meta);
+}
+
+const M68K_FPU_XML: &str = m68k_target_xml!(
+    "m68k-core.xml", // feature "org.gnu.gdb.m68k.core"
+    "m68k-fp.xml"    // feature "org.gnu.gdb.coldfire.fp" (96-bit)
+);
+static M68K_FPU_REGS: RegTable = reg_table!(M68K_FPU_XML, "");
+
+impl Arch for M68kFpu {
+    type Usize = u32;
+    type Registers = reg::M68kRegsWithFpu;
+    type RegId = reg::id::M68kRegId<reg::M68881Ext>;
+    type BreakpointKind = usize;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(M68K_FPU_XML)
+    }
+
+    impl_reg_meta!(M68K_FPU_REGS, meta::m68k_meta);
+}
+
+const COLDFIRE_XML: &str = m68k_target_xml!(
+    "coldfire-core.xml" // feature "org.gnu.gdb.coldfire.core"
+);
+static COLDFIRE_REGS: RegTable = reg_table!(COLDFIRE_XML, "");
+
+impl Arch for ColdFire {
+    type Usize = u32;
+    type Registers = reg::M68kCoreRegs;
+    type RegId = reg::id::M68kRegId<f64>;
+    type BreakpointKind = usize;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(COLDFIRE_XML)
+    }
+
+    impl_reg_meta!(COLDFIRE_REGS, meta::m68k_meta);
+}
+
+const COLDFIRE_FPU_XML: &str = m68k_target_xml!(
+    "coldfire-core.xml", // feature "org.gnu.gdb.coldfire.core"
+    "coldfire-fp.xml"    // feature "org.gnu.gdb.coldfire.fp" (64-bit)
+);
+static COLDFIRE_FPU_REGS: RegTable = reg_table!(COLDFIRE_FPU_XML, "");
+
+impl Arch for ColdFireFpu {
+    type Usize = u32;
+    type Registers = reg::ColdFireRegsWithFpu;
+    type RegId = reg::id::M68kRegId<f64>;
+    type BreakpointKind = usize;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(COLDFIRE_FPU_XML)
+    }
+
+    impl_reg_meta!(COLDFIRE_FPU_REGS, meta::m68k_meta);
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn coldfire_core_matches_m68k_core() {
+        // everything from the first register onwards must be identical
+        let regs = |xml: &'static str| &xml[xml.find("<reg ").unwrap()..];
+        assert_eq!(
+            regs(include_str!("coldfire-core.xml")),
+            regs(include_str!("m68k-core.xml"))
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use core::num::NonZeroUsize;
+use gdbstub::arch::RegId;
+
+/// m68k / ColdFire register identifier.
+///
+/// The width of the floating-point data registers is set based on the `<F>`
+/// type: [`M68881Ext`](super::M68881Ext) (96 bits) for the 68881 / 68882
+/// FPU, or `f64` for ColdFire FPUs.
+///
+/// Sources:
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/m68k-core.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/m68k-fp.xml>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum M68kRegId<F> {
+    /// Data Registers (D0-D7)
+    D(u8),
+    /// Address Registers (A0-A7)
+    A(u8),
+    /// Status Register
+    Sr,
+    /// Program Counter
+    Pc,
+    /// Floating-point Data Registers (FP0-FP7)
+    Fp(u8),
+    /// Floating-point Control Register
+    Fpcontrol,
+    /// Floating-point Status Register
+    Fpstatus,
+    /// Floating-point Instruction Address Register
+    Fpiaddr,
+
+    #[doc(hidden)]
+    _Marker(core::marker::PhantomData<F>),
+}
+
+macro_rules! impl_m68k_reg_id {
+    ($fp:ty) => {
+        impl RegId for M68kRegId<$fp> {
+            fn from_raw_id(id: usize) -> Option<(Self, Option<NonZeroUsize>)> {
+                let (reg, size) = match id {
+                    0..=7 => (Self::D(id as u8), 4),
+                    8..=15 => (Self::A((id - 8) as u8), 4),
+                    16 => (Self::Sr, 4),
+                    17 => (Self::Pc, 4),
+                    18..=25 => (Self::Fp((id - 18) as u8), core::mem::size_of::<$fp>()),
+                    26 => (Self::Fpcontrol, 4),
+                    27 => (Self::Fpstatus, 4),
+                    28 => (Self::Fpiaddr, 4),
+                    _ => return None,
+                };
+
+                Some((reg, Some(NonZeroUsize::new(size)?)))
+            }
+
+            fn to_raw_id(&self) -> Option<usize> {
+                let id = match *self {
+                    Self::D(n @ 0..=7) => n as usize,
+                    Self::A(n @ 0..=7) => 8 + n as usize,
+                    Self::Sr => 16,
+                    Self::Pc => 17,
+                    Self::Fp(n @ 0..=7) => 18 + n as usize,
+                    Self::Fpcontrol => 26,
+                    Self::Fpstatus => 27,
+                    Self::Fpiaddr => 28,
+                    _ => return None,
+                };
+                Some(id)
+            }
+        }
+    };
+}
+
+impl_m68k_reg_id!(super::M68881Ext);
+impl_m68k_reg_id!(f64);
+
+#[cfg(test)]
+mod tests {
+    use gdbstub::arch::RegId;
+
+    #[test]
+    fn test_m68k_fpu_out_of_range() {
+        assert!(super::M68kRegId::<crate::m68k::reg::M68881Ext>::from_raw_id(29).is_none());
+    }
+}
@@ -0,0 +1,266 @@
+use super::M68881Ext;
+use core::convert::TryInto;
+use gdbstub::arch::Registers;
+
+/// Size (in bytes) of the serialized `M68kCoreRegs`.
+const CORE_LEN: usize = 18 * 4;
+
+/// m68k / ColdFire integer registers.
+///
+/// Sources:
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/m68k-core.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/m68k-tdep.c>
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct M68kCoreRegs {
+    /// Data Registers (D0-D7)
+    pub d: [u32; 8],
+    /// Address Registers (A0-A7)
+    ///
+    /// `a6` is the frame pointer (`fp`), and `a7` is the active stack pointer
+    /// (`sp`).
+    pub a: [u32; 8],
+    /// Status Register (GDB uses the name `ps`)
+    pub sr: u32,
+    /// Program Counter
+    pub pc: u32,
+}
+
+impl Registers for M68kCoreRegs {
+    type ProgramCounter = u32;
+
+    fn pc(&self) -> Self::ProgramCounter {
+        self.pc
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        for reg in self.d.iter().chain(self.a.iter()) {
+            for b in reg.to_be_bytes() {
+                write_byte(Some(b));
+            }
+        }
+        for b in self.sr.to_be_bytes() {
+            write_byte(Some(b));
+        }
+        for b in self.pc.to_be_bytes() {
+            write_byte(Some(b));
+        }
+    }
+
+    fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        if bytes.len() != CORE_LEN {
+            return Err(());
+        }
+
+        let mut regs = bytes
+            .chunks_exact(4)
+            .map(|c| u32::from_be_bytes(c.try_into().unwrap()));
+
+        for reg in self.d.iter_mut().chain(self.a.iter_mut()) {
+            *reg = regs.next().ok_or(())?;
+        }
+        self.sr = regs.next().ok_or(())?;
+        self.pc = regs.next().ok_or(())?;
+
+        Ok(())
+    }
+}
+
+/// m68k integer registers + 68881 / 68882 FPU registers.
+///
+/// Source: <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/m68k-fp.xml>
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct M68kRegsWithFpu {
+    /// Integer registers
+    pub core: M68kCoreRegs,
+    /// Floating-point Data Registers (FP0-FP7), in 96-bit extended precision
+    pub fp: [M68881Ext; 8],
+    /// Floating-point Control Register
+    pub fpcontrol: u32,
+    /// Floating-point Status Register
+    pub fpstatus: u32,
+    /// Floating-point Instruction Address Register
+    pub fpiaddr: u32,
+}
+
+impl Registers for M68kRegsWithFpu {
+    type ProgramCounter = u32;
+
+    fn pc(&self) -> Self::ProgramCounter {
+        self.core.pc
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        self.core.gdb_serialize(&mut write_byte);
+
+        for reg in self.fp.iter() {
+            for b in reg {
+                write_byte(Some(*b));
+            }
+        }
+        for reg in [self.fpcontrol, self.fpstatus, self.fpiaddr] {
+            for b in reg.to_be_bytes() {
+                write_byte(Some(b));
+            }
+        }
+    }
+
+    fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        if bytes.len() != CORE_LEN + 8 * 12 + 3 * 4 {
+            return Err(());
+        }
+
+        self.core.gdb_deserialize(&bytes[..CORE_LEN])?;
+
+        let (fp, ctrl) = bytes[CORE_LEN..].split_at(8 * 12);
+        for (reg, b) in self.fp.iter_mut().zip(fp.chunks_exact(12)) {
+            reg.copy_from_slice(b);
+        }
+
+        let mut regs = ctrl
+            .chunks_exact(4)
+            .map(|c| u32::from_be_bytes(c.try_into().unwrap()));
+
+        self.fpcontrol = regs.next().ok_or(())?;
+        self.fpstatus = regs.next().ok_or(())?;
+        self.fpiaddr = regs.next().ok_or(())?;
+
+        Ok(())
+    }
+}
+
+/// ColdFire integer registers + FPU registers.
+///
+/// Source: <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/m68k-fp.xml>
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColdFireRegsWithFpu {
+    /// Integer registers
+    pub core: M68kCoreRegs,
+    /// Floating-point Data Registers (FP0-FP7), in double precision
+    pub fp: [f64; 8],
+    /// Floating-point Control Register
+    pub fpcontrol: u32,
+    /// Floating-point Status Register
+    pub fpstatus: u32,
+    /// Floating-point Instruction Address Register
+    pub fpiaddr: u32,
+}
+
+impl Registers for ColdFireRegsWithFpu {
+    type ProgramCounter = u32;
+
+    fn pc(&self) -> Self::ProgramCounter {
+        self.core.pc
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        self.core.gdb_serialize(&mut write_byte);
+
+        for reg in self.fp.iter() {
+            for b in reg.to_be_bytes() {
+                write_byte(Some(b));
+            }
+        }
+        for reg in [self.fpcontrol, self.fpstatus, self.fpiaddr] {
+            for b in reg.to_be_bytes() {
+                write_byte(Some(b));
+            }
+        }
+    }
+
+    fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        if bytes.len() != CORE_LEN + 8 * 8 + 3 * 4 {
+            return Err(());
+        }
+
+        self.core.gdb_deserialize(&bytes[..CORE_LEN])?;
+
+        let (fp, ctrl) = bytes[CORE_LEN..].split_at(8 * 8);
+        let mut regs = fp
+            .chunks_exact(8)
+            .map(|c| f64::from_be_bytes(c.try_into().unwrap()));
+
+        for reg in self.fp.iter_mut() {
+            *reg = regs.next().ok_or(())?;
+        }
+
+        let mut regs = ctrl
+            .chunks_exact(4)
+            .map(|c| u32::from_be_bytes(c.try_into().unwrap()));
+
+        self.fpcontrol = regs.next().ok_or(())?;
+        self.fpstatus = regs.next().ok_or(())?;
+        self.fpiaddr = regs.next().ok_or(())?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<R: Registers>(regs_before: R, expected_len: usize) -> Vec<u8> {
+        let mut data = vec![];
+
+        regs_before.gdb_serialize(|x| {
+            data.push(x.unwrap_or(b'x'));
+        });
+
+        assert_eq!(data.len(), expected_len);
+
+        let mut regs_after = R::default();
+        regs_after.gdb_deserialize(&data).unwrap();
+
+        assert_eq!(regs_before, regs_after);
+
+        data
+    }
+
+    fn core_regs() -> M68kCoreRegs {
+        M68kCoreRegs {
+            d: [1, 2, 3, 4, 5, 6, 7, 8],
+            a: [9, 10, 11, 12, 13, 14, 15, 0x00ff_fffc],
+            sr: 0x2700,
+            pc: 0x0000_0400,
+        }
+    }
+
+    #[test]
+    fn m68k_core_big_endian() {
+        let data = round_trip(core_regs(), CORE_LEN);
+        assert_eq!(&data[..4], &[0, 0, 0, 1]);
+        assert_eq!(&data[CORE_LEN - 8..], &[0, 0, 0x27, 0, 0, 0, 4, 0]);
+    }
+
+    #[test]
+    fn m68k_fpu_round_trip() {
+        let mut regs = M68kRegsWithFpu {
+            core: core_regs(),
+            fpcontrol: 1,
+            fpstatus: 2,
+            fpiaddr: 3,
+            ..Default::default()
+        };
+        // 1.0 in 68881 extended precision
+        regs.fp[0] = [0x3f, 0xff, 0, 0, 0x80, 0, 0, 0, 0, 0, 0, 0];
+
+        round_trip(regs, CORE_LEN + 108);
+    }
+
+    #[test]
+    fn coldfire_fpu_round_trip() {
+        let regs = ColdFireRegsWithFpu {
+            core: core_regs(),
+            fp: [1.5; 8],
+            fpcontrol: 1,
+            fpstatus: 2,
+            fpiaddr: 3,
+        };
+
+        let data = round_trip(regs, CORE_LEN + 76);
+        assert_eq!(&data[CORE_LEN..CORE_LEN + 8], &1.5f64.to_be_bytes());
+    }
+}
@@ -0,0 +1,17 @@
+//! `Register` structs for m68k / ColdFire processors.
+
+/// `RegId` definitions for m68k / ColdFire processors.
+pub mod id;
+
+mod m68k;
+
+pub use m68k::ColdFireRegsWithFpu;
+pub use m68k::M68kCoreRegs;
+pub use m68k::M68kRegsWithFpu;
+
+/// A 96-bit 68881 extended-precision floating point value, as raw big-endian
+/// bytes.
+///
+/// The value is laid out as a 16-bit sign + exponent, followed by 16 bits of
+/// zero padding, followed by a 64-bit mantissa (with an explicit integer bit).
+pub type M68881Ext = [u8; 12];
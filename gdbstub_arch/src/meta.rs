@@ -0,0 +1,453 @@
+//! Register metadata derived from target description XML.
+//!
+//! Each register's name, size, and group are taken from the arch's target
+//! description XML (supplemented by hand-written XML for arches which don't
+//! describe their registers in their target description). Information which
+//! isn't part of the target description (e.g: DWARF register numbers, and
+//! generic register roles) is provided by each arch via a [`RegMeta`]
+//! callback.
+//!
+//! This metadata backs both [`Arch::reg_info`] and the LLDB `qRegisterInfo`
+//! support in [`crate::lldb`].
+//!
+//! [`Arch::reg_info`]: gdbstub::arch::Arch::reg_info
+
+use crate::xml_util::RegScanner;
+use crate::xml_util::RegSpan;
+use crate::xml_util::XmlReg;
+use core::fmt;
+use core::num::NonZeroUsize;
+use gdbstub::arch::lldb::Generic;
+use gdbstub::arch::RegId;
+use gdbstub::arch::RegInfo;
+
+/// Arch-specific register information which isn't part of the target
+/// description XML.
+#[derive(Default)]
+pub struct RegMeta {
+    pub alt_name: Option<&'static str>,
+    pub dwarf: Option<usize>,
+    pub generic: Option<Generic>,
+    /// Display the register as a floating point value, regardless of its
+    /// XML type (e.g: for registers using custom `<union>` types).
+    pub float: bool,
+}
+
+impl RegMeta {
+    pub fn dwarf(dwarf: usize) -> Self {
+        RegMeta {
+            dwarf: Some(dwarf),
+            ..Default::default()
+        }
+    }
+
+    pub fn generic(self, generic: Generic) -> Self {
+        RegMeta {
+            generic: Some(generic),
+            ..self
+        }
+    }
+
+    /// Mark the register as holding the `n`th (zero-indexed) function
+    /// argument. Only the first 8 arguments can be marked.
+    pub fn arg(self, n: usize) -> Self {
+        let generic = match n {
+            0 => Generic::Arg1,
+            1 => Generic::Arg2,
+            2 => Generic::Arg3,
+            3 => Generic::Arg4,
+            4 => Generic::Arg5,
+            5 => Generic::Arg6,
+            6 => Generic::Arg7,
+            7 => Generic::Arg8,
+            _ => return self,
+        };
+        self.generic(generic)
+    }
+
+    pub fn alt_name(self, alt_name: &'static str) -> Self {
+        RegMeta {
+            alt_name: Some(alt_name),
+            ..self
+        }
+    }
+
+    pub fn float(self) -> Self {
+        RegMeta {
+            float: true,
+            ..self
+        }
+    }
+}
+
+/// Concatenate a list of `<reg>` elements sharing the same `bitsize` and
+/// `type` into a `&'static str`.
+///
+/// Used to describe registers for arches which don't include any registers in
+/// their target description XML.
+macro_rules! regs_xml {
+    ($bitsize:literal, $ty:literal: $($name:literal),* $(,)?) => {
+        concat!($(
+            r#"<reg name=""#, $name,
+            r#"" bitsize=""#, $bitsize,
+            r#"" type=""#, $ty, r#""/>"#,
+        )*)
+    };
+    ($bitsize:literal, $ty:literal, $group:literal: $($name:literal),* $(,)?) => {
+        concat!($(
+            r#"<reg name=""#, $name,
+            r#"" bitsize=""#, $bitsize,
+            r#"" type=""#, $ty,
+            r#"" group=""#, $group, r#""/>"#,
+        )*)
+    };
+}
+
+/// Index the `<reg>` elements of the static XML `$xml` (followed by those in
+/// `$gaps`) at compile time, producing a [`RegTable`].
+///
+/// `$gaps` describes registers which aren't part of the "g" packet (see
+/// [`crate::lldb::register_info`]).
+macro_rules! reg_table {
+    ($xml:expr, $gaps:expr $(,)?) => {{
+        const XML: &str = $xml;
+        const GAPS: &str = $gaps;
+        // every `<reg>` element is comfortably longer than 16 bytes
+        const INDEX: crate::xml_util::RegIndex<{ (XML.len() + GAPS.len()) / 16 }> =
+            crate::xml_util::index_regs(XML, GAPS);
+        const SPANS: [crate::xml_util::RegSpan; INDEX.len] =
+            crate::xml_util::truncate(&INDEX.spans);
+        crate::meta::RegTable {
+            xml: XML,
+            gaps: GAPS,
+            spans: &SPANS,
+            xml_regs: INDEX.xml_regs,
+        }
+    }};
+}
+
+/// Implement [`Arch::lldb_register_info`], [`Arch::reg_info`], and
+/// [`Arch::reg_id_from_name`] for an arch whose registers are described by
+/// the static [`RegTable`] `$table` (see [`reg_table!`]), with `$meta`
+/// providing each register's [`RegMeta`].
+///
+/// [`Arch::lldb_register_info`]: gdbstub::arch::Arch::lldb_register_info
+/// [`Arch::reg_info`]: gdbstub::arch::Arch::reg_info
+/// [`Arch::reg_id_from_name`]: gdbstub::arch::Arch::reg_id_from_name
+macro_rules! impl_reg_meta {
+    ($table:expr, $meta:expr $(,)?) => {
+        fn lldb_register_info(reg_id: usize) -> Option<gdbstub::arch::lldb::RegisterInfo<'static>> {
+            crate::lldb::register_info(&$table, reg_id, $meta)
+        }
+
+        fn reg_info(idx: usize) -> Option<gdbstub::arch::RegInfo<'static>> {
+            crate::meta::reg_info(&$table, idx, $meta)
+        }
+
+        fn reg_id_from_name(name: &str) -> Option<(Self::RegId, Option<core::num::NonZeroUsize>)> {
+            crate::meta::reg_id_from_name(&$table, name, $meta)
+        }
+    };
+}
+
+/// The `<reg>` elements of a static target description, located at compile
+/// time by [`reg_table!`].
+///
+/// Looking up a register only requires parsing its own tag, rather than
+/// re-scanning the entire XML.
+pub(crate) struct RegTable {
+    pub xml: &'static str,
+    pub gaps: &'static str,
+    /// The registers in `xml`, followed by those in `gaps`.
+    pub spans: &'static [RegSpan],
+    /// The number of registers in `xml`.
+    pub xml_regs: usize,
+}
+
+impl RegTable {
+    /// Whether the `idx`-th register is described by `xml` (rather than
+    /// `gaps`).
+    pub fn in_xml(&self, idx: usize) -> bool {
+        idx < self.xml_regs
+    }
+
+    /// Parse the `idx`-th register.
+    pub fn reg(&self, idx: usize) -> Option<XmlReg<'static>> {
+        let span = self.spans.get(idx)?;
+        let src = if self.in_xml(idx) {
+            self.xml
+        } else {
+            self.gaps
+        };
+        XmlReg::parse(&src[span.range()], span.regnum as usize)
+    }
+}
+
+/// The broad class of a register, derived from its XML type and size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RegKind {
+    General,
+    Float,
+    Vector,
+}
+
+impl RegKind {
+    pub fn of(reg: &XmlReg<'_>, meta: &RegMeta) -> RegKind {
+        let is_float = meta.float
+            || matches!(
+                reg.ty,
+                Some(
+                    "ieee_half"
+                        | "ieee_single"
+                        | "ieee_double"
+                        | "ieee_quad"
+                        | "i387_ext"
+                        | "arm_fpa_ext"
+                        | "bfloat16"
+                        | "float"
+                )
+            );
+        if is_float {
+            RegKind::Float
+        } else if reg.bitsize > 64 {
+            RegKind::Vector
+        } else {
+            RegKind::General
+        }
+    }
+
+    /// The register group used for registers without an explicit XML
+    /// `group` attribute.
+    pub fn group(self) -> &'static str {
+        match self {
+            RegKind::General => "general",
+            RegKind::Float => "float",
+            RegKind::Vector => "vector",
+        }
+    }
+}
+
+/// Construct the [`RegInfo`] for `reg`.
+pub(crate) fn info<'a>(reg: &XmlReg<'a>, meta: RegMeta) -> RegInfo<'a> {
+    RegInfo {
+        name: reg.name,
+        alt_name: meta.alt_name,
+        regnum: reg.regnum,
+        bitsize: reg.bitsize,
+        group: reg.group.unwrap_or_else(|| RegKind::of(reg, &meta).group()),
+        dwarf: meta.dwarf,
+        generic: meta.generic,
+    }
+}
+
+/// Implementation of `Arch::reg_info` for arches with a static target
+/// description.
+///
+/// Registers are enumerated in the order they appear in `xml`. Registers in
+/// `gaps` are placeholders which only exist to keep LLDB's `qRegisterInfo`
+/// walk going, and aren't reported.
+pub(crate) fn reg_info(
+    table: &RegTable,
+    idx: usize,
+    meta: impl Fn(&XmlReg<'_>) -> RegMeta,
+) -> Option<RegInfo<'static>> {
+    if !table.in_xml(idx) {
+        return None;
+    }
+    let reg = table.reg(idx)?;
+    Some(info(&reg, meta(&reg)))
+}
+
+/// Implementation of `Arch::reg_id_from_name` for arches with a static
+/// target description.
+pub(crate) fn reg_id_from_name<R: RegId>(
+    table: &RegTable,
+    name: &str,
+    meta: impl Fn(&XmlReg<'_>) -> RegMeta,
+) -> Option<(R, Option<NonZeroUsize>)> {
+    let reg = (0..table.xml_regs)
+        .filter_map(|idx| table.reg(idx))
+        .find(|reg| reg.name == name || meta(reg).alt_name == Some(name))?;
+    R::from_raw_id(reg.regnum)
+}
+
+/// Invoke `f` with the [`RegInfo`] of each register described by the runtime
+/// generated target description `xml`.
+pub(crate) fn for_each_reg_info(
+    xml: &impl fmt::Display,
+    meta: impl Fn(&XmlReg<'_>) -> RegMeta,
+    mut f: impl FnMut(RegInfo<'_>),
+) {
+    RegScanner::new(|reg: &XmlReg<'_>, _| f(info(reg, meta(reg)))).scan(xml);
+}
+
+/// Parse the numeric suffix of a register name (e.g: `"x12"` with prefix `"x"`
+/// returns `Some(12)`).
+pub(crate) fn reg_index(name: &str, prefix: &str) -> Option<usize> {
+    let idx = name.strip_prefix(prefix)?;
+    if idx.is_empty() || !idx.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    idx.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::xml_util::RegScanner;
+    use crate::xml_util::XmlReg;
+    use gdbstub::arch::lldb::Generic;
+    use gdbstub::arch::Arch;
+
+    #[test]
+    fn x86_64() {
+        use crate::x86::reg::id::X86_64CoreRegId;
+        use crate::x86::X86_64_SSE;
+
+        let rax = X86_64_SSE::reg_info(0).unwrap();
+        assert_eq!(rax.name, "rax");
+        assert_eq!(rax.regnum, 0);
+        assert_eq!(rax.size(), 8);
+        assert_eq!(rax.group, "general");
+        assert_eq!(rax.dwarf, Some(0));
+
+        let rip = X86_64_SSE::reg_info(16).unwrap();
+        assert_eq!(rip.name, "rip");
+        assert_eq!(rip.generic, Some(Generic::Pc));
+
+        let st0 = X86_64_SSE::reg_info(24).unwrap();
+        assert_eq!(st0.name, "st0");
+        assert_eq!(st0.bitsize, 80);
+        assert_eq!(st0.group, "float");
+
+        let xmm0 = X86_64_SSE::reg_info(40).unwrap();
+        assert_eq!(xmm0.name, "xmm0");
+        assert_eq!(xmm0.group, "vector");
+
+        let n = (0..).map_while(X86_64_SSE::reg_info).count();
+        assert_eq!(n, 57);
+
+        assert!(matches!(
+            X86_64_SSE::reg_id_from_name("rip"),
+            Some((X86_64CoreRegId::Rip, _))
+        ));
+        assert!(matches!(
+            X86_64_SSE::reg_id_from_name("rsp"),
+            Some((X86_64CoreRegId::Gpr(7), _))
+        ));
+        assert!(X86_64_SSE::reg_id_from_name("eax").is_none());
+    }
+
+    #[test]
+    fn alt_names() {
+        use crate::aarch64::reg::id::AArch64RegId;
+        use crate::aarch64::AArch64;
+        use crate::riscv::reg::id::RiscvRegId;
+        use crate::riscv::Riscv64;
+
+        assert!(matches!(
+            AArch64::reg_id_from_name("fp"),
+            Some((AArch64RegId::X(29), _))
+        ));
+        assert!(matches!(
+            AArch64::reg_id_from_name("x29"),
+            Some((AArch64RegId::X(29), _))
+        ));
+        assert!(matches!(
+            Riscv64::reg_id_from_name("a0"),
+            Some((RiscvRegId::Gpr(10), _))
+        ));
+
+        assert!(matches!(
+            Riscv64::reg_id_from_name("x10"),
+            Some((RiscvRegId::Gpr(10), _))
+        ));
+
+        let sp = Riscv64::reg_info(2).unwrap();
+        assert_eq!(sp.name, "sp");
+        assert_eq!(sp.alt_name, Some("x2"));
+        assert!(sp.matches("x2"));
+        assert_eq!(sp.generic, Some(Generic::Sp));
+    }
+
+    #[test]
+    fn supplemental_xml() {
+        use crate::arm::reg::id::ArmCoreRegId;
+        use crate::arm::Armv4t;
+
+        let pc = Armv4t::reg_info(15).unwrap();
+        assert_eq!(pc.name, "pc");
+        assert_eq!(pc.generic, Some(Generic::Pc));
+        assert!(matches!(
+            Armv4t::reg_id_from_name("lr"),
+            Some((ArmCoreRegId::Lr, _))
+        ));
+    }
+
+    #[test]
+    fn gaps_not_reported() {
+        use crate::riscv::Riscv64D;
+
+        // `ustatus` is only reported to LLDB, to fill in the gap at regnum 65
+        assert!((0..)
+            .map_while(Riscv64D::reg_info)
+            .all(|info| info.name != "ustatus"));
+        assert!(Riscv64D::reg_id_from_name("ustatus").is_none());
+    }
+
+    /// Check that `A::reg_info` reports the registers in `A`'s target
+    /// description (as scanned at runtime) in the same order, with the same
+    /// numbering.
+    fn check_static_table<A: Arch>() {
+        let mut regs = Vec::new();
+        let xml = A::target_description_xml().unwrap();
+        RegScanner::new(|reg: &XmlReg<'_>, _| {
+            regs.push((reg.name.to_string(), reg.regnum, reg.bitsize))
+        })
+        .scan(&xml);
+        assert!(!regs.is_empty());
+
+        let infos: Vec<_> = (0..)
+            .map_while(A::reg_info)
+            .map(|info| (info.name.to_string(), info.regnum, info.bitsize))
+            .collect();
+        assert_eq!(infos, regs);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn static_tables() {
+        check_static_table::<crate::aarch64::AArch64>();
+        check_static_table::<crate::aarch64::AArch64Pauth>();
+        check_static_table::<crate::aarch64::AArch64PauthMte>();
+        check_static_table::<crate::arm::Armv7a>();
+        check_static_table::<crate::loongarch::LoongArch64>();
+        check_static_table::<crate::loongarch::LoongArch64Lsx>();
+        check_static_table::<crate::loongarch::LoongArch64Lasx>();
+        check_static_table::<crate::m68k::M68k>();
+        check_static_table::<crate::m68k::M68kFpu>();
+        check_static_table::<crate::m68k::ColdFire>();
+        check_static_table::<crate::m68k::ColdFireFpu>();
+        check_static_table::<crate::ppc::PowerPc64Vsx>();
+        check_static_table::<crate::riscv::Riscv32>();
+        check_static_table::<crate::riscv::Riscv64>();
+        check_static_table::<crate::riscv::Riscv32F>();
+        check_static_table::<crate::riscv::Riscv32D>();
+        check_static_table::<crate::riscv::Riscv64F>();
+        check_static_table::<crate::riscv::Riscv64D>();
+        check_static_table::<crate::s390x::S390x>();
+        check_static_table::<crate::s390x::S390xVx>();
+        check_static_table::<crate::x86::X86_64_AVX>();
+        check_static_table::<crate::x86::X86_64_AVX512>();
+        check_static_table::<crate::x86::X86_64_Linux_AVX>();
+        check_static_table::<crate::x86::X86_64_Linux_AVX512>();
+        check_static_table::<crate::x86::X86_64_AVX_System>();
+        check_static_table::<crate::x86::X86_64_AVX512_System>();
+    }
+
+    #[test]
+    fn reg_index() {
+        assert_eq!(super::reg_index("x12", "x"), Some(12));
+        assert_eq!(super::reg_index("xmm12", "x"), None);
+        assert_eq!(super::reg_index("x", "x"), None);
+    }
+}
@@ -0,0 +1,66 @@
+//! Register metadata for MIPS.
+
+use crate::meta::reg_index;
+use crate::meta::RegMeta;
+use crate::xml_util::XmlReg;
+use gdbstub::arch::lldb::Generic;
+
+macro_rules! mips_regs_xml {
+    ($bitsize:literal, $float:literal) => {
+        concat!(
+            regs_xml!($bitsize, "int":
+                "zero", "at", "v0", "v1", "a0", "a1", "a2", "a3", "t0", "t1", "t2",
+                "t3", "t4", "t5", "t6", "t7", "s0", "s1", "s2", "s3", "s4", "s5",
+                "s6", "s7", "t8", "t9", "k0", "k1", "gp", "sp", "s8", "ra", "sr",
+                "lo", "hi", "bad", "cause",
+            ),
+            regs_xml!($bitsize, "code_ptr": "pc"),
+            regs_xml!($bitsize, $float, "float":
+                "f0", "f1", "f2", "f3", "f4", "f5", "f6", "f7", "f8", "f9", "f10",
+                "f11", "f12", "f13", "f14", "f15", "f16", "f17", "f18", "f19",
+                "f20", "f21", "f22", "f23", "f24", "f25", "f26", "f27", "f28",
+                "f29", "f30", "f31",
+            ),
+            regs_xml!($bitsize, "int", "float": "fsr", "fir"),
+        )
+    };
+}
+
+macro_rules! mips_dsp_regs_xml {
+    ($bitsize:literal, $float:literal) => {
+        concat!(
+            mips_regs_xml!($bitsize, $float),
+            regs_xml!($bitsize, "int": "hi1", "lo1", "hi2", "lo2", "hi3", "lo3"),
+            regs_xml!(32, "int": "dspctl"),
+            regs_xml!($bitsize, "int": "restart"),
+        )
+    };
+}
+
+/// Registers in the 32-bit MIPS "g" packet.
+pub(crate) const MIPS_REGS_XML: &str = mips_regs_xml!(32, "ieee_single");
+/// Registers in the 32-bit MIPS "g" packet, with DSP registers.
+pub(crate) const MIPS_DSP_REGS_XML: &str = mips_dsp_regs_xml!(32, "ieee_single");
+/// Registers in the 64-bit MIPS "g" packet.
+pub(crate) const MIPS64_REGS_XML: &str = mips_regs_xml!(64, "ieee_double");
+/// Registers in the 64-bit MIPS "g" packet, with DSP registers.
+pub(crate) const MIPS64_DSP_REGS_XML: &str = mips_dsp_regs_xml!(64, "ieee_double");
+
+/// DWARF register numbers and generic registers, as per the MIPS SysV ABI.
+pub(crate) fn mips_meta(reg: &XmlReg<'_>) -> RegMeta {
+    match reg.regnum {
+        n @ 4..=7 => RegMeta::dwarf(n).arg(n - 4),
+        29 => RegMeta::dwarf(29).generic(Generic::Sp),
+        30 => RegMeta::dwarf(30).generic(Generic::Fp),
+        31 => RegMeta::dwarf(31).generic(Generic::Ra),
+        n @ 0..=31 => RegMeta::dwarf(n),
+        32 => RegMeta::default().generic(Generic::Flags),
+        33 => RegMeta::dwarf(65),
+        34 => RegMeta::dwarf(64),
+        37 => RegMeta::default().generic(Generic::Pc),
+        _ => match reg_index(reg.name, "f") {
+            Some(n) => RegMeta::dwarf(32 + n),
+            None => RegMeta::default(),
+        },
+    }
+}
@@ -1,9 +1,12 @@
 //! Implementations for the MIPS architecture.
 
+use crate::meta::RegTable;
 use gdbstub::arch::Arch;
 
 pub mod reg;
 
+mod meta;
+
 /// MIPS-specific breakpoint kinds.
 ///
 /// Extracted from the GDB documentation at
@@ -64,6 +67,8 @@ pub enum Mips64 {}
 /// [`TargetDescriptionXmlOverride`]: gdbstub::target::ext::target_description_xml_override::TargetDescriptionXmlOverride
 pub enum Mips64WithDsp {}
 
+static MIPS_REGS: RegTable = reg_table!(meta::MIPS_REGS_XML, "");
+
 impl Arch for Mips {
     type Usize = u32;
     type Registers = reg::MipsCoreRegs<u32>;
@@ -73,8 +78,12 @@ impl Arch for Mips {
     fn target_description_xml() -> Option<&'static str> {
         Some(r#"<target version="1.0"><architecture>mips</architecture></target>"#)
     }
+
+    impl_reg_meta!(MIPS_REGS, meta::mips_meta);
 }
 
+static MIPS_WITH_DSP_REGS: RegTable = reg_table!(meta::MIPS_DSP_REGS_XML, "");
+
 impl Arch for MipsWithDsp {
     type Usize = u32;
     type Registers = reg::MipsCoreRegsWithDsp<u32>;
@@ -86,8 +95,12 @@ impl Arch for MipsWithDsp {
             r#"<target version="1.0"><architecture>mips</architecture><feature name="org.gnu.gdb.mips.dsp"></feature></target>"#,
         )
     }
+
+    impl_reg_meta!(MIPS_WITH_DSP_REGS, meta::mips_meta);
 }
 
+static MIPS64_REGS: RegTable = reg_table!(meta::MIPS64_REGS_XML, "");
+
 #[allow(deprecated)]
 impl Arch for Mips64 {
     type Usize = u64;
@@ -98,8 +111,12 @@ impl Arch for Mips64 {
     fn target_description_xml() -> Option<&'static str> {
         None
     }
+
+    impl_reg_meta!(MIPS64_REGS, meta::mips_meta);
 }
 
+static MIPS64_WITH_DSP_REGS: RegTable = reg_table!(meta::MIPS64_DSP_REGS_XML, "");
+
 #[allow(deprecated)]
 impl Arch for Mips64WithDsp {
     type Usize = u64;
@@ -110,4 +127,6 @@ impl Arch for Mips64WithDsp {
     fn target_description_xml() -> Option<&'static str> {
         None
     }
+
+    impl_reg_meta!(MIPS64_WITH_DSP_REGS, meta::mips_meta);
 }
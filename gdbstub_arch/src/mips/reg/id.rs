@@ -3,6 +3,7 @@ use gdbstub::arch::RegId;
 
 /// MIPS register identifier.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum MipsRegId<U> {
     /// General purpose registers (R0-R31)
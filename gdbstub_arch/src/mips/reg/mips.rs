@@ -9,6 +9,7 @@ use num_traits::PrimInt;
 ///
 /// Source: <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/mips-cpu.xml>
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MipsCoreRegs<U> {
     /// General purpose registers (R0-R31)
     pub r: [U; 32],
@@ -28,6 +29,7 @@ pub struct MipsCoreRegs<U> {
 ///
 /// Source: <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/mips-cp0.xml>
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MipsCp0Regs<U> {
     /// Status register (regnum 32)
     pub status: U,
@@ -41,6 +43,7 @@ pub struct MipsCp0Regs<U> {
 ///
 /// Source: <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/mips-fpu.xml>
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MipsFpuRegs<U> {
     /// FP registers (F0-F31) starting at regnum 38
     pub r: [U; 32],
@@ -54,6 +57,7 @@ pub struct MipsFpuRegs<U> {
 ///
 /// Source: <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/mips-dsp.xml>
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MipsDspRegs<U> {
     /// High 1 register (regnum 72)
     pub hi1: U,
@@ -79,6 +83,7 @@ pub struct MipsDspRegs<U> {
 ///
 /// Source: <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/mips-dsp-linux.xml>
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MipsCoreRegsWithDsp<U> {
     /// Core registers
     pub core: MipsCoreRegs<U>,
@@ -0,0 +1,40 @@
+//! Register metadata for MSP430.
+
+use crate::meta::reg_index;
+use crate::meta::RegMeta;
+use crate::xml_util::XmlReg;
+use gdbstub::arch::lldb::Generic;
+
+macro_rules! msp430_regs_xml {
+    ($bitsize:literal) => {
+        concat!(
+            regs_xml!($bitsize, "code_ptr": "pc"),
+            regs_xml!($bitsize, "data_ptr": "sp"),
+            regs_xml!($bitsize, "uint16": "sr", "cg"),
+            regs_xml!($bitsize, "int":
+                "r4", "r5", "r6", "r7", "r8", "r9", "r10", "r11", "r12", "r13",
+                "r14", "r15",
+            ),
+        )
+    };
+}
+
+/// Registers in the MSP430 "g" packet.
+pub(crate) const MSP430_REGS_XML: &str = msp430_regs_xml!(16);
+/// Registers in the MSP430X "g" packet.
+pub(crate) const MSP430X_REGS_XML: &str = msp430_regs_xml!(32);
+
+/// DWARF register numbers and generic registers, as per the MSP430 EABI.
+pub(crate) fn msp430_meta(reg: &XmlReg<'_>) -> RegMeta {
+    match reg.name {
+        "pc" => RegMeta::dwarf(0).generic(Generic::Pc).alt_name("r0"),
+        "sp" => RegMeta::dwarf(1).generic(Generic::Sp).alt_name("r1"),
+        "sr" => RegMeta::dwarf(2).generic(Generic::Flags).alt_name("r2"),
+        "cg" => RegMeta::dwarf(3).alt_name("r3"),
+        name => match reg_index(name, "r") {
+            Some(n @ 12..=15) => RegMeta::dwarf(n).arg(n - 12),
+            Some(n) => RegMeta::dwarf(n),
+            None => RegMeta::default(),
+        },
+    }
+}
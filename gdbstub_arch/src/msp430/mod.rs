@@ -1,12 +1,17 @@
 //! Implementations for the TI-MSP430 family of MCUs.
 
+use crate::meta::RegTable;
 use gdbstub::arch::Arch;
 
 pub mod reg;
 
+mod meta;
+
 /// Implements `Arch` for standard 16-bit TI-MSP430 MCUs.
 pub struct Msp430 {}
 
+static MSP430_REGS: RegTable = reg_table!(meta::MSP430_REGS_XML, "");
+
 impl Arch for Msp430 {
     type Usize = u16;
     type Registers = reg::Msp430Regs<u16>;
@@ -16,11 +21,15 @@ impl Arch for Msp430 {
     fn target_description_xml() -> Option<&'static str> {
         Some(r#"<target version="1.0"><architecture>msp430</architecture></target>"#)
     }
+
+    impl_reg_meta!(MSP430_REGS, meta::msp430_meta);
 }
 
 /// Implements `Arch` for 20-bit TI-MSP430 MCUs (CPUX).
 pub struct Msp430X {}
 
+static MSP430_X_REGS: RegTable = reg_table!(meta::MSP430X_REGS_XML, "");
+
 impl Arch for Msp430X {
     type Usize = u32;
     type Registers = reg::Msp430Regs<u32>;
@@ -30,4 +39,6 @@ impl Arch for Msp430X {
     fn target_description_xml() -> Option<&'static str> {
         Some(r#"<target version="1.0"><architecture>msp430x</architecture></target>"#)
     }
+
+    impl_reg_meta!(MSP430_X_REGS, meta::msp430_meta);
 }
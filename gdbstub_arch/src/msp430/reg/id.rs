@@ -6,6 +6,7 @@ use gdbstub::arch::RegId;
 /// GDB does not provide a XML file for the MSP430.
 /// The best file to reference is [msp430-tdep.c](https://github.com/bminor/binutils-gdb/blob/master/gdb/msp430-tdep.c).
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum Msp430RegId<U> {
     /// Program Counter (R0)
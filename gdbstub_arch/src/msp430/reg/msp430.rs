@@ -7,6 +7,7 @@ use num_traits::PrimInt;
 /// The register width is set based on the `<U>` type. For 16-bit MSP430 CPUs
 /// this should be `u16` and for 20-bit MSP430 CPUs (CPUX) this should be `u32`.
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Msp430Regs<U> {
     /// Program Counter (R0)
     pub pc: U,
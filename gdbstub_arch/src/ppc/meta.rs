@@ -0,0 +1,59 @@
+//! Register metadata for PowerPC.
+
+use crate::meta::reg_index;
+use crate::meta::RegMeta;
+use crate::xml_util::XmlReg;
+use gdbstub::arch::lldb::Generic;
+
+/// Registers in the 32-bit PowerPC (+ AltiVec) "g" packet.
+pub(crate) const PPC32_ALTIVEC_REGS_XML: &str = concat!(
+    regs_xml!(32, "uint32":
+        "r0", "r1", "r2", "r3", "r4", "r5", "r6", "r7", "r8", "r9", "r10",
+        "r11", "r12", "r13", "r14", "r15", "r16", "r17", "r18", "r19", "r20",
+        "r21", "r22", "r23", "r24", "r25", "r26", "r27", "r28", "r29", "r30",
+        "r31",
+    ),
+    regs_xml!(64, "ieee_double":
+        "f0", "f1", "f2", "f3", "f4", "f5", "f6", "f7", "f8", "f9", "f10",
+        "f11", "f12", "f13", "f14", "f15", "f16", "f17", "f18", "f19", "f20",
+        "f21", "f22", "f23", "f24", "f25", "f26", "f27", "f28", "f29", "f30",
+        "f31",
+    ),
+    regs_xml!(32, "code_ptr": "pc"),
+    regs_xml!(32, "uint32": "msr", "cr"),
+    regs_xml!(32, "code_ptr": "lr"),
+    regs_xml!(32, "uint32": "ctr", "xer"),
+    regs_xml!(32, "uint32", "float": "fpscr"),
+    regs_xml!(128, "vec128":
+        "vr0", "vr1", "vr2", "vr3", "vr4", "vr5", "vr6", "vr7", "vr8", "vr9",
+        "vr10", "vr11", "vr12", "vr13", "vr14", "vr15", "vr16", "vr17", "vr18",
+        "vr19", "vr20", "vr21", "vr22", "vr23", "vr24", "vr25", "vr26", "vr27",
+        "vr28", "vr29", "vr30", "vr31",
+    ),
+    regs_xml!(32, "uint32", "vector": "vscr", "vrsave"),
+);
+
+/// DWARF register numbers and generic registers, as per the PowerPC ELF ABI.
+pub(crate) fn ppc_meta(reg: &XmlReg<'_>) -> RegMeta {
+    match reg.name {
+        "pc" => RegMeta::default().generic(Generic::Pc),
+        "cr" => RegMeta::dwarf(64),
+        "lr" => RegMeta::dwarf(65).generic(Generic::Ra),
+        "ctr" => RegMeta::dwarf(66),
+        "xer" => RegMeta::dwarf(76),
+        "vscr" => RegMeta::dwarf(110),
+        name => match (
+            reg_index(name, "r"),
+            reg_index(name, "f"),
+            reg_index(name, "vr"),
+        ) {
+            (Some(1), _, _) => RegMeta::dwarf(1).generic(Generic::Sp).alt_name("sp"),
+            (Some(31), _, _) => RegMeta::dwarf(31).generic(Generic::Fp),
+            (Some(n @ 3..=10), _, _) => RegMeta::dwarf(n).arg(n - 3),
+            (Some(n), _, _) => RegMeta::dwarf(n),
+            (_, Some(n), _) => RegMeta::dwarf(32 + n),
+            (_, _, Some(n)) => RegMeta::dwarf(77 + n),
+            _ => RegMeta::default(),
+        },
+    }
+}
@@ -1,19 +1,25 @@
 //! Implementations for various PowerPC architectures.
 
+use crate::meta::RegTable;
 use gdbstub::arch::Arch;
 use gdbstub::arch::RegId;
 
 pub mod reg;
 
+mod meta;
+
 /// Implements `Arch` for 32-bit PowerPC + AltiVec SIMD.
 ///
 /// Check out the [module level docs](gdbstub::arch#whats-with-regidimpl) for
-/// more info about the `RegIdImpl` type parameter.
-pub enum PowerPcAltivec32<RegIdImpl: RegId> {
+/// more info about the `RegIdImpl` type parameter, which defaults to
+/// [`PowerPcRegId<u32>`](reg::id::PowerPcRegId).
+pub enum PowerPcAltivec32<RegIdImpl: RegId = reg::id::PowerPcRegId<u32>> {
     #[doc(hidden)]
     _Marker(core::marker::PhantomData<RegIdImpl>),
 }
 
+static POWERPC_ALTIVEC32_REGS: RegTable = reg_table!(meta::PPC32_ALTIVEC_REGS_XML, "");
+
 impl<RegIdImpl: RegId> Arch for PowerPcAltivec32<RegIdImpl> {
     type Usize = u32;
     type Registers = reg::PowerPcCommonRegs;
@@ -25,4 +31,35 @@ impl<RegIdImpl: RegId> Arch for PowerPcAltivec32<RegIdImpl> {
             r#"<target version="1.0"><architecture>powerpc:common</architecture><feature name="org.gnu.gdb.power.core"></feature><feature name="org.gnu.gdb.power.fpu"></feature><feature name="org.gnu.gdb.power.altivec"></feature></target>"#,
         )
     }
+
+    impl_reg_meta!(POWERPC_ALTIVEC32_REGS, meta::ppc_meta);
+}
+
+/// Implements `Arch` for 64-bit PowerPC + AltiVec SIMD + VSX (big-endian).
+pub enum PowerPc64Vsx {}
+
+const POWERPC64_VSX_XML: &str = concat!(
+    r#"<?xml version="1.0"?>"#,
+    r#"<!DOCTYPE target SYSTEM "gdb-target.dtd">"#,
+    r#"<target version="1.0">"#,
+    "<architecture>powerpc:common64</architecture>",
+    include_str!("power64-core.xml"), // feature "org.gnu.gdb.power.core"
+    include_str!("power-fpu.xml"),    // feature "org.gnu.gdb.power.fpu"
+    include_str!("power-altivec.xml"), // feature "org.gnu.gdb.power.altivec"
+    include_str!("power-vsx.xml"),    // feature "org.gnu.gdb.power.vsx"
+    "</target>",
+);
+static POWERPC64_VSX_REGS: RegTable = reg_table!(POWERPC64_VSX_XML, "");
+
+impl Arch for PowerPc64Vsx {
+    type Usize = u64;
+    type Registers = reg::PowerPc64VsxRegs;
+    type RegId = reg::id::PowerPcRegId<u64>;
+    type BreakpointKind = usize;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(POWERPC64_VSX_XML)
+    }
+
+    impl_reg_meta!(POWERPC64_VSX_REGS, meta::ppc_meta);
 }
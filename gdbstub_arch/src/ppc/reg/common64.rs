@@ -0,0 +1,195 @@
+use super::PpcVector;
+use core::convert::TryInto;
+use gdbstub::arch::Registers;
+
+/// 64-bit PowerPC core registers, FPU registers, AltiVec SIMD registers, and
+/// VSX registers.
+///
+/// The 64 VSX registers (`vs0`-`vs63`) are pseudo-registers synthesized by the
+/// GDB client: `vs0`-`vs31` are composed of `vsh` (upper 64 bits) and `f`
+/// (lower 64 bits), and `vs32`-`vs63` alias the AltiVec `vr` registers.
+///
+/// All registers are serialized in big-endian byte order.
+///
+/// Sources:
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/rs6000/powerpc-vsx64.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/rs6000/power64-core.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/rs6000/power-fpu.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/rs6000/power-altivec.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/rs6000/power-vsx.xml>
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PowerPc64VsxRegs {
+    /// General purpose registers
+    pub r: [u64; 32],
+    /// Floating Point registers
+    pub f: [f64; 32],
+    /// Program counter
+    pub pc: u64,
+    /// Machine state
+    pub msr: u64,
+    /// Condition register
+    pub cr: u32,
+    /// Link register
+    pub lr: u64,
+    /// Count register
+    pub ctr: u64,
+    /// Integer exception register
+    pub xer: u32,
+    /// Floating-point status and control register
+    pub fpscr: u32,
+    /// Vector registers
+    pub vr: [PpcVector; 32],
+    /// Vector status and control register
+    pub vscr: u32,
+    /// Vector context save register
+    pub vrsave: u32,
+    /// Upper 64 bits of VSX registers 0-31
+    pub vsh: [u64; 32],
+}
+
+impl Registers for PowerPc64VsxRegs {
+    type ProgramCounter = u64;
+
+    fn pc(&self) -> Self::ProgramCounter {
+        self.pc
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        macro_rules! write_bytes {
+            ($bytes:expr) => {
+                for b in $bytes {
+                    write_byte(Some(*b))
+                }
+            };
+        }
+
+        macro_rules! write_regs {
+            ($($reg:ident),*) => {
+                $(
+                    write_bytes!(&self.$reg.to_be_bytes());
+                )*
+            }
+        }
+
+        for reg in &self.r {
+            write_bytes!(&reg.to_be_bytes());
+        }
+
+        for reg in &self.f {
+            write_bytes!(&reg.to_be_bytes());
+        }
+
+        write_regs!(pc, msr, cr, lr, ctr, xer, fpscr);
+
+        for &reg in &self.vr {
+            let reg: u128 = reg;
+            write_bytes!(&reg.to_be_bytes());
+        }
+
+        write_regs!(vscr, vrsave);
+
+        for reg in &self.vsh {
+            write_bytes!(&reg.to_be_bytes());
+        }
+    }
+
+    fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        if bytes.len() < 0x534 {
+            return Err(());
+        }
+
+        let mut regs = bytes[0..0x100]
+            .chunks_exact(8)
+            .map(|x| u64::from_be_bytes(x.try_into().unwrap()));
+
+        for reg in &mut self.r {
+            *reg = regs.next().ok_or(())?;
+        }
+
+        let mut regs = bytes[0x100..0x200]
+            .chunks_exact(8)
+            .map(|x| f64::from_be_bytes(x.try_into().unwrap()));
+
+        for reg in &mut self.f {
+            *reg = regs.next().ok_or(())?;
+        }
+
+        macro_rules! parse_reg {
+            ($ty:ty, $off:expr) => {{
+                const SIZE: usize = core::mem::size_of::<$ty>();
+                let val = <$ty>::from_be_bytes(bytes[$off..$off + SIZE].try_into().unwrap());
+                $off += SIZE;
+                val
+            }};
+        }
+
+        let mut off = 0x200;
+        self.pc = parse_reg!(u64, off);
+        self.msr = parse_reg!(u64, off);
+        self.cr = parse_reg!(u32, off);
+        self.lr = parse_reg!(u64, off);
+        self.ctr = parse_reg!(u64, off);
+        self.xer = parse_reg!(u32, off);
+        self.fpscr = parse_reg!(u32, off);
+
+        let mut regs = bytes[off..off + 0x200]
+            .chunks_exact(0x10)
+            .map(|x| u128::from_be_bytes(x.try_into().unwrap()));
+
+        for reg in &mut self.vr {
+            *reg = regs.next().ok_or(())?;
+        }
+        off += 0x200;
+
+        self.vscr = parse_reg!(u32, off);
+        self.vrsave = parse_reg!(u32, off);
+
+        let mut regs = bytes[off..off + 0x100]
+            .chunks_exact(8)
+            .map(|x| u64::from_be_bytes(x.try_into().unwrap()));
+
+        for reg in &mut self.vsh {
+            *reg = regs.next().ok_or(())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ppc64_vsx_round_trip() {
+        let regs_before = PowerPc64VsxRegs {
+            r: [0x1111_2222_3333_4444; 32],
+            f: [9.0; 32],
+            pc: 0xc000_0000_0000_1000,
+            msr: 0x8000_0000_0000_1033,
+            cr: 4,
+            lr: 5,
+            ctr: 6,
+            xer: 7,
+            fpscr: 8,
+            vr: [52; 32],
+            vscr: 11,
+            vrsave: 10,
+            vsh: [0xdead_beef; 32],
+        };
+
+        let mut data = vec![];
+
+        regs_before.gdb_serialize(|x| {
+            data.push(x.unwrap_or(b'x'));
+        });
+
+        assert_eq!(data.len(), 0x534);
+
+        let mut regs_after = PowerPc64VsxRegs::default();
+        regs_after.gdb_deserialize(&data).unwrap();
+
+        assert_eq!(regs_before, regs_after);
+    }
+}
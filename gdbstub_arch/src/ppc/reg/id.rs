@@ -1,2 +1,111 @@
-// TODO: Add proper `RegId` implementation. See [issue #29](https://github.com/daniel5151/gdbstub/issues/29)
-// pub enum PowerPc32RegId {}
+use core::num::NonZeroUsize;
+use gdbstub::arch::RegId;
+
+/// PowerPC register identifier.
+///
+/// The width of the GPRs, `pc`, `msr`, `lr`, and `ctr` registers is set based
+/// on the `<U>` type. All other registers have a fixed width, regardless of
+/// the mode.
+///
+/// Sources:
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/rs6000/power-core.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/rs6000/power64-core.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/rs6000/power-fpu.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/rs6000/power-altivec.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/rs6000/power-vsx.xml>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum PowerPcRegId<U> {
+    /// General purpose registers (r0-r31)
+    Gpr(u8),
+    /// Floating Point registers (f0-f31)
+    Fpr(u8),
+    /// Program counter
+    Pc,
+    /// Machine state
+    Msr,
+    /// Condition register
+    Cr,
+    /// Link register
+    Lr,
+    /// Count register
+    Ctr,
+    /// Integer exception register
+    Xer,
+    /// Floating-point status and control register
+    Fpscr,
+    /// AltiVec vector registers (vr0-vr31)
+    Vr(u8),
+    /// Vector status and control register
+    Vscr,
+    /// Vector save/restore register
+    Vrsave,
+    /// Upper 64 bits of the first 32 VSX registers (vs0h-vs31h)
+    Vsh(u8),
+
+    #[doc(hidden)]
+    _Marker(core::marker::PhantomData<U>),
+}
+
+macro_rules! impl_powerpc_reg_id {
+    ($usize:ty) => {
+        impl RegId for PowerPcRegId<$usize> {
+            fn from_raw_id(id: usize) -> Option<(Self, Option<NonZeroUsize>)> {
+                let usize = core::mem::size_of::<$usize>();
+
+                let (reg, size) = match id {
+                    0..=31 => (Self::Gpr(id as u8), usize),
+                    32..=63 => (Self::Fpr((id - 32) as u8), 8),
+                    64 => (Self::Pc, usize),
+                    65 => (Self::Msr, usize),
+                    66 => (Self::Cr, 4),
+                    67 => (Self::Lr, usize),
+                    68 => (Self::Ctr, usize),
+                    69 => (Self::Xer, 4),
+                    70 => (Self::Fpscr, 4),
+                    71..=102 => (Self::Vr((id - 71) as u8), 16),
+                    103 => (Self::Vscr, 4),
+                    104 => (Self::Vrsave, 4),
+                    105..=136 => (Self::Vsh((id - 105) as u8), 8),
+                    _ => return None,
+                };
+
+                Some((reg, Some(NonZeroUsize::new(size)?)))
+            }
+
+            fn to_raw_id(&self) -> Option<usize> {
+                let id = match *self {
+                    Self::Gpr(n @ 0..=31) => n as usize,
+                    Self::Fpr(n @ 0..=31) => 32 + n as usize,
+                    Self::Pc => 64,
+                    Self::Msr => 65,
+                    Self::Cr => 66,
+                    Self::Lr => 67,
+                    Self::Ctr => 68,
+                    Self::Xer => 69,
+                    Self::Fpscr => 70,
+                    Self::Vr(n @ 0..=31) => 71 + n as usize,
+                    Self::Vscr => 103,
+                    Self::Vrsave => 104,
+                    Self::Vsh(n @ 0..=31) => 105 + n as usize,
+                    _ => return None,
+                };
+                Some(id)
+            }
+        }
+    };
+}
+
+impl_powerpc_reg_id!(u32);
+impl_powerpc_reg_id!(u64);
+
+#[cfg(test)]
+mod tests {
+    use gdbstub::arch::RegId;
+
+    #[test]
+    fn test_powerpc64_vsx_out_of_range() {
+        assert!(super::PowerPcRegId::<u64>::from_raw_id(137).is_none());
+    }
+}
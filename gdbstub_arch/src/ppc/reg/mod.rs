@@ -4,6 +4,8 @@
 pub mod id;
 
 mod common;
+mod common64;
 
 pub use common::PowerPcCommonRegs;
+pub use common64::PowerPc64VsxRegs;
 type PpcVector = u128;
@@ -0,0 +1,47 @@
+//! Register metadata for RISC-V.
+
+use crate::meta::reg_index;
+use crate::meta::RegMeta;
+use crate::xml_util::XmlReg;
+use gdbstub::arch::lldb::Generic;
+
+/// The `ustatus` CSR occupies regnum 65 (i.e: 65 + CSR number) in between the
+/// FPRs and the floating point CSRs, but is not part of the "g" packet.
+pub(crate) const RV32_FPU_GAPS_XML: &str =
+    r#"<reg name="ustatus" bitsize="32" type="int" regnum="65" group="system"/>"#;
+/// See [`RV32_FPU_GAPS_XML`].
+pub(crate) const RV64_FPU_GAPS_XML: &str =
+    r#"<reg name="ustatus" bitsize="64" type="int" regnum="65" group="system"/>"#;
+
+/// DWARF register numbers and generic registers, as per the RISC-V ELF psABI.
+///
+/// GPRs are described using their ABI names, with the architectural names
+/// (`x0`-`x31`) used as alternate names.
+pub(crate) fn riscv_meta(reg: &XmlReg<'_>) -> RegMeta {
+    const X_NAMES: [&str; 32] = [
+        "x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7", "x8", "x9", "x10", "x11", "x12", "x13",
+        "x14", "x15", "x16", "x17", "x18", "x19", "x20", "x21", "x22", "x23", "x24", "x25", "x26",
+        "x27", "x28", "x29", "x30", "x31",
+    ];
+
+    match reg.regnum {
+        // GPRs
+        n @ 0..=31 => {
+            let meta = RegMeta::dwarf(n).alt_name(X_NAMES[n]);
+            match n {
+                1 => meta.generic(Generic::Ra),
+                2 => meta.generic(Generic::Sp),
+                8 => meta.generic(Generic::Fp),
+                10..=17 => meta.arg(n - 10),
+                _ => meta,
+            }
+        }
+        32 => RegMeta::default().generic(Generic::Pc),
+        // FPRs
+        n @ 33..=64 => RegMeta::dwarf(n - 1).float(),
+        _ => match reg_index(reg.name, "v") {
+            Some(n) => RegMeta::dwarf(96 + n),
+            None => RegMeta::default(),
+        },
+    }
+}
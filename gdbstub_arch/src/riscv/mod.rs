@@ -1,17 +1,41 @@
 //! Implementations for the [RISC-V](https://riscv.org/) architecture.
 //!
-//! *Note*: currently only supports integer versions of the ISA.
+//! The [`Riscv32`] and [`Riscv64`] arches only support integer versions of
+//! the ISA. Floating-point (F / D extensions) and Control and Status Register
+//! support is provided by the `Riscv{32,64}{F,D}` family of arches.
+//!
+//! As the width of RISC-V vector registers (VLEN) is implementation-defined,
+//! there are no dedicated arches for the V extension. Instead, targets should
+//! use one of the floating-point arches, and implement the
+//! [`TargetDescriptionXmlOverride`] IDET using [`RiscvVectorXml`].
+//!
+//! [`TargetDescriptionXmlOverride`]:
+//!     gdbstub::target::ext::target_description_xml_override::TargetDescriptionXmlOverride
 
+use crate::meta::RegTable;
 use gdbstub::arch::Arch;
 
 pub mod reg;
 
+mod meta;
+mod vector;
+
+pub use vector::RiscvVectorBaseArch;
+pub use vector::RiscvVectorXml;
+
 /// Implements `Arch` for 32-bit RISC-V.
 pub enum Riscv32 {}
 
 /// Implements `Arch` for 64-bit RISC-V.
 pub enum Riscv64 {}
 
+const RISCV32_XML: &str = concat!(
+    r#"<?xml version="1.0"?>"#,
+    r#"<!DOCTYPE feature SYSTEM "gdb-target.dtd">"#,
+    include_str!("rv32i.xml"),
+);
+static RISCV32_REGS: RegTable = reg_table!(RISCV32_XML, "");
+
 impl Arch for Riscv32 {
     type Usize = u32;
     type Registers = reg::RiscvCoreRegs<u32>;
@@ -19,10 +43,19 @@ impl Arch for Riscv32 {
     type RegId = reg::id::RiscvRegId<u32>;
 
     fn target_description_xml() -> Option<&'static str> {
-        Some(include_str!("rv32i.xml"))
+        Some(RISCV32_XML)
     }
+
+    impl_reg_meta!(RISCV32_REGS, meta::riscv_meta);
 }
 
+const RISCV64_XML: &str = concat!(
+    r#"<?xml version="1.0"?>"#,
+    r#"<!DOCTYPE feature SYSTEM "gdb-target.dtd">"#,
+    include_str!("rv64i.xml"),
+);
+static RISCV64_REGS: RegTable = reg_table!(RISCV64_XML, "");
+
 impl Arch for Riscv64 {
     type Usize = u64;
     type Registers = reg::RiscvCoreRegs<u64>;
@@ -30,6 +63,109 @@ impl Arch for Riscv64 {
     type RegId = reg::id::RiscvRegId<u64>;
 
     fn target_description_xml() -> Option<&'static str> {
-        Some(include_str!("rv64i.xml"))
+        Some(RISCV64_XML)
+    }
+
+    impl_reg_meta!(RISCV64_REGS, meta::riscv_meta);
+}
+
+macro_rules! riscv_fpu_target_xml {
+    ($arch:literal, $core:literal, $fpu:literal, $csr:literal) => {
+        concat!(
+            r#"<?xml version="1.0"?>"#,
+            r#"<!DOCTYPE target SYSTEM "gdb-target.dtd">"#,
+            r#"<target version="1.0">"#,
+            "<architecture>",
+            $arch,
+            "</architecture>",
+            include_str!($core), // feature "org.gnu.gdb.riscv.cpu"
+            include_str!($fpu),  // feature "org.gnu.gdb.riscv.fpu"
+            include_str!($csr),  // feature "org.gnu.gdb.riscv.csr"
+            "</target>",
+        )
+    };
+}
+
+/// Implements `Arch` for 32-bit RISC-V + single-precision floating point (F
+/// extension) + Machine / Supervisor-level CSRs.
+pub enum Riscv32F {}
+
+/// Implements `Arch` for 32-bit RISC-V + double-precision floating point (D
+/// extension) + Machine / Supervisor-level CSRs.
+pub enum Riscv32D {}
+
+/// Implements `Arch` for 64-bit RISC-V + single-precision floating point (F
+/// extension) + Machine / Supervisor-level CSRs.
+pub enum Riscv64F {}
+
+/// Implements `Arch` for 64-bit RISC-V + double-precision floating point (D
+/// extension) + Machine / Supervisor-level CSRs.
+pub enum Riscv64D {}
+
+const RISCV32_F_XML: &str =
+    riscv_fpu_target_xml!("riscv:rv32", "rv32i.xml", "fpu32.xml", "csr32.xml");
+static RISCV32_F_REGS: RegTable = reg_table!(RISCV32_F_XML, meta::RV32_FPU_GAPS_XML);
+
+impl Arch for Riscv32F {
+    type Usize = u32;
+    type Registers = reg::RiscvCoreRegsWithFpu<u32, u32>;
+    type BreakpointKind = usize;
+    type RegId = reg::id::RiscvRegId<u32, u32>;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(RISCV32_F_XML)
+    }
+
+    impl_reg_meta!(RISCV32_F_REGS, meta::riscv_meta);
+}
+
+const RISCV32_D_XML: &str =
+    riscv_fpu_target_xml!("riscv:rv32", "rv32i.xml", "fpu64.xml", "csr32.xml");
+static RISCV32_D_REGS: RegTable = reg_table!(RISCV32_D_XML, meta::RV32_FPU_GAPS_XML);
+
+impl Arch for Riscv32D {
+    type Usize = u32;
+    type Registers = reg::RiscvCoreRegsWithFpu<u32, u64>;
+    type BreakpointKind = usize;
+    type RegId = reg::id::RiscvRegId<u32, u64>;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(RISCV32_D_XML)
     }
+
+    impl_reg_meta!(RISCV32_D_REGS, meta::riscv_meta);
+}
+
+const RISCV64_F_XML: &str =
+    riscv_fpu_target_xml!("riscv:rv64", "rv64i.xml", "fpu32.xml", "csr64.xml");
+static RISCV64_F_REGS: RegTable = reg_table!(RISCV64_F_XML, meta::RV64_FPU_GAPS_XML);
+
+impl Arch for Riscv64F {
+    type Usize = u64;
+    type Registers = reg::RiscvCoreRegsWithFpu<u64, u32>;
+    type BreakpointKind = usize;
+    type RegId = reg::id::RiscvRegId<u64, u32>;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(RISCV64_F_XML)
+    }
+
+    impl_reg_meta!(RISCV64_F_REGS, meta::riscv_meta);
+}
+
+const RISCV64_D_XML: &str =
+    riscv_fpu_target_xml!("riscv:rv64", "rv64i.xml", "fpu64.xml", "csr64.xml");
+static RISCV64_D_REGS: RegTable = reg_table!(RISCV64_D_XML, meta::RV64_FPU_GAPS_XML);
+
+impl Arch for Riscv64D {
+    type Usize = u64;
+    type Registers = reg::RiscvCoreRegsWithFpu<u64, u64>;
+    type BreakpointKind = usize;
+    type RegId = reg::id::RiscvRegId<u64, u64>;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(RISCV64_D_XML)
+    }
+
+    impl_reg_meta!(RISCV64_D_REGS, meta::riscv_meta);
 }
@@ -2,9 +2,20 @@ use core::num::NonZeroUsize;
 use gdbstub::arch::RegId;
 
 /// RISC-V Register identifier.
+///
+/// The integer register width is set based on the `<U>` type, and the
+/// floating-point register width is set based on the `<F>` type (which
+/// defaults to the integer register width).
+///
+/// The `fflags`, `frm`, and `fcsr` CSRs are always 32-bits wide, in accordance
+/// with the `org.gnu.gdb.riscv.fpu` target feature.
+///
+/// Vector registers are reported without a size, as the vector register length
+/// (VLEN) is only known at runtime.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
-pub enum RiscvRegId<U> {
+pub enum RiscvRegId<U, F = U> {
     /// General Purpose Register (x0-x31).
     Gpr(u8),
     /// Floating Point Register (f0-f31).
@@ -15,31 +26,155 @@ pub enum RiscvRegId<U> {
     Csr(u16),
     /// Privilege level.
     Priv,
+    /// Vector Register (v0-v31).
+    Vr(u8),
 
     #[doc(hidden)]
-    _Marker(core::marker::PhantomData<U>),
+    _Marker(core::marker::PhantomData<(U, F)>),
+}
+
+impl<U, F> RiscvRegId<U, F> {
+    /// Floating-Point Accrued Exceptions
+    pub const FFLAGS: Self = Self::Csr(0x001);
+    /// Floating-Point Dynamic Rounding Mode
+    pub const FRM: Self = Self::Csr(0x002);
+    /// Floating-Point Control and Status Register
+    pub const FCSR: Self = Self::Csr(0x003);
+    /// Vector start position
+    pub const VSTART: Self = Self::Csr(0x008);
+    /// Fixed-Point accrued saturation flag
+    pub const VXSAT: Self = Self::Csr(0x009);
+    /// Fixed-Point rounding mode
+    pub const VXRM: Self = Self::Csr(0x00a);
+    /// Vector control and status register
+    pub const VCSR: Self = Self::Csr(0x00f);
+    /// Vector length
+    pub const VL: Self = Self::Csr(0xc20);
+    /// Vector data type register
+    pub const VTYPE: Self = Self::Csr(0xc21);
+    /// VLEN/8 (vector register length in bytes)
+    pub const VLENB: Self = Self::Csr(0xc22);
+    /// Supervisor status register
+    pub const SSTATUS: Self = Self::Csr(0x100);
+    /// Supervisor interrupt-enable register
+    pub const SIE: Self = Self::Csr(0x104);
+    /// Supervisor trap handler base address
+    pub const STVEC: Self = Self::Csr(0x105);
+    /// Supervisor scratch register
+    pub const SSCRATCH: Self = Self::Csr(0x140);
+    /// Supervisor exception program counter
+    pub const SEPC: Self = Self::Csr(0x141);
+    /// Supervisor trap cause
+    pub const SCAUSE: Self = Self::Csr(0x142);
+    /// Supervisor bad address or instruction
+    pub const STVAL: Self = Self::Csr(0x143);
+    /// Supervisor interrupt pending
+    pub const SIP: Self = Self::Csr(0x144);
+    /// Supervisor address translation and protection
+    pub const SATP: Self = Self::Csr(0x180);
+    /// Machine status register
+    pub const MSTATUS: Self = Self::Csr(0x300);
+    /// ISA and extensions
+    pub const MISA: Self = Self::Csr(0x301);
+    /// Machine exception delegation register
+    pub const MEDELEG: Self = Self::Csr(0x302);
+    /// Machine interrupt delegation register
+    pub const MIDELEG: Self = Self::Csr(0x303);
+    /// Machine interrupt-enable register
+    pub const MIE: Self = Self::Csr(0x304);
+    /// Machine trap-handler base address
+    pub const MTVEC: Self = Self::Csr(0x305);
+    /// Machine scratch register
+    pub const MSCRATCH: Self = Self::Csr(0x340);
+    /// Machine exception program counter
+    pub const MEPC: Self = Self::Csr(0x341);
+    /// Machine trap cause
+    pub const MCAUSE: Self = Self::Csr(0x342);
+    /// Machine bad address or instruction
+    pub const MTVAL: Self = Self::Csr(0x343);
+    /// Machine interrupt pending
+    pub const MIP: Self = Self::Csr(0x344);
+    /// Hardware thread ID
+    pub const MHARTID: Self = Self::Csr(0xf14);
 }
 
 macro_rules! impl_riscv_reg_id {
-    ($usize:ty) => {
-        impl RegId for RiscvRegId<$usize> {
+    ($usize:ty, $fsize:ty) => {
+        impl RegId for RiscvRegId<$usize, $fsize> {
             fn from_raw_id(id: usize) -> Option<(Self, Option<NonZeroUsize>)> {
                 const USIZE: usize = core::mem::size_of::<$usize>();
+                const FSIZE: usize = core::mem::size_of::<$fsize>();
 
                 let (id, size) = match id {
                     0..=31 => (Self::Gpr(id as u8), USIZE),
                     32 => (Self::Pc, USIZE),
-                    33..=64 => (Self::Fpr((id - 33) as u8), USIZE),
+                    33..=64 => (Self::Fpr((id - 33) as u8), FSIZE),
+                    // fflags, frm, fcsr
+                    66..=68 => (Self::Csr((id - 65) as u16), 4),
                     65..=4160 => (Self::Csr((id - 65) as u16), USIZE),
                     4161 => (Self::Priv, 1),
+                    4162..=4193 => return Some((Self::Vr((id - 4162) as u8), None)),
                     _ => return None,
                 };
 
                 Some((id, Some(NonZeroUsize::new(size)?)))
             }
+
+            fn to_raw_id(&self) -> Option<usize> {
+                let id = match *self {
+                    Self::Gpr(n @ 0..=31) => n as usize,
+                    Self::Pc => 32,
+                    Self::Fpr(n @ 0..=31) => 33 + n as usize,
+                    Self::Csr(n @ 0..=4095) => 65 + n as usize,
+                    Self::Priv => 4161,
+                    Self::Vr(n @ 0..=31) => 4162 + n as usize,
+                    _ => return None,
+                };
+                Some(id)
+            }
         }
     };
 }
 
-impl_riscv_reg_id!(u32);
-impl_riscv_reg_id!(u64);
+impl_riscv_reg_id!(u32, u32);
+impl_riscv_reg_id!(u32, u64);
+impl_riscv_reg_id!(u64, u32);
+impl_riscv_reg_id!(u64, u64);
+
+#[cfg(test)]
+mod tests {
+    use gdbstub::arch::RegId;
+    use gdbstub::arch::Registers;
+
+    /// Compare the length of data written by `Registers::gdb_serialize()` with
+    /// the sum of sizes of all registers included in the "g" packet (i.e:
+    /// GPRs, PC, FPRs, and the `fflags`, `frm`, and `fcsr` CSRs).
+    fn test<U, F>()
+    where
+        crate::riscv::reg::RiscvCoreRegsWithFpu<U, F>: Registers,
+        super::RiscvRegId<U, F>: RegId,
+    {
+        let mut serialized_data_len = 0;
+        crate::riscv::reg::RiscvCoreRegsWithFpu::<U, F>::default().gdb_serialize(|b| {
+            if b.is_some() {
+                serialized_data_len += 1;
+            }
+        });
+
+        let mut sum_reg_sizes = 0;
+        for i in (0..=64).chain(66..=68) {
+            let (_, size) = super::RiscvRegId::<U, F>::from_raw_id(i).unwrap();
+            sum_reg_sizes += size.unwrap().get();
+        }
+
+        assert_eq!(serialized_data_len, sum_reg_sizes);
+    }
+
+    #[test]
+    fn test_riscv_fpu() {
+        test::<u32, u32>();
+        test::<u32, u64>();
+        test::<u64, u32>();
+        test::<u64, u64>();
+    }
+}
@@ -6,3 +6,5 @@ pub mod id;
 mod riscv;
 
 pub use riscv::RiscvCoreRegs;
+pub use riscv::RiscvCoreRegsWithFpu;
+pub use riscv::RiscvFpuRegs;
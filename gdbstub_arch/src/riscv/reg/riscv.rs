@@ -1,3 +1,4 @@
+use core::convert::TryInto;
 use gdbstub::arch::Registers;
 use gdbstub::internal::LeBytes;
 use num_traits::PrimInt;
@@ -10,6 +11,7 @@ use num_traits::PrimInt;
 /// * [GNU binutils-gdb XML descriptions](https://github.com/bminor/binutils-gdb/blob/master/gdb/features/riscv)
 /// * [riscv-tdep.h](https://github.com/bminor/binutils-gdb/blob/master/gdb/riscv-tdep.h)
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RiscvCoreRegs<U> {
     /// General purpose registers (x0-x31)
     pub x: [U; 32],
@@ -74,3 +76,213 @@ where
         Ok(())
     }
 }
+
+/// RISC-V Floating-point registers.
+///
+/// The register width is set to `u32` (F extension) or `u64` (D extension)
+/// based on the `<F>` type.
+///
+/// Source: <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/riscv/64bit-fpu.xml>
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RiscvFpuRegs<F> {
+    /// Floating-point registers (f0-f31)
+    pub f: [F; 32],
+    /// Floating-point Control and Status Register.
+    ///
+    /// The `fflags` (bits 4:0) and `frm` (bits 7:5) registers are views into
+    /// this register, and are serialized / deserialized accordingly.
+    ///
+    /// When deserializing a "G" packet, `fflags` or `frm` values which differ
+    /// from the corresponding bits of the current `fcsr` are treated as edits,
+    /// and are merged into the incoming `fcsr` value (with any bits outside
+    /// their respective bitfields ignored).
+    pub fcsr: u32,
+}
+
+/// RISC-V Integer + Floating-point registers.
+///
+/// The integer register width is set to `u32` or `u64` based on the `<U>`
+/// type, and the floating-point register width is set to `u32` (F extension)
+/// or `u64` (D extension) based on the `<F>` type.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RiscvCoreRegsWithFpu<U, F> {
+    /// Integer registers
+    pub core: RiscvCoreRegs<U>,
+    /// Floating-point registers
+    pub fpu: RiscvFpuRegs<F>,
+}
+
+impl<U, F> Registers for RiscvCoreRegsWithFpu<U, F>
+where
+    U: PrimInt + LeBytes + Default + core::fmt::Debug,
+    F: PrimInt + LeBytes + Default + core::fmt::Debug,
+{
+    type ProgramCounter = U;
+
+    fn pc(&self) -> Self::ProgramCounter {
+        self.core.pc
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        macro_rules! write_le_bytes {
+            ($value:expr) => {
+                let mut buf = [0; 16];
+                // infallible (unless digit is a >128 bit number)
+                let len = $value.to_le_bytes(&mut buf).unwrap();
+                let buf = &buf[..len];
+                for b in buf {
+                    write_byte(Some(*b));
+                }
+            };
+        }
+
+        self.core.gdb_serialize(&mut write_byte);
+
+        // FPRs are regnums 33-64
+        for reg in self.fpu.f.iter() {
+            write_le_bytes!(reg);
+        }
+
+        // fflags, frm, and fcsr are regnums 66-68 (i.e: 65 + CSR number)
+        let fcsr = self.fpu.fcsr;
+        for reg in [fcsr & 0x1f, (fcsr >> 5) & 0x7, fcsr] {
+            for b in reg.to_le_bytes() {
+                write_byte(Some(b));
+            }
+        }
+    }
+
+    fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        let ptrsize = core::mem::size_of::<U>();
+        let flen = core::mem::size_of::<F>();
+
+        let fpr_off = ptrsize * 33;
+        let fcsr_off = fpr_off + flen * 32;
+
+        if bytes.len() != fcsr_off + 4 * 3 {
+            return Err(());
+        }
+
+        self.core.gdb_deserialize(&bytes[..fpr_off])?;
+
+        let mut regs = bytes[fpr_off..fcsr_off]
+            .chunks_exact(flen)
+            .map(|c| F::from_le_bytes(c).unwrap());
+
+        for reg in self.fpu.f.iter_mut() {
+            *reg = regs.next().ok_or(())?
+        }
+
+        // `fcsr` is the source of truth. `fflags` and `frm` are views into it,
+        // so any edits made via those views must be merged back into it.
+        let mut regs = bytes[fcsr_off..]
+            .chunks_exact(4)
+            .map(|c| u32::from_le_bytes(c.try_into().unwrap()));
+
+        let fflags = regs.next().ok_or(())?;
+        let frm = regs.next().ok_or(())?;
+        let mut fcsr = regs.next().ok_or(())?;
+
+        for (val, shift, mask) in [(fflags & 0x1f, 0, 0x1f), (frm & 0x7, 5, 0x7)] {
+            if val != (self.fpu.fcsr >> shift) & mask {
+                fcsr = (fcsr & !(mask << shift)) | (val << shift);
+            }
+        }
+        self.fpu.fcsr = fcsr;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn riscv_fpu_round_trip() {
+        fn round_trip<U, F>(regs_before: RiscvCoreRegsWithFpu<U, F>, expected_len: usize)
+        where
+            U: PrimInt + LeBytes + Default + core::fmt::Debug,
+            F: PrimInt + LeBytes + Default + core::fmt::Debug,
+        {
+            let mut data = vec![];
+
+            regs_before.gdb_serialize(|x| {
+                data.push(x.unwrap_or(b'x'));
+            });
+
+            assert_eq!(data.len(), expected_len);
+
+            let mut regs_after = RiscvCoreRegsWithFpu::<U, F>::default();
+            regs_after.gdb_deserialize(&data).unwrap();
+
+            assert_eq!(regs_before, regs_after);
+        }
+
+        let mut regs = RiscvCoreRegsWithFpu::<u32, u64>::default();
+        for (i, x) in regs.core.x.iter_mut().enumerate() {
+            *x = i as u32;
+        }
+        regs.core.pc = 0x8000_0000;
+        for (i, f) in regs.fpu.f.iter_mut().enumerate() {
+            *f = 0x0101_0101_0101_0101 * i as u64;
+        }
+        regs.fpu.fcsr = 0b101_10011;
+        round_trip(regs, 33 * 4 + 32 * 8 + 3 * 4);
+
+        let mut regs = RiscvCoreRegsWithFpu::<u64, u32>::default();
+        regs.core.x[1] = 0xdead_beef_0000_0000;
+        regs.fpu.f[31] = 0x3f80_0000;
+        regs.fpu.fcsr = 0xff;
+        round_trip(regs, 33 * 8 + 32 * 4 + 3 * 4);
+    }
+
+    #[test]
+    fn riscv_fpu_fcsr_views() {
+        let regs = RiscvCoreRegsWithFpu::<u32, u32> {
+            fpu: RiscvFpuRegs {
+                fcsr: 0b010_00001,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let mut data = vec![];
+        regs.gdb_serialize(|x| data.push(x.unwrap()));
+        let fcsr_off = data.len() - 3 * 4;
+        let deserialize = |fflags: u32, frm: u32, fcsr: u32| {
+            let mut data = data.clone();
+            for (i, val) in [fflags, frm, fcsr].iter().enumerate() {
+                data[fcsr_off + i * 4..][..4].copy_from_slice(&val.to_le_bytes());
+            }
+            let mut regs = regs.clone();
+            regs.gdb_deserialize(&data).map(|()| regs.fpu.fcsr)
+        };
+
+        // unchanged
+        assert_eq!(deserialize(0b00001, 0b010, 0b010_00001), Ok(0b010_00001));
+        // edited via `fcsr`
+        assert_eq!(deserialize(0b00001, 0b010, 0b100_00010), Ok(0b100_00010));
+        // edited via `fflags` / `frm`
+        assert_eq!(deserialize(0b10000, 0b010, 0b010_00001), Ok(0b010_10000));
+        assert_eq!(deserialize(0b00001, 0b111, 0b010_00001), Ok(0b111_00001));
+        // bits outside of the views are ignored
+        assert_eq!(deserialize(0b100001, 0b1010, 0b010_00001), Ok(0b010_00001));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let mut regs = RiscvCoreRegsWithFpu::<u64, u32>::default();
+        regs.core.x[1] = 1;
+        regs.fpu.f[31] = 2;
+
+        let json = serde_json::to_string(&regs).unwrap();
+        assert_eq!(
+            serde_json::from_str::<RiscvCoreRegsWithFpu<u64, u32>>(&json).unwrap(),
+            regs
+        );
+    }
+}
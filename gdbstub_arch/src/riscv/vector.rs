@@ -0,0 +1,315 @@
+//! Runtime-generated target description XML for the RISC-V vector extension.
+
+use super::meta;
+use crate::meta::RegMeta;
+use crate::target_xml::sealed::RegSource;
+use crate::target_xml::TargetXml;
+use crate::xml_util::indexed_name;
+use crate::xml_util::write_features;
+use crate::xml_util::RegScanner;
+use crate::xml_util::XmlReg;
+use core::fmt;
+use core::ops::Range;
+use gdbstub::arch::tdesc::Field;
+use gdbstub::arch::tdesc::Reg;
+use gdbstub::arch::tdesc::RegLayout;
+use gdbstub::arch::tdesc::TdescError;
+use gdbstub::arch::tdesc::TdescWriter;
+use gdbstub::arch::tdesc::Type;
+use gdbstub::arch::Arch;
+
+mod sealed {
+    pub trait Sealed {}
+    impl Sealed for super::super::Riscv32F {}
+    impl Sealed for super::super::Riscv32D {}
+    impl Sealed for super::super::Riscv64F {}
+    impl Sealed for super::super::Riscv64D {}
+}
+
+const CSR_FEATURE: &str = r#"<feature name="org.gnu.gdb.riscv.csr">"#;
+
+/// RISC-V arches whose target description can be extended with vector
+/// registers via [`RiscvVectorXml`].
+///
+/// This trait is sealed, and is implemented for all floating-point enabled
+/// RISC-V arches (e.g: [`Riscv64D`](super::Riscv64D)).
+pub trait RiscvVectorBaseArch: Arch + sealed::Sealed {}
+
+impl<A: Arch + sealed::Sealed> RiscvVectorBaseArch for A {}
+
+/// Target description XML for RISC-V targets implementing the vector
+/// extension, with a runtime-specified vector register length (VLEN).
+///
+/// The generated XML contains all the features of the base arch `A`, with the
+/// vector CSRs (`vstart`, `vxsat`, `vxrm`, `vcsr`, `vl`, `vtype`, and `vlenb`)
+/// added to its `org.gnu.gdb.riscv.csr` feature, plus an
+/// `org.gnu.gdb.riscv.vector` feature with the vector registers (`v0`-`v31`).
+///
+/// Vector registers are not a part of the "g" packet, and must be accessed via
+/// [`RiscvRegId::Vr`](super::reg::id::RiscvRegId::Vr) (i.e: by implementing
+/// the `SingleRegisterAccess` IDET).
+///
+/// # Example
+///
+/// ```
+/// use gdbstub_arch::riscv::Riscv64D;
+/// use gdbstub_arch::riscv::RiscvVectorXml;
+/// use gdbstub_arch::target_xml::TargetXml;
+///
+/// // in `TargetDescriptionXmlOverride::target_description_xml`
+/// # let (annex, offset, length, mut buf) = (b"target.xml", 0, 0x1000, [0; 0x1000]);
+/// let xml = RiscvVectorXml::<Riscv64D>::new(256).unwrap();
+/// let n = match annex {
+///     b"target.xml" => xml.read_range(offset, length, &mut buf),
+///     _ => 0, // return Err(TargetError::NonFatal)
+/// };
+/// assert!(n > 0);
+/// ```
+pub struct RiscvVectorXml<A: RiscvVectorBaseArch> {
+    vlen: usize,
+    _arch: core::marker::PhantomData<A>,
+}
+
+impl<A: RiscvVectorBaseArch> RiscvVectorXml<A> {
+    /// Create a new target description with the specified vector register
+    /// length (in bits).
+    ///
+    /// Returns `None` if `vlen` is not a power of two in the range
+    /// `32..=65536`, as required by the RISC-V vector specification.
+    pub fn new(vlen: usize) -> Option<Self> {
+        if !vlen.is_power_of_two() || !(32..=65536).contains(&vlen) {
+            return None;
+        }
+
+        Some(RiscvVectorXml {
+            vlen,
+            _arch: core::marker::PhantomData,
+        })
+    }
+
+    /// The vector register length (in bits).
+    pub fn vlen(&self) -> usize {
+        self.vlen
+    }
+}
+
+impl<A: RiscvVectorBaseArch> TargetXml for RiscvVectorXml<A> {}
+
+impl<A: RiscvVectorBaseArch> RegSource for RiscvVectorXml<A> {
+    fn gaps_xml(&self) -> &'static str {
+        match core::mem::size_of::<A::Usize>() {
+            4 => meta::RV32_FPU_GAPS_XML,
+            _ => meta::RV64_FPU_GAPS_XML,
+        }
+    }
+
+    fn reg_meta(&self, reg: &XmlReg<'_>) -> RegMeta {
+        meta::riscv_meta(reg)
+    }
+}
+
+impl<A: RiscvVectorBaseArch> RiscvVectorXml<A> {
+    fn write_tdesc(&self, mut xml: TdescWriter<'_, '_>) -> Result<RegLayout, TdescError> {
+        let xlen = core::mem::size_of::<A::Usize>() * 8;
+        let vlenb = self.vlen / 8;
+
+        xml.architecture(match xlen {
+            32 => "riscv:rv32",
+            _ => "riscv:rv64",
+        })?;
+
+        // the base arch's features follow its `<architecture>` element
+        let base = A::target_description_xml().unwrap_or_default();
+        let features = base
+            .split_once("</architecture>")
+            .and_then(|(_, features)| features.strip_suffix("</target>"))
+            .unwrap_or_default();
+        let (fpu_features, csr_feature) =
+            features.split_at(features.find(CSR_FEATURE).unwrap_or(features.len()));
+
+        // features "org.gnu.gdb.riscv.cpu" and "org.gnu.gdb.riscv.fpu"
+        write_features(&mut xml, fpu_features, |_| true)?;
+        self.write_csr_feature(&mut xml, csr_feature, xlen)?;
+
+        xml.feature("org.gnu.gdb.riscv.vector")?;
+
+        // element types wider than VLEN are omitted
+        let elems = [
+            ("bytes", "uint8", 1),
+            ("shorts", "uint16", 2),
+            ("words", "uint32", 4),
+            ("longs", "uint64", 8),
+            ("quads", "uint128", 16),
+        ];
+        let num_elems = elems.iter().filter(|elem| vlenb >= elem.2).count();
+
+        for &(id, ty, elem_size) in &elems[..num_elems] {
+            xml.ty(Type::Vector {
+                id,
+                ty,
+                count: vlenb / elem_size,
+            })?;
+        }
+
+        let fields = [
+            Field::new("b", "bytes"),
+            Field::new("s", "shorts"),
+            Field::new("w", "words"),
+            Field::new("l", "longs"),
+            Field::new("q", "quads"),
+        ];
+        xml.ty(Type::Union {
+            id: "riscv_vector",
+            fields: &fields[..num_elems],
+        })?;
+
+        let mut name = [0; 4];
+        for i in 0..32 {
+            let reg = Reg::new(indexed_name(&mut name, "v", i), self.vlen)
+                .regnum(4162 + i)
+                .ty("riscv_vector")
+                .group("vector")
+                .exclude_from_g_packet();
+            xml.reg(reg)?;
+        }
+
+        xml.finish().map(|(_, layout)| layout)
+    }
+
+    /// Write the base arch's CSR feature, with the vector CSRs merged in
+    /// (ordered by their register numbers).
+    fn write_csr_feature(
+        &self,
+        xml: &mut TdescWriter<'_, '_>,
+        csr_feature: &str,
+        xlen: usize,
+    ) -> Result<(), TdescError> {
+        let mut vector_csrs = [
+            ("vstart", 0x008),
+            ("vxsat", 0x009),
+            ("vxrm", 0x00a),
+            ("vcsr", 0x00f),
+            ("vl", 0xc20),
+            ("vtype", 0xc21),
+            ("vlenb", 0xc22),
+        ]
+        .iter()
+        .map(|&(name, csr)| (name, 65 + csr))
+        .peekable();
+        let vector_csr =
+            |(name, regnum)| Reg::new(name, xlen).regnum(regnum).exclude_from_g_packet();
+
+        xml.feature("org.gnu.gdb.riscv.csr")?;
+
+        // CSRs are not a part of the "g" packet
+        let next_regnum = xml.layout().next_regnum();
+        let mut res = Ok(());
+        let mut scanner = RegScanner::new(|reg: &XmlReg<'_>, _: Range<usize>| {
+            while let Some(csr) = vector_csrs.next_if(|&(_, regnum)| regnum < reg.regnum) {
+                res = res.and_then(|()| xml.reg(vector_csr(csr)).map(drop));
+            }
+
+            let mut csr = Reg::new(reg.name, reg.bitsize)
+                .regnum(reg.regnum)
+                .exclude_from_g_packet();
+            if let Some(ty) = reg.ty {
+                csr = csr.ty(ty);
+            }
+            if let Some(group) = reg.group {
+                csr = csr.group(group);
+            }
+            res = res.and_then(|()| xml.reg(csr).map(drop));
+        })
+        .with_next_regnum(next_regnum);
+        scanner.scan(&csr_feature);
+        res?;
+
+        for csr in vector_csrs {
+            xml.reg(vector_csr(csr))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<A: RiscvVectorBaseArch> fmt::Display for RiscvVectorXml<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_tdesc(TdescWriter::target_fmt(f))
+            .map(drop)
+            .map_err(|_| fmt::Error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::riscv::Riscv32D;
+    use crate::riscv::Riscv32F;
+    use crate::riscv::Riscv64D;
+
+    #[test]
+    fn riscv_vector_xml() {
+        assert!(RiscvVectorXml::<Riscv64D>::new(100).is_none());
+        assert!(RiscvVectorXml::<Riscv64D>::new(16).is_none());
+
+        let base = Riscv64D::target_description_xml().unwrap();
+        let xml = RiscvVectorXml::<Riscv64D>::new(128).unwrap().to_string();
+        assert!(xml.starts_with(&base[..base.find(CSR_FEATURE).unwrap()]));
+        assert!(xml.ends_with("</feature></target>"));
+        assert!(xml.contains(r#"<reg name="v0" bitsize="128" regnum="4162""#));
+        assert!(xml.contains(r#"<reg name="v31" bitsize="128" regnum="4193""#));
+        assert!(xml.contains(r#"<vector id="quads" type="uint128" count="1"/>"#));
+
+        // vector CSRs are ordered by regnum within the CSR feature
+        assert!(xml.contains(concat!(
+            r#"<feature name="org.gnu.gdb.riscv.csr">"#,
+            r#"<reg name="vstart" bitsize="64" regnum="73"/>"#,
+        )));
+        assert!(xml.contains(concat!(
+            r#"<reg name="instret" bitsize="64" regnum="3139"/>"#,
+            r#"<reg name="vl" bitsize="64" regnum="3169"/>"#,
+            r#"<reg name="vtype" bitsize="64" regnum="3170"/>"#,
+            r#"<reg name="vlenb" bitsize="64" regnum="3171"/>"#,
+            r#"<reg name="mvendorid" bitsize="64" regnum="3922"/>"#,
+        )));
+
+        // element types wider than VLEN are omitted
+        let xml = RiscvVectorXml::<Riscv32F>::new(32).unwrap().to_string();
+        assert!(xml.contains(r#"<reg name="vl" bitsize="32" regnum="3169"/>"#));
+        assert!(!xml.contains("longs"));
+        assert!(!xml.contains("quads"));
+    }
+
+    #[test]
+    fn riscv_vector_xml_g_packet() {
+        let mut xml = String::new();
+        let layout = RiscvVectorXml::<Riscv32D>::new(64)
+            .unwrap()
+            .write_tdesc(TdescWriter::target_fmt(&mut xml))
+            .unwrap();
+        assert_eq!(
+            layout.check_registers(&<Riscv32D as Arch>::Registers::default()),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn riscv_vector_xml_read_range() {
+        let xml = RiscvVectorXml::<Riscv64D>::new(512).unwrap();
+        let expected = xml.to_string();
+
+        let mut buf = [0; 0x100];
+        let mut offset = 0;
+        let mut data = Vec::new();
+        loop {
+            let n = xml.read_range(offset, 0xff, &mut buf);
+            if n == 0 {
+                break;
+            }
+            data.extend_from_slice(&buf[..n]);
+            offset += n as u64;
+        }
+
+        assert_eq!(data, expected.as_bytes());
+    }
+}
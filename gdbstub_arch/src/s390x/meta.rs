@@ -0,0 +1,36 @@
+//! Register metadata for s390x.
+
+use crate::meta::reg_index;
+use crate::meta::RegMeta;
+use crate::xml_util::XmlReg;
+use gdbstub::arch::lldb::Generic;
+
+/// DWARF register numbers and generic registers, as per the s390x ELF ABI.
+pub(crate) fn s390x_meta(reg: &XmlReg<'_>) -> RegMeta {
+    // the FPRs are numbered in an interleaved order
+    const FPR_DWARF: [usize; 16] = [
+        16, 20, 17, 21, 18, 22, 19, 23, 24, 28, 25, 29, 26, 30, 27, 31,
+    ];
+
+    match reg.name {
+        "pswm" => RegMeta::dwarf(64).generic(Generic::Flags),
+        "pswa" => RegMeta::dwarf(65).generic(Generic::Pc).alt_name("pc"),
+        name => match (
+            reg_index(name, "r"),
+            reg_index(name, "acr"),
+            reg_index(name, "f"),
+        ) {
+            (Some(11), _, _) => RegMeta::dwarf(11).generic(Generic::Fp),
+            (Some(14), _, _) => RegMeta::dwarf(14).generic(Generic::Ra),
+            (Some(15), _, _) => RegMeta::dwarf(15).generic(Generic::Sp).alt_name("sp"),
+            (Some(n @ 2..=6), _, _) => RegMeta::dwarf(n).arg(n - 2),
+            (Some(n), _, _) => RegMeta::dwarf(n),
+            (_, Some(n), _) => RegMeta::dwarf(48 + n),
+            (_, _, Some(n)) => match FPR_DWARF.get(n) {
+                Some(&dwarf) => RegMeta::dwarf(dwarf),
+                None => RegMeta::default(),
+            },
+            _ => RegMeta::default(),
+        },
+    }
+}
@@ -0,0 +1,70 @@
+//! Implementations for the IBM [z/Architecture](https://en.wikipedia.org/wiki/Z/Architecture)
+//! (s390x) architecture.
+//!
+//! All registers are serialized in big-endian byte order.
+//!
+//! *Note*: doesn't support the 31-bit ESA/390 addressing mode, or the
+//! Linux-specific `org.gnu.gdb.s390.linux` feature.
+
+use crate::meta::RegTable;
+use gdbstub::arch::Arch;
+
+pub mod reg;
+
+mod meta;
+
+macro_rules! s390x_target_xml {
+    ($($feature:literal),*) => {
+        concat!(
+            r#"<?xml version="1.0"?>"#,
+            r#"<!DOCTYPE target SYSTEM "gdb-target.dtd">"#,
+            r#"<target version="1.0">"#,
+            "<architecture>s390:64-bit</architecture>",
+            include_str!("s390x-core64.xml"), // feature "org.gnu.gdb.s390.core"
+            include_str!("s390-acr.xml"),     // feature "org.gnu.gdb.s390.acr"
+            include_str!("s390-fpr.xml"),     // feature "org.gnu.gdb.s390.fpr"
+            $(include_str!($feature),)*
+            "</target>",
+        )
+    };
+}
+
+/// Implements `Arch` for 64-bit s390x.
+pub enum S390x {}
+
+/// Implements `Arch` for 64-bit s390x + vector facility.
+pub enum S390xVx {}
+
+const S390X_XML: &str = s390x_target_xml!();
+static S390X_REGS: RegTable = reg_table!(S390X_XML, "");
+
+impl Arch for S390x {
+    type Usize = u64;
+    type Registers = reg::S390xCoreRegs;
+    type RegId = reg::id::S390xRegId;
+    type BreakpointKind = usize;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(S390X_XML)
+    }
+
+    impl_reg_meta!(S390X_REGS, meta::s390x_meta);
+}
+
+const S390X_VX_XML: &str = s390x_target_xml!(
+    "s390-vx.xml" // feature "org.gnu.gdb.s390.vx"
+);
+static S390X_VX_REGS: RegTable = reg_table!(S390X_VX_XML, "");
+
+impl Arch for S390xVx {
+    type Usize = u64;
+    type Registers = reg::S390xVxRegs;
+    type RegId = reg::id::S390xRegId;
+    type BreakpointKind = usize;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(S390X_VX_XML)
+    }
+
+    impl_reg_meta!(S390X_VX_REGS, meta::s390x_meta);
+}
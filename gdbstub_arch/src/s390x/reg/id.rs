@@ -0,0 +1,74 @@
+use core::num::NonZeroUsize;
+use gdbstub::arch::RegId;
+
+/// s390x register identifier.
+///
+/// Sources:
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/s390x-core64.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/s390-acr.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/s390-fpr.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/s390-vx.xml>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum S390xRegId {
+    /// Program Status Word (mask)
+    Pswm,
+    /// Program Status Word (instruction address)
+    Pswa,
+    /// General purpose registers (r0-r15)
+    Gpr(u8),
+    /// Access registers (a0-a15)
+    Acr(u8),
+    /// Floating-point control register
+    Fpc,
+    /// Floating-point registers (f0-f15)
+    Fpr(u8),
+    /// Lower 64 bits of vector registers v0-v15
+    Vl(u8),
+    /// Vector registers v16-v31
+    V(u8),
+}
+
+impl RegId for S390xRegId {
+    fn from_raw_id(id: usize) -> Option<(Self, Option<NonZeroUsize>)> {
+        let (reg, size) = match id {
+            0 => (Self::Pswm, 8),
+            1 => (Self::Pswa, 8),
+            2..=17 => (Self::Gpr((id - 2) as u8), 8),
+            18..=33 => (Self::Acr((id - 18) as u8), 4),
+            34 => (Self::Fpc, 4),
+            35..=50 => (Self::Fpr((id - 35) as u8), 8),
+            51..=66 => (Self::Vl((id - 51) as u8), 8),
+            67..=82 => (Self::V((id - 67 + 16) as u8), 16),
+            _ => return None,
+        };
+
+        Some((reg, Some(NonZeroUsize::new(size)?)))
+    }
+
+    fn to_raw_id(&self) -> Option<usize> {
+        let id = match *self {
+            Self::Pswm => 0,
+            Self::Pswa => 1,
+            Self::Gpr(n @ 0..=15) => 2 + n as usize,
+            Self::Acr(n @ 0..=15) => 18 + n as usize,
+            Self::Fpc => 34,
+            Self::Fpr(n @ 0..=15) => 35 + n as usize,
+            Self::Vl(n @ 0..=15) => 51 + n as usize,
+            Self::V(n @ 16..=31) => 67 + (n - 16) as usize,
+            _ => return None,
+        };
+        Some(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gdbstub::arch::RegId;
+
+    #[test]
+    fn test_s390x_vx_out_of_range() {
+        assert!(super::S390xRegId::from_raw_id(83).is_none());
+    }
+}
@@ -0,0 +1,9 @@
+//! `Register` structs for s390x.
+
+/// `RegId` definitions for s390x.
+pub mod id;
+
+mod s390x;
+
+pub use s390x::S390xCoreRegs;
+pub use s390x::S390xVxRegs;
@@ -0,0 +1,247 @@
+use core::convert::TryInto;
+use gdbstub::arch::Registers;
+
+/// Size (in bytes) of the serialized `S390xCoreRegs`.
+const CORE_LEN: usize = 18 * 8 + 16 * 4 + 4 + 16 * 8;
+
+/// s390x core registers, access registers, and floating-point registers.
+///
+/// Sources:
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/s390x-core64.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/s390-acr.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/s390-fpr.xml>
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct S390xCoreRegs {
+    /// Program Status Word (mask)
+    pub pswm: u64,
+    /// Program Status Word (instruction address)
+    pub pswa: u64,
+    /// General purpose registers (r0-r15)
+    pub r: [u64; 16],
+    /// Access registers (a0-a15)
+    pub a: [u32; 16],
+    /// Floating-point control register
+    pub fpc: u32,
+    /// Floating-point registers (f0-f15), as raw bits
+    pub f: [u64; 16],
+}
+
+impl Registers for S390xCoreRegs {
+    type ProgramCounter = u64;
+
+    fn pc(&self) -> Self::ProgramCounter {
+        self.pswa
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        macro_rules! write_bytes {
+            ($bytes:expr) => {
+                for b in $bytes {
+                    write_byte(Some(*b))
+                }
+            };
+        }
+
+        write_bytes!(&self.pswm.to_be_bytes());
+        write_bytes!(&self.pswa.to_be_bytes());
+        for reg in &self.r {
+            write_bytes!(&reg.to_be_bytes());
+        }
+        for reg in &self.a {
+            write_bytes!(&reg.to_be_bytes());
+        }
+        write_bytes!(&self.fpc.to_be_bytes());
+        for reg in &self.f {
+            write_bytes!(&reg.to_be_bytes());
+        }
+    }
+
+    fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        if bytes.len() != CORE_LEN {
+            return Err(());
+        }
+
+        let mut regs = bytes[0..0x90]
+            .chunks_exact(8)
+            .map(|x| u64::from_be_bytes(x.try_into().unwrap()));
+
+        self.pswm = regs.next().ok_or(())?;
+        self.pswa = regs.next().ok_or(())?;
+        for reg in self.r.iter_mut() {
+            *reg = regs.next().ok_or(())?;
+        }
+
+        let mut regs = bytes[0x90..0xd4]
+            .chunks_exact(4)
+            .map(|x| u32::from_be_bytes(x.try_into().unwrap()));
+
+        for reg in self.a.iter_mut() {
+            *reg = regs.next().ok_or(())?;
+        }
+        self.fpc = regs.next().ok_or(())?;
+
+        let mut regs = bytes[0xd4..CORE_LEN]
+            .chunks_exact(8)
+            .map(|x| u64::from_be_bytes(x.try_into().unwrap()));
+
+        for reg in self.f.iter_mut() {
+            *reg = regs.next().ok_or(())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// s390x core registers + vector registers.
+///
+/// Vector registers `v0`-`v15` overlap with the floating-point registers: the
+/// upper 64 bits of `v{n}` are stored in `core.f[n]`, while the lower 64 bits
+/// are stored in `vl[n]`. See [`S390xVxRegs::v`] for a helper to reassemble
+/// the full vector register.
+///
+/// Source: <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/s390-vx.xml>
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct S390xVxRegs {
+    /// Core registers (+ access and floating-point registers)
+    pub core: S390xCoreRegs,
+    /// Lower 64 bits of vector registers v0-v15
+    pub vl: [u64; 16],
+    /// Vector registers v16-v31
+    pub v_hi: [u128; 16],
+}
+
+impl S390xVxRegs {
+    /// Returns the full value of vector register `v{n}` (0-31).
+    pub fn v(&self, n: usize) -> u128 {
+        match n {
+            0..=15 => (self.core.f[n] as u128) << 64 | self.vl[n] as u128,
+            _ => self.v_hi[n - 16],
+        }
+    }
+
+    /// Sets the full value of vector register `v{n}` (0-31).
+    pub fn set_v(&mut self, n: usize, val: u128) {
+        match n {
+            0..=15 => {
+                self.core.f[n] = (val >> 64) as u64;
+                self.vl[n] = val as u64;
+            }
+            _ => self.v_hi[n - 16] = val,
+        }
+    }
+}
+
+impl Registers for S390xVxRegs {
+    type ProgramCounter = u64;
+
+    fn pc(&self) -> Self::ProgramCounter {
+        self.core.pswa
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        macro_rules! write_bytes {
+            ($bytes:expr) => {
+                for b in $bytes {
+                    write_byte(Some(*b))
+                }
+            };
+        }
+
+        self.core.gdb_serialize(&mut write_byte);
+
+        for reg in &self.vl {
+            write_bytes!(&reg.to_be_bytes());
+        }
+        for reg in &self.v_hi {
+            write_bytes!(&reg.to_be_bytes());
+        }
+    }
+
+    fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        if bytes.len() != CORE_LEN + 0x180 {
+            return Err(());
+        }
+
+        self.core.gdb_deserialize(&bytes[..CORE_LEN])?;
+
+        let bytes = &bytes[CORE_LEN..];
+        let mut regs = bytes[0..0x80]
+            .chunks_exact(8)
+            .map(|x| u64::from_be_bytes(x.try_into().unwrap()));
+
+        for reg in self.vl.iter_mut() {
+            *reg = regs.next().ok_or(())?;
+        }
+
+        let mut regs = bytes[0x80..0x180]
+            .chunks_exact(0x10)
+            .map(|x| u128::from_be_bytes(x.try_into().unwrap()));
+
+        for reg in self.v_hi.iter_mut() {
+            *reg = regs.next().ok_or(())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<R: Registers>(regs_before: R, expected_len: usize) -> Vec<u8> {
+        let mut data = vec![];
+
+        regs_before.gdb_serialize(|x| {
+            data.push(x.unwrap_or(b'x'));
+        });
+
+        assert_eq!(data.len(), expected_len);
+
+        let mut regs_after = R::default();
+        regs_after.gdb_deserialize(&data).unwrap();
+
+        assert_eq!(regs_before, regs_after);
+
+        data
+    }
+
+    fn core_regs() -> S390xCoreRegs {
+        let mut regs = S390xCoreRegs {
+            pswm: 0x0705_0001_8000_0000,
+            pswa: 0x0000_0000_0001_0000,
+            fpc: 0x0800_0000,
+            ..Default::default()
+        };
+        for i in 0..16 {
+            regs.r[i] = 0x100 + i as u64;
+            regs.a[i] = 0x200 + i as u32;
+            regs.f[i] = (i as f64).to_bits();
+        }
+        regs
+    }
+
+    #[test]
+    fn s390x_core_big_endian() {
+        let data = round_trip(core_regs(), 0x154);
+        assert_eq!(&data[..8], &[0x07, 0x05, 0, 0x01, 0x80, 0, 0, 0]);
+        assert_eq!(&data[0x10..0x18], &[0, 0, 0, 0, 0, 0, 0x01, 0]);
+    }
+
+    #[test]
+    fn s390x_vx_round_trip() {
+        let mut regs = S390xVxRegs {
+            core: core_regs(),
+            ..Default::default()
+        };
+        for i in 0..32 {
+            regs.set_v(i, (i as u128) << 100 | 0x1234);
+        }
+        assert_eq!(regs.v(3), 3 << 100 | 0x1234);
+        assert_eq!(regs.v(20), 20 << 100 | 0x1234);
+
+        round_trip(regs, 0x154 + 0x180);
+    }
+}
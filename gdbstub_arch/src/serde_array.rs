@@ -0,0 +1,89 @@
+//! `serde` support for arrays with more than 32 elements, which `serde` does
+//! not implement `Serialize` / `Deserialize` for out of the box.
+//!
+//! Use via `#[serde(with = "crate::serde_array")]`.
+
+use core::fmt;
+use core::marker::PhantomData;
+use serde::de::Deserialize;
+use serde::de::Deserializer;
+use serde::de::Error;
+use serde::de::SeqAccess;
+use serde::de::Visitor;
+use serde::ser::Serialize;
+use serde::ser::SerializeTuple;
+use serde::ser::Serializer;
+
+pub(crate) fn serialize<S, T, const N: usize>(
+    arr: &[T; N],
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize,
+{
+    let mut tup = serializer.serialize_tuple(N)?;
+    for x in arr.iter() {
+        tup.serialize_element(x)?;
+    }
+    tup.end()
+}
+
+pub(crate) fn deserialize<'de, D, T, const N: usize>(deserializer: D) -> Result<[T; N], D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Default + Copy,
+{
+    struct ArrayVisitor<T, const N: usize>(PhantomData<T>);
+
+    impl<'de, T, const N: usize> Visitor<'de> for ArrayVisitor<T, N>
+    where
+        T: Deserialize<'de> + Default + Copy,
+    {
+        type Value = [T; N];
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "an array of length {}", N)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<[T; N], A::Error> {
+            let mut arr = [T::default(); N];
+            for (i, x) in arr.iter_mut().enumerate() {
+                *x = seq
+                    .next_element()?
+                    .ok_or_else(|| A::Error::invalid_length(i, &self))?;
+            }
+            Ok(arr)
+        }
+    }
+
+    deserializer.deserialize_tuple(N, ArrayVisitor(PhantomData))
+}
+
+#[cfg(test)]
+mod tests {
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Big {
+        #[serde(with = "crate::serde_array")]
+        arr: [u32; 40],
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut big = Big { arr: [0; 40] };
+        big.arr[39] = 0x1234;
+
+        let json = serde_json::to_string(&big).unwrap();
+        assert_eq!(serde_json::from_str::<Big>(&json).unwrap(), big);
+    }
+
+    #[test]
+    fn wrong_length() {
+        let json = serde_json::to_string(&Big { arr: [0; 40] }).unwrap();
+
+        let short = json.replacen("[0,", "[", 1);
+        assert!(serde_json::from_str::<Big>(&short).is_err());
+        let long = json.replacen("[0,", "[0,0,", 1);
+        assert!(serde_json::from_str::<Big>(&long).is_err());
+    }
+}
@@ -0,0 +1,80 @@
+//! Common functionality for target descriptions generated at runtime.
+
+use crate::meta::RegMeta;
+use crate::xml_util::XmlReg;
+use core::fmt;
+use gdbstub::arch::RegInfo;
+use gdbstub::target::ext::lldb_register_info_override::Callback;
+use gdbstub::target::ext::lldb_register_info_override::CallbackToken;
+
+pub(crate) mod sealed {
+    use super::*;
+
+    /// Per-type hooks backing the [`TargetXml`](super::TargetXml) methods.
+    pub trait RegSource {
+        /// Registers which aren't part of the target description, but are
+        /// required to fill in gaps in its register numbers when reporting
+        /// registers to LLDB (see [`crate::lldb::register_info`]).
+        fn gaps_xml(&self) -> &'static str {
+            ""
+        }
+
+        /// Register information which isn't part of the target description.
+        fn reg_meta(&self, reg: &XmlReg<'_>) -> RegMeta;
+    }
+}
+
+/// A target description XML document generated at runtime (via its `Display`
+/// implementation).
+///
+/// This trait is sealed, and is implemented by all runtime-generated target
+/// descriptions in this crate (e.g:
+/// [`AArch64SveXml`](crate::aarch64::AArch64SveXml)).
+pub trait TargetXml: fmt::Display + Sized + sealed::RegSource {
+    /// Copy the range `offset..offset + length` of the generated XML into
+    /// `buf`, returning the number of bytes written.
+    ///
+    /// This method's signature matches that of
+    /// [`TargetDescriptionXmlOverride::target_description_xml`], and returns
+    /// `0` if `offset` is past the end of the generated XML.
+    ///
+    /// [`TargetDescriptionXmlOverride::target_description_xml`]:
+    ///     gdbstub::target::ext::target_description_xml_override::TargetDescriptionXmlOverride::target_description_xml
+    fn read_range(&self, offset: u64, length: usize, buf: &mut [u8]) -> usize {
+        crate::xml_util::read_range(self, offset, length, buf)
+    }
+
+    /// Report the LLDB register info for the register `reg_id`, as described
+    /// by this target description.
+    ///
+    /// This method's signature matches that of
+    /// [`LldbRegisterInfoOverride::lldb_register_info`] (sans the error type).
+    ///
+    /// _Note:_ LLDB stops querying registers at the first unused register
+    /// number, so registers following a gap in the register numbers may not
+    /// be reported.
+    ///
+    /// [`LldbRegisterInfoOverride::lldb_register_info`]:
+    ///     gdbstub::target::ext::lldb_register_info_override::LldbRegisterInfoOverride::lldb_register_info
+    fn lldb_register_info<'a>(&self, reg_id: usize, reg_info: Callback<'a>) -> CallbackToken<'a> {
+        crate::lldb::register_info_dyn(
+            self,
+            self.gaps_xml(),
+            reg_id,
+            |reg| self.reg_meta(reg),
+            reg_info,
+        )
+    }
+
+    /// Invoke `f` with the [`RegInfo`] of each register described by this
+    /// target description, in the order they appear in the XML.
+    ///
+    /// This is the runtime equivalent of [`Arch::reg_info`], and can be used
+    /// alongside [`RegId::from_raw_id`] to resolve registers by name.
+    ///
+    /// [`Arch::reg_info`]: gdbstub::arch::Arch::reg_info
+    /// [`RegId::from_raw_id`]: gdbstub::arch::RegId::from_raw_id
+    fn for_each_reg_info(&self, f: impl FnMut(RegInfo<'_>)) {
+        crate::meta::for_each_reg_info(self, |reg| self.reg_meta(reg), f)
+    }
+}
@@ -0,0 +1,16 @@
+//! Register metadata for WebAssembly.
+
+use crate::meta::RegMeta;
+use crate::xml_util::XmlReg;
+use gdbstub::arch::lldb::Generic;
+
+/// Registers in the Wasm "g" packet.
+pub(crate) const WASM_REGS_XML: &str = regs_xml!(64, "code_ptr": "pc");
+
+/// Generic registers for Wasm (which only exposes a program counter).
+pub(crate) fn wasm_meta(reg: &XmlReg<'_>) -> RegMeta {
+    match reg.name {
+        "pc" => RegMeta::default().generic(Generic::Pc),
+        _ => RegMeta::default(),
+    }
+}
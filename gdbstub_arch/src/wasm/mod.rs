@@ -63,14 +63,19 @@
 //! [this PR]: https://github.com/WebAssembly/wasi-sdk/pull/596
 //! [Wasmtime]: https://github.com/bytecodealliance/wasmtime
 
+use crate::meta::RegTable;
 use gdbstub::arch::Arch;
 
 pub mod addr;
 pub mod reg;
 
+mod meta;
+
 /// Implements `Arch` for the WebAssembly architecture.
 pub enum Wasm {}
 
+static WASM_REGS: RegTable = reg_table!(meta::WASM_REGS_XML, "");
+
 impl Arch for Wasm {
     /// Even though Wasm is nominally a 32-bit platform, LLDB's GDB RSP
     /// extensions for Wasm uses a 64-bit address word to multiplex module
@@ -79,4 +84,6 @@ impl Arch for Wasm {
     type Registers = reg::WasmRegisters;
     type RegId = reg::id::WasmRegId;
     type BreakpointKind = usize;
+
+    impl_reg_meta!(WASM_REGS, meta::wasm_meta);
 }
@@ -3,6 +3,7 @@ use gdbstub::arch::RegId;
 
 /// The only register exposed to GDB: `pc` (register index 0).
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum WasmRegId {
     /// Program Counter.
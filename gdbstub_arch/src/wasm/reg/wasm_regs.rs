@@ -3,6 +3,7 @@ use gdbstub::arch::Registers;
 
 /// The register state for WebAssembly.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WasmRegisters {
     /// Program Counter. See [`crate::wasm::addr`] for the 64-bit
     /// synthetic address space in which this PC exists.
@@ -0,0 +1,98 @@
+//! Register metadata for x86 / x86-64.
+
+use crate::meta::reg_index;
+use crate::meta::RegMeta;
+use crate::xml_util::XmlReg;
+use gdbstub::arch::lldb::Generic;
+
+/// Registers in the 32-bit x86 "g" packet, as described by GDB's
+/// `32bit-core.xml` and `32bit-sse.xml` features.
+pub(crate) const X86_REGS_XML: &str = concat!(
+    regs_xml!(32, "int32": "eax", "ecx", "edx", "ebx"),
+    regs_xml!(32, "data_ptr": "esp", "ebp"),
+    regs_xml!(32, "int32": "esi", "edi"),
+    regs_xml!(32, "code_ptr": "eip"),
+    regs_xml!(32, "i386_eflags": "eflags"),
+    regs_xml!(32, "int32": "cs", "ss", "ds", "es", "fs", "gs"),
+    regs_xml!(80, "i387_ext": "st0", "st1", "st2", "st3", "st4", "st5", "st6", "st7"),
+    regs_xml!(32, "int", "float": "fctrl", "fstat", "ftag", "fiseg", "fioff", "foseg", "fooff", "fop"),
+    regs_xml!(128, "vec128": "xmm0", "xmm1", "xmm2", "xmm3", "xmm4", "xmm5", "xmm6", "xmm7"),
+    regs_xml!(32, "i386_mxcsr", "vector": "mxcsr"),
+);
+
+/// DWARF register numbers and generic registers for x86-64, as per the
+/// System V AMD64 psABI.
+pub(crate) fn x86_64_meta(reg: &XmlReg<'_>) -> RegMeta {
+    match reg.name {
+        "rax" => RegMeta::dwarf(0),
+        "rdx" => RegMeta::dwarf(1).arg(2),
+        "rcx" => RegMeta::dwarf(2).arg(3),
+        "rbx" => RegMeta::dwarf(3),
+        "rsi" => RegMeta::dwarf(4).arg(1),
+        "rdi" => RegMeta::dwarf(5).arg(0),
+        "rbp" => RegMeta::dwarf(6).generic(Generic::Fp).alt_name("fp"),
+        "rsp" => RegMeta::dwarf(7).generic(Generic::Sp).alt_name("sp"),
+        "r8" => RegMeta::dwarf(8).arg(4),
+        "r9" => RegMeta::dwarf(9).arg(5),
+        "rip" => RegMeta::dwarf(16).generic(Generic::Pc).alt_name("pc"),
+        "eflags" => RegMeta::dwarf(49).generic(Generic::Flags).alt_name("flags"),
+        "es" => RegMeta::dwarf(50),
+        "cs" => RegMeta::dwarf(51),
+        "ss" => RegMeta::dwarf(52),
+        "ds" => RegMeta::dwarf(53),
+        "fs" => RegMeta::dwarf(54),
+        "gs" => RegMeta::dwarf(55),
+        "fs_base" => RegMeta::dwarf(58),
+        "gs_base" => RegMeta::dwarf(59),
+        "mxcsr" => RegMeta::dwarf(64),
+        "fctrl" => RegMeta::dwarf(65),
+        "fstat" => RegMeta::dwarf(66),
+        name => {
+            let dwarf = if let Some(n @ 10..=15) = reg_index(name, "r") {
+                n
+            } else if let Some(n) = reg_index(name, "xmm") {
+                if n < 16 {
+                    17 + n
+                } else {
+                    67 + (n - 16)
+                }
+            } else if let Some(n) = reg_index(name, "st") {
+                33 + n
+            } else if let Some(n) = reg_index(name, "k") {
+                118 + n
+            } else {
+                return RegMeta::default();
+            };
+            RegMeta::dwarf(dwarf)
+        }
+    }
+}
+
+/// DWARF register numbers and generic registers for 32-bit x86, as per the
+/// System V i386 psABI.
+pub(crate) fn x86_meta(reg: &XmlReg<'_>) -> RegMeta {
+    match reg.name {
+        "eax" => RegMeta::dwarf(0),
+        "ecx" => RegMeta::dwarf(1),
+        "edx" => RegMeta::dwarf(2),
+        "ebx" => RegMeta::dwarf(3),
+        "esp" => RegMeta::dwarf(4).generic(Generic::Sp).alt_name("sp"),
+        "ebp" => RegMeta::dwarf(5).generic(Generic::Fp).alt_name("fp"),
+        "esi" => RegMeta::dwarf(6),
+        "edi" => RegMeta::dwarf(7),
+        "eip" => RegMeta::dwarf(8).generic(Generic::Pc).alt_name("pc"),
+        "eflags" => RegMeta::dwarf(9).generic(Generic::Flags).alt_name("flags"),
+        "mxcsr" => RegMeta::dwarf(39),
+        "es" => RegMeta::dwarf(40),
+        "cs" => RegMeta::dwarf(41),
+        "ss" => RegMeta::dwarf(42),
+        "ds" => RegMeta::dwarf(43),
+        "fs" => RegMeta::dwarf(44),
+        "gs" => RegMeta::dwarf(45),
+        name => match (reg_index(name, "st"), reg_index(name, "xmm")) {
+            (Some(n), _) => RegMeta::dwarf(11 + n),
+            (_, Some(n)) => RegMeta::dwarf(21 + n),
+            _ => RegMeta::default(),
+        },
+    }
+}
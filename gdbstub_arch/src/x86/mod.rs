@@ -1,13 +1,35 @@
 //! Implementations for various x86 architectures.
 
+use crate::meta::RegTable;
 use gdbstub::arch::Arch;
 
 pub mod reg;
 
+mod meta;
+
+macro_rules! x86_64_target_xml {
+    ($osabi:literal, $($feature:literal),*) => {
+        concat!(
+            r#"<?xml version="1.0"?>"#,
+            r#"<!DOCTYPE target SYSTEM "gdb-target.dtd">"#,
+            r#"<target version="1.0">"#,
+            "<architecture>i386:x86-64</architecture>",
+            $osabi,
+            $(include_str!($feature),)*
+            "</target>",
+        )
+    };
+}
+
 /// Implements `Arch` for 64-bit x86 + SSE Extensions.
 #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
 pub enum X86_64_SSE {}
 
+static X86_64_SSE_REGS: RegTable = reg_table!(
+    x86_64_target_xml!("", "64bit-core.xml", "64bit-sse.xml"),
+    ""
+);
+
 impl Arch for X86_64_SSE {
     type Usize = u64;
     type Registers = reg::X86_64CoreRegs;
@@ -19,12 +41,16 @@ impl Arch for X86_64_SSE {
             r#"<target version="1.0"><architecture>i386:x86-64</architecture><feature name="org.gnu.gdb.i386.sse"></feature></target>"#,
         )
     }
+
+    impl_reg_meta!(X86_64_SSE_REGS, meta::x86_64_meta);
 }
 
 /// Implements `Arch` for 32-bit x86 + SSE Extensions.
 #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
 pub enum X86_SSE {}
 
+static X86_SSE_REGS: RegTable = reg_table!(meta::X86_REGS_XML, "");
+
 impl Arch for X86_SSE {
     type Usize = u32;
     type Registers = reg::X86CoreRegs;
@@ -36,4 +62,189 @@ impl Arch for X86_SSE {
             r#"<target version="1.0"><architecture>i386:intel</architecture><feature name="org.gnu.gdb.i386.sse"></feature></target>"#,
         )
     }
+
+    impl_reg_meta!(X86_SSE_REGS, meta::x86_meta);
+}
+
+/// Implements `Arch` for 64-bit x86 + SSE + AVX Extensions, including the
+/// `fs_base` / `gs_base` segment base registers.
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+pub enum X86_64_AVX {}
+
+const X86_64_AVX_XML: &str = x86_64_target_xml!(
+    "",
+    "64bit-core.xml",
+    "64bit-sse.xml",
+    "64bit-segments.xml",
+    "64bit-avx.xml"
+);
+static X86_64_AVX_REGS: RegTable = reg_table!(X86_64_AVX_XML, "");
+
+impl Arch for X86_64_AVX {
+    type Usize = u64;
+    type Registers = reg::X86_64AvxRegs;
+    type RegId = reg::id::X86_64CoreRegId;
+    type BreakpointKind = usize;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(X86_64_AVX_XML)
+    }
+
+    impl_reg_meta!(X86_64_AVX_REGS, meta::x86_64_meta);
+}
+
+/// Implements `Arch` for 64-bit x86 + SSE + AVX + AVX-512 Extensions,
+/// including the `fs_base` / `gs_base` segment base registers.
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+pub enum X86_64_AVX512 {}
+
+const X86_64_AVX512_XML: &str = x86_64_target_xml!(
+    "",
+    "64bit-core.xml",
+    "64bit-sse.xml",
+    "64bit-segments.xml",
+    "64bit-avx.xml",
+    "64bit-avx512.xml"
+);
+static X86_64_AVX512_REGS: RegTable = reg_table!(X86_64_AVX512_XML, "");
+
+impl Arch for X86_64_AVX512 {
+    type Usize = u64;
+    type Registers = reg::X86_64Avx512Regs;
+    type RegId = reg::id::X86_64CoreRegId;
+    type BreakpointKind = usize;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(X86_64_AVX512_XML)
+    }
+
+    impl_reg_meta!(X86_64_AVX512_REGS, meta::x86_64_meta);
+}
+
+/// Implements `Arch` for 64-bit x86 Linux userspace processes (SSE + AVX
+/// Extensions, segment base registers, and `orig_rax`).
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+pub enum X86_64_Linux_AVX {}
+
+const X86_64_LINUX_AVX_XML: &str = x86_64_target_xml!(
+    "<osabi>GNU/Linux</osabi>",
+    "64bit-core.xml",
+    "64bit-sse.xml",
+    "64bit-segments.xml",
+    "64bit-avx.xml",
+    "64bit-linux.xml"
+);
+static X86_64_LINUX_AVX_REGS: RegTable = reg_table!(X86_64_LINUX_AVX_XML, "");
+
+impl Arch for X86_64_Linux_AVX {
+    type Usize = u64;
+    type Registers = reg::X86_64LinuxRegs<reg::X86_64AvxRegs>;
+    type RegId = reg::id::X86_64CoreRegId;
+    type BreakpointKind = usize;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(X86_64_LINUX_AVX_XML)
+    }
+
+    impl_reg_meta!(X86_64_LINUX_AVX_REGS, meta::x86_64_meta);
+}
+
+/// Implements `Arch` for 64-bit x86 Linux userspace processes (SSE + AVX +
+/// AVX-512 Extensions, segment base registers, and `orig_rax`).
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+pub enum X86_64_Linux_AVX512 {}
+
+const X86_64_LINUX_AVX512_XML: &str = x86_64_target_xml!(
+    "<osabi>GNU/Linux</osabi>",
+    "64bit-core.xml",
+    "64bit-sse.xml",
+    "64bit-segments.xml",
+    "64bit-avx.xml",
+    "64bit-avx512.xml",
+    "64bit-linux.xml"
+);
+static X86_64_LINUX_AVX512_REGS: RegTable = reg_table!(X86_64_LINUX_AVX512_XML, "");
+
+impl Arch for X86_64_Linux_AVX512 {
+    type Usize = u64;
+    type Registers = reg::X86_64LinuxRegs<reg::X86_64Avx512Regs>;
+    type RegId = reg::id::X86_64CoreRegId;
+    type BreakpointKind = usize;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(X86_64_LINUX_AVX512_XML)
+    }
+
+    impl_reg_meta!(X86_64_LINUX_AVX512_REGS, meta::x86_64_meta);
+}
+
+/// Implements `Arch` for 64-bit x86 + SSE + AVX Extensions + system
+/// registers, for debugging kernels / hypervisor guests.
+///
+/// In addition to the registers provided by [`X86_64_AVX`], this arch
+/// advertises the custom `org.rust.gdb.i386.sys` target feature, which exposes
+/// the control registers (`cr0`, `cr2`, `cr3`, `cr4`, `cr8`), `efer`, the
+/// GDTR / IDTR bases and limits, and a selection of MSRs (e.g: `lstar`,
+/// `kernel_gs_base`). See [`reg::id::X86_64SysRegId`] for the full list.
+///
+/// These registers are not included in the "g" packet. Instead, they are
+/// accessed individually via the
+/// [`SingleRegisterAccess`](gdbstub::target::ext::base::single_register_access::SingleRegisterAccess)
+/// IDET, using the [`reg::id::X86_64CoreRegId::Sys`] register identifiers
+/// (e.g: `p/x $cr3`).
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+pub enum X86_64_AVX_System {}
+
+const X86_64_AVX_SYSTEM_XML: &str = x86_64_target_xml!(
+    "",
+    "64bit-core.xml",
+    "64bit-sse.xml",
+    "64bit-segments.xml",
+    "64bit-avx.xml",
+    "64bit-sys.xml"
+);
+static X86_64_AVX_SYSTEM_REGS: RegTable = reg_table!(X86_64_AVX_SYSTEM_XML, "");
+
+impl Arch for X86_64_AVX_System {
+    type Usize = u64;
+    type Registers = reg::X86_64AvxRegs;
+    type RegId = reg::id::X86_64CoreRegId;
+    type BreakpointKind = usize;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(X86_64_AVX_SYSTEM_XML)
+    }
+
+    impl_reg_meta!(X86_64_AVX_SYSTEM_REGS, meta::x86_64_meta);
+}
+
+/// Implements `Arch` for 64-bit x86 + SSE + AVX + AVX-512 Extensions + system
+/// registers, for debugging kernels / hypervisor guests.
+///
+/// See [`X86_64_AVX_System`] for details on the system registers.
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+pub enum X86_64_AVX512_System {}
+
+const X86_64_AVX512_SYSTEM_XML: &str = x86_64_target_xml!(
+    "",
+    "64bit-core.xml",
+    "64bit-sse.xml",
+    "64bit-segments.xml",
+    "64bit-avx.xml",
+    "64bit-avx512.xml",
+    "64bit-sys.xml"
+);
+static X86_64_AVX512_SYSTEM_REGS: RegTable = reg_table!(X86_64_AVX512_SYSTEM_XML, "");
+
+impl Arch for X86_64_AVX512_System {
+    type Usize = u64;
+    type Registers = reg::X86_64Avx512Regs;
+    type RegId = reg::id::X86_64CoreRegId;
+    type BreakpointKind = usize;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(X86_64_AVX512_SYSTEM_XML)
+    }
+
+    impl_reg_meta!(X86_64_AVX512_SYSTEM_REGS, meta::x86_64_meta);
 }
@@ -0,0 +1,304 @@
+use super::X86_64CoreRegs;
+use core::convert::TryInto;
+use gdbstub::arch::Registers;
+
+/// Size (in bytes) of the serialized `X86_64CoreRegs`.
+const CORE_LEN: usize = 0x218;
+
+/// 64-bit x86 core registers + segment base registers + AVX extensions.
+///
+/// Sources:
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/i386/64bit-segments.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/i386/64bit-avx.xml>
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct X86_64AvxRegs {
+    /// Core registers (+ SSE extensions)
+    pub core: X86_64CoreRegs,
+    /// FS segment base address
+    pub fs_base: u64,
+    /// GS segment base address
+    pub gs_base: u64,
+    /// Upper 128 bits of YMM0 through YMM15
+    pub ymmh: [u128; 16],
+}
+
+impl Registers for X86_64AvxRegs {
+    type ProgramCounter = u64;
+
+    fn pc(&self) -> Self::ProgramCounter {
+        self.core.rip
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        macro_rules! write_bytes {
+            ($bytes:expr) => {
+                for b in $bytes {
+                    write_byte(Some(*b))
+                }
+            };
+        }
+
+        self.core.gdb_serialize(&mut write_byte);
+
+        write_bytes!(&self.fs_base.to_le_bytes());
+        write_bytes!(&self.gs_base.to_le_bytes());
+
+        for reg in &self.ymmh {
+            write_bytes!(&reg.to_le_bytes());
+        }
+    }
+
+    fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        if bytes.len() != CORE_LEN + 0x110 {
+            return Err(());
+        }
+
+        self.core.gdb_deserialize(&bytes[..CORE_LEN])?;
+
+        let bytes = &bytes[CORE_LEN..];
+        self.fs_base = u64::from_le_bytes(bytes[0x0..0x8].try_into().unwrap());
+        self.gs_base = u64::from_le_bytes(bytes[0x8..0x10].try_into().unwrap());
+
+        let mut regs = bytes[0x10..0x110]
+            .chunks_exact(0x10)
+            .map(|x| u128::from_le_bytes(x.try_into().unwrap()));
+
+        for reg in self.ymmh.iter_mut() {
+            *reg = regs.next().ok_or(())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// 64-bit x86 core registers + segment base registers + AVX and AVX-512
+/// extensions.
+///
+/// Sources:
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/i386/64bit-segments.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/i386/64bit-avx.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/i386/64bit-avx512.xml>
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct X86_64Avx512Regs {
+    /// Core registers (+ SSE extensions)
+    pub core: X86_64CoreRegs,
+    /// FS segment base address
+    pub fs_base: u64,
+    /// GS segment base address
+    pub gs_base: u64,
+    /// SIMD Registers: XMM16 through XMM31
+    pub xmm_hi: [u128; 16],
+    /// Upper 128 bits of YMM0 through YMM31
+    pub ymmh: [u128; 32],
+    /// Opmask Registers: K0 through K7
+    pub k: [u64; 8],
+    /// Upper 256 bits of ZMM0 through ZMM31 (as `[bits 383:256, bits
+    /// 511:384]`)
+    pub zmmh: [[u128; 2]; 32],
+}
+
+impl Registers for X86_64Avx512Regs {
+    type ProgramCounter = u64;
+
+    fn pc(&self) -> Self::ProgramCounter {
+        self.core.rip
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        macro_rules! write_bytes {
+            ($bytes:expr) => {
+                for b in $bytes {
+                    write_byte(Some(*b))
+                }
+            };
+        }
+
+        self.core.gdb_serialize(&mut write_byte);
+
+        write_bytes!(&self.fs_base.to_le_bytes());
+        write_bytes!(&self.gs_base.to_le_bytes());
+
+        // ymm0h to ymm15h (org.gnu.gdb.i386.avx)
+        for reg in &self.ymmh[..16] {
+            write_bytes!(&reg.to_le_bytes());
+        }
+
+        // xmm16 to xmm31, ymm16h to ymm31h (org.gnu.gdb.i386.avx512)
+        for reg in &self.xmm_hi {
+            write_bytes!(&reg.to_le_bytes());
+        }
+        for reg in &self.ymmh[16..] {
+            write_bytes!(&reg.to_le_bytes());
+        }
+
+        // k0 to k7
+        for reg in &self.k {
+            write_bytes!(&reg.to_le_bytes());
+        }
+
+        // zmm0h to zmm31h
+        for reg in &self.zmmh {
+            write_bytes!(&reg[0].to_le_bytes());
+            write_bytes!(&reg[1].to_le_bytes());
+        }
+    }
+
+    fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        if bytes.len() != CORE_LEN + 0x750 {
+            return Err(());
+        }
+
+        self.core.gdb_deserialize(&bytes[..CORE_LEN])?;
+
+        let bytes = &bytes[CORE_LEN..];
+        self.fs_base = u64::from_le_bytes(bytes[0x0..0x8].try_into().unwrap());
+        self.gs_base = u64::from_le_bytes(bytes[0x8..0x10].try_into().unwrap());
+
+        let mut regs = bytes[0x10..0x110]
+            .chunks_exact(0x10)
+            .map(|x| u128::from_le_bytes(x.try_into().unwrap()));
+
+        for reg in self.ymmh[..16].iter_mut() {
+            *reg = regs.next().ok_or(())?;
+        }
+
+        let mut regs = bytes[0x110..0x310]
+            .chunks_exact(0x10)
+            .map(|x| u128::from_le_bytes(x.try_into().unwrap()));
+
+        for reg in self.xmm_hi.iter_mut() {
+            *reg = regs.next().ok_or(())?;
+        }
+        for reg in self.ymmh[16..].iter_mut() {
+            *reg = regs.next().ok_or(())?;
+        }
+
+        let mut regs = bytes[0x310..0x350]
+            .chunks_exact(0x8)
+            .map(|x| u64::from_le_bytes(x.try_into().unwrap()));
+
+        for reg in self.k.iter_mut() {
+            *reg = regs.next().ok_or(())?;
+        }
+
+        let mut regs = bytes[0x350..0x750]
+            .chunks_exact(0x10)
+            .map(|x| u128::from_le_bytes(x.try_into().unwrap()));
+
+        for reg in self.zmmh.iter_mut() {
+            reg[0] = regs.next().ok_or(())?;
+            reg[1] = regs.next().ok_or(())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// 64-bit x86 registers + Linux-specific `orig_rax` register.
+///
+/// `orig_rax` holds the original syscall number of the current system call
+/// (or `-1` if not in a syscall), and is used by GDB to correctly handle
+/// syscall restarts when modifying registers / calling functions.
+///
+/// The inner `R` register file should be one of [`X86_64CoreRegs`],
+/// [`X86_64AvxRegs`], or [`X86_64Avx512Regs`].
+///
+/// Source: <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/i386/64bit-linux.xml>
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct X86_64LinuxRegs<R> {
+    /// Register file
+    pub regs: R,
+    /// Original value of RAX on syscall entry
+    pub orig_rax: u64,
+}
+
+impl<R> Registers for X86_64LinuxRegs<R>
+where
+    R: Registers<ProgramCounter = u64>,
+{
+    type ProgramCounter = u64;
+
+    fn pc(&self) -> Self::ProgramCounter {
+        self.regs.pc()
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        self.regs.gdb_serialize(&mut write_byte);
+
+        // `orig_rax` uses the highest regnum of all x86-64 features, and is
+        // therefore always serialized last.
+        for b in self.orig_rax.to_le_bytes() {
+            write_byte(Some(b))
+        }
+    }
+
+    fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        if bytes.len() < 8 {
+            return Err(());
+        }
+
+        let (regs, orig_rax) = bytes.split_at(bytes.len() - 8);
+        self.regs.gdb_deserialize(regs)?;
+        self.orig_rax = u64::from_le_bytes(orig_rax.try_into().unwrap());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<R: Registers>(regs_before: R, expected_len: usize) {
+        let mut data = vec![];
+
+        regs_before.gdb_serialize(|x| {
+            data.push(x.unwrap_or(b'x'));
+        });
+
+        assert_eq!(data.len(), expected_len);
+
+        let mut regs_after = R::default();
+        regs_after.gdb_deserialize(&data).unwrap();
+
+        assert_eq!(regs_before, regs_after);
+    }
+
+    #[test]
+    fn x86_64_avx_round_trip() {
+        let mut regs = X86_64AvxRegs::default();
+        regs.core.rip = 0xffff_ffff_8100_0000;
+        regs.core.xmm[3] = 0x1234;
+        regs.fs_base = 0x7fff_0000_1000;
+        regs.gs_base = 0xffff_8880_0000_0000;
+        for (i, reg) in regs.ymmh.iter_mut().enumerate() {
+            *reg = (i as u128) << 100;
+        }
+
+        round_trip(regs, 0x218 + 0x110);
+    }
+
+    #[test]
+    fn x86_64_linux_avx512_round_trip() {
+        let mut regs = X86_64LinuxRegs::<X86_64Avx512Regs>::default();
+        regs.regs.core.rip = 0x40_1000;
+        regs.regs.fs_base = 1;
+        regs.regs.gs_base = 2;
+        for i in 0..32 {
+            regs.regs.ymmh[i] = 0x100 + i as u128;
+            regs.regs.zmmh[i] = [0x200 + i as u128, 0x300 + i as u128];
+        }
+        for i in 0..16 {
+            regs.regs.xmm_hi[i] = 0x400 + i as u128;
+        }
+        for i in 0..8 {
+            regs.regs.k[i] = 0x500 + i as u64;
+        }
+        regs.orig_rax = u64::MAX;
+
+        round_trip(regs, 0x218 + 0x750 + 8);
+    }
+}
@@ -9,6 +9,7 @@ use gdbstub::arch::Registers;
 /// Source: <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/i386/64bit-core.xml>
 /// Additionally: <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/i386/64bit-sse.xml>
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct X86_64CoreRegs {
     /// RAX, RBX, RCX, RDX, RSI, RDI, RBP, RSP, r8-r15
     pub regs: [u64; 16],
@@ -157,4 +158,22 @@ mod tests {
 
         assert_eq!(regs_before, regs_after);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let mut st = [[0; 10]; 8];
+        st[3] = [0xff; 10];
+        let mut xmm = [0; 16];
+        xmm[15] = u128::MAX;
+        let regs = X86_64CoreRegs {
+            rip: 0xdead_beef,
+            st,
+            xmm,
+            ..Default::default()
+        };
+
+        let json = serde_json::to_string(&regs).unwrap();
+        assert_eq!(serde_json::from_str::<X86_64CoreRegs>(&json).unwrap(), regs);
+    }
 }
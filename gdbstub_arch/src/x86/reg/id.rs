@@ -3,6 +3,7 @@ use gdbstub::arch::RegId;
 
 /// FPU register identifier.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum X87FpuInternalRegId {
     /// Floating-point control register
     Fctrl,
@@ -43,6 +44,7 @@ impl X87FpuInternalRegId {
 
 /// Segment register identifier.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::upper_case_acronyms)]
 pub enum X86SegmentRegId {
     /// Code Segment
@@ -76,11 +78,133 @@ impl X86SegmentRegId {
     }
 }
 
+/// x86-64 system register identifier.
+///
+/// Registers from the custom `org.rust.gdb.i386.sys` target feature, which is
+/// included by the `X86_64_*_System` arches. These registers are not part of
+/// the "g" packet, and are only accessed through the "p"/"P" packets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum X86_64SysRegId {
+    /// Control Register 0
+    Cr0,
+    /// Control Register 2 (page fault linear address)
+    Cr2,
+    /// Control Register 3 (page directory base)
+    Cr3,
+    /// Control Register 4
+    Cr4,
+    /// Control Register 8 (task priority)
+    Cr8,
+    /// Extended Feature Enable Register (`IA32_EFER`)
+    Efer,
+    /// Global Descriptor Table Register base address
+    GdtrBase,
+    /// Global Descriptor Table Register limit
+    GdtrLimit,
+    /// Interrupt Descriptor Table Register base address
+    IdtrBase,
+    /// Interrupt Descriptor Table Register limit
+    IdtrLimit,
+    /// Swap target of the `swapgs` instruction (`IA32_KERNEL_GS_BASE`)
+    KernelGsBase,
+    /// System call target segments (`IA32_STAR`)
+    Star,
+    /// 64-bit mode system call target address (`IA32_LSTAR`)
+    Lstar,
+    /// Compatibility mode system call target address (`IA32_CSTAR`)
+    Cstar,
+    /// System call RFLAGS mask (`IA32_FMASK`)
+    Fmask,
+    /// `sysenter` code segment (`IA32_SYSENTER_CS`)
+    SysenterCs,
+    /// `sysenter` stack pointer (`IA32_SYSENTER_ESP`)
+    SysenterEsp,
+    /// `sysenter` instruction pointer (`IA32_SYSENTER_EIP`)
+    SysenterEip,
+    /// Page Attribute Table (`IA32_PAT`)
+    Pat,
+    /// APIC base address (`IA32_APIC_BASE`)
+    ApicBase,
+    /// Time-Stamp Counter (`IA32_TIME_STAMP_COUNTER`)
+    Tsc,
+    /// Auxiliary TSC signature (`IA32_TSC_AUX`)
+    TscAux,
+}
+
+impl X86_64SysRegId {
+    fn from_u8(val: u8) -> Option<Self> {
+        use self::X86_64SysRegId::*;
+
+        let r = match val {
+            0 => Cr0,
+            1 => Cr2,
+            2 => Cr3,
+            3 => Cr4,
+            4 => Cr8,
+            5 => Efer,
+            6 => GdtrBase,
+            7 => GdtrLimit,
+            8 => IdtrBase,
+            9 => IdtrLimit,
+            10 => KernelGsBase,
+            11 => Star,
+            12 => Lstar,
+            13 => Cstar,
+            14 => Fmask,
+            15 => SysenterCs,
+            16 => SysenterEsp,
+            17 => SysenterEip,
+            18 => Pat,
+            19 => ApicBase,
+            20 => Tsc,
+            21 => TscAux,
+            _ => return None,
+        };
+        Some(r)
+    }
+
+    /// Returns the size of the register (in bytes).
+    pub fn size(self) -> usize {
+        match self {
+            X86_64SysRegId::GdtrLimit | X86_64SysRegId::IdtrLimit => 2,
+            _ => 8,
+        }
+    }
+
+    /// Returns the MSR index (i.e: the `ecx` value used by `rdmsr` / `wrmsr`)
+    /// of the register, or `None` if the register is not an MSR.
+    pub fn msr_index(self) -> Option<u32> {
+        use self::X86_64SysRegId::*;
+
+        let idx = match self {
+            Efer => 0xc000_0080,
+            KernelGsBase => 0xc000_0102,
+            Star => 0xc000_0081,
+            Lstar => 0xc000_0082,
+            Cstar => 0xc000_0083,
+            Fmask => 0xc000_0084,
+            SysenterCs => 0x174,
+            SysenterEsp => 0x175,
+            SysenterEip => 0x176,
+            Pat => 0x277,
+            ApicBase => 0x1b,
+            Tsc => 0x10,
+            TscAux => 0xc000_0103,
+            Cr0 | Cr2 | Cr3 | Cr4 | Cr8 | GdtrBase | GdtrLimit | IdtrBase | IdtrLimit => {
+                return None
+            }
+        };
+        Some(idx)
+    }
+}
+
 /// 32-bit x86 core + SSE register identifier.
 ///
 /// Source: <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/i386/32bit-core.xml>
 /// Additionally: <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/i386/32bit-sse.xml>
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum X86CoreRegId {
     /// Accumulator
@@ -144,9 +268,15 @@ impl RegId for X86CoreRegId {
 
 /// 64-bit x86 core + SSE register identifier.
 ///
+/// Also includes identifiers for registers from the segments, AVX, AVX-512,
+/// Linux, and system register target features. `gdbstub_arch`'s x86-64 target
+/// descriptions use explicit regnums, such that each register has the same
+/// identifier regardless of which features are advertised.
+///
 /// Source: <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/i386/64bit-core.xml>
 /// Additionally: <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/i386/64bit-sse.xml>
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum X86_64CoreRegId {
     /// General purpose registers:
@@ -162,10 +292,24 @@ pub enum X86_64CoreRegId {
     St(u8),
     /// FPU internal registers
     Fpu(X87FpuInternalRegId),
-    /// SIMD Registers: XMM0 through XMM15
+    /// SIMD Registers: XMM0 through XMM15 (XMM16 through XMM31 with AVX-512)
     Xmm(u8),
     /// SSE Status/Control Register
     Mxcsr,
+    /// FS segment base address
+    FsBase,
+    /// GS segment base address
+    GsBase,
+    /// Upper 128 bits of YMM0 through YMM15 (YMM16 through YMM31 with AVX-512)
+    Ymmh(u8),
+    /// AVX-512 Opmask Registers: K0 through K7
+    K(u8),
+    /// Upper 256 bits of ZMM0 through ZMM31
+    Zmmh(u8),
+    /// (Linux) Original value of RAX on syscall entry
+    OrigRax,
+    /// System registers (control registers, descriptor tables, MSRs)
+    Sys(X86_64SysRegId),
 }
 
 impl RegId for X86_64CoreRegId {
@@ -181,6 +325,18 @@ impl RegId for X86_64CoreRegId {
             32..=39 => (Fpu(X87FpuInternalRegId::from_u8(id as u8 - 32)?), 4),
             40..=55 => (Xmm(id as u8 - 40), 16),
             56 => (Mxcsr, 4),
+            57 => (FsBase, 8),
+            58 => (GsBase, 8),
+            59..=74 => (Ymmh(id as u8 - 59), 16),
+            75..=90 => (Xmm(id as u8 - 75 + 16), 16),
+            91..=106 => (Ymmh(id as u8 - 91 + 16), 16),
+            107..=114 => (K(id as u8 - 107), 8),
+            115..=146 => (Zmmh(id as u8 - 115), 32),
+            147 => (OrigRax, 8),
+            148..=169 => {
+                let sys = X86_64SysRegId::from_u8(id as u8 - 148)?;
+                (Sys(sys), sys.size())
+            }
             _ => return None,
         };
 
@@ -195,8 +351,9 @@ mod tests {
 
     /// Compare the following two values which are expected to be the same:
     /// * length of data written by `Registers::gdb_serialize()` in byte
-    /// * sum of sizes of all registers obtained by `RegId::from_raw_id()`
-    fn test<Rs: Registers, RId: RegId>() {
+    /// * sum of sizes of all registers obtained by `RegId::from_raw_id()`,
+    ///   for all raw ids in `ids`
+    fn test<Rs: Registers, RId: RegId>(ids: impl Iterator<Item = usize>) {
         // Obtain the data length written by `gdb_serialize` by passing a custom
         // closure.
         let mut serialized_data_len = 0;
@@ -208,11 +365,10 @@ mod tests {
         Rs::default().gdb_serialize(counter);
 
         // Accumulate register sizes returned by `from_raw_id`.
-        let mut i = 0;
         let mut sum_reg_sizes = 0;
-        while let Some((_, size)) = RId::from_raw_id(i) {
+        for i in ids {
+            let (_, size) = RId::from_raw_id(i).unwrap();
             sum_reg_sizes += size.unwrap().get();
-            i += 1;
         }
 
         assert_eq!(serialized_data_len, sum_reg_sizes);
@@ -220,11 +376,63 @@ mod tests {
 
     #[test]
     fn test_x86() {
-        test::<crate::x86::reg::X86CoreRegs, crate::x86::reg::id::X86CoreRegId>()
+        test::<crate::x86::reg::X86CoreRegs, crate::x86::reg::id::X86CoreRegId>(0..=40);
+        assert!(crate::x86::reg::id::X86CoreRegId::from_raw_id(41).is_none());
     }
 
     #[test]
     fn test_x86_64() {
-        test::<crate::x86::reg::X86_64CoreRegs, crate::x86::reg::id::X86_64CoreRegId>()
+        test::<crate::x86::reg::X86_64CoreRegs, crate::x86::reg::id::X86_64CoreRegId>(0..=56)
+    }
+
+    #[test]
+    fn test_x86_64_avx() {
+        test::<crate::x86::reg::X86_64AvxRegs, crate::x86::reg::id::X86_64CoreRegId>(0..=74)
+    }
+
+    #[test]
+    fn test_x86_64_linux_avx512() {
+        test::<
+            crate::x86::reg::X86_64LinuxRegs<crate::x86::reg::X86_64Avx512Regs>,
+            crate::x86::reg::id::X86_64CoreRegId,
+        >(0..=147);
+    }
+
+    #[test]
+    fn test_x86_64_sys() {
+        use crate::x86::reg::id::X86_64CoreRegId;
+        use crate::x86::reg::id::X86_64SysRegId;
+
+        for i in 148..=169 {
+            assert!(matches!(
+                X86_64CoreRegId::from_raw_id(i),
+                Some((X86_64CoreRegId::Sys(_), Some(_)))
+            ));
+        }
+        assert!(X86_64CoreRegId::from_raw_id(170).is_none());
+
+        assert!(matches!(
+            X86_64CoreRegId::from_raw_id(150),
+            Some((X86_64CoreRegId::Sys(X86_64SysRegId::Cr3), _))
+        ));
+        assert_eq!(
+            X86_64CoreRegId::from_raw_id(155).map(|(_, sz)| sz.unwrap().get()),
+            Some(2)
+        );
+        assert_eq!(X86_64SysRegId::Efer.msr_index(), Some(0xc000_0080));
+        assert_eq!(X86_64SysRegId::Cr3.msr_index(), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        use crate::x86::reg::id::X86SegmentRegId;
+        use crate::x86::reg::id::X86_64CoreRegId;
+
+        let json = serde_json::to_string(&X86_64CoreRegId::Segment(X86SegmentRegId::GS)).unwrap();
+        assert!(matches!(
+            serde_json::from_str(&json).unwrap(),
+            X86_64CoreRegId::Segment(X86SegmentRegId::GS)
+        ));
     }
 }
@@ -6,9 +6,13 @@ use gdbstub::arch::Registers;
 /// `RegId` definitions for x86 architectures.
 pub mod id;
 
+mod avx64;
 mod core32;
 mod core64;
 
+pub use avx64::X86_64Avx512Regs;
+pub use avx64::X86_64AvxRegs;
+pub use avx64::X86_64LinuxRegs;
 pub use core32::X86CoreRegs;
 pub use core64::X86_64CoreRegs;
 
@@ -17,6 +21,7 @@ pub type F80 = [u8; 10];
 
 /// FPU registers
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct X87FpuInternalRegs {
     /// Floating-point control register
     pub fctrl: u32,
@@ -92,6 +97,7 @@ impl Registers for X87FpuInternalRegs {
 ///
 /// Source: <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/i386/64bit-core.xml>
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct X86SegmentRegs {
     /// Code Segment
     pub cs: u32,
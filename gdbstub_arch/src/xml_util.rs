@@ -0,0 +1,569 @@
+//! Helpers for scanning and generating target description XML.
+
+use core::fmt;
+use core::fmt::Write;
+use core::ops::Range;
+use gdbstub::arch::tdesc::Reg;
+use gdbstub::arch::tdesc::TdescError;
+use gdbstub::arch::tdesc::TdescWriter;
+
+/// Copy the range `offset..offset + length` of `xml`'s `Display` output into
+/// `buf`, returning the number of bytes written.
+///
+/// The output is generated on-the-fly, and is never buffered in its entirety.
+pub(crate) fn read_range(
+    xml: &impl fmt::Display,
+    offset: u64,
+    length: usize,
+    buf: &mut [u8],
+) -> usize {
+    let len = length.min(buf.len());
+    let mut w = RangeWriter {
+        skip: offset,
+        buf: &mut buf[..len],
+        written: 0,
+    };
+    // `RangeWriter` never fails, and simply discards any trailing data
+    let _ = write!(w, "{}", xml);
+    w.written
+}
+
+/// A `fmt::Write` implementation which skips the first `skip` bytes written
+/// to it, and silently discards any bytes that don't fit into `buf`.
+struct RangeWriter<'a> {
+    skip: u64,
+    buf: &'a mut [u8],
+    written: usize,
+}
+
+impl Write for RangeWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut s = s.as_bytes();
+
+        if self.skip != 0 {
+            let n = (self.skip).min(s.len() as u64);
+            self.skip -= n;
+            s = &s[n as usize..];
+        }
+
+        let n = s.len().min(self.buf.len() - self.written);
+        self.buf[self.written..][..n].copy_from_slice(&s[..n]);
+        self.written += n;
+
+        Ok(())
+    }
+}
+
+/// Format `prefix` followed by the (at most 2 digit) index `i` into `buf`.
+pub(crate) fn indexed_name<'a>(buf: &'a mut [u8; 4], prefix: &str, i: usize) -> &'a str {
+    let mut len = prefix.len();
+    buf[..len].copy_from_slice(prefix.as_bytes());
+    if i >= 10 {
+        buf[len] = b'0' + (i / 10) as u8;
+        len += 1;
+    }
+    buf[len] = b'0' + (i % 10) as u8;
+    core::str::from_utf8(&buf[..len + 1]).unwrap_or_default()
+}
+
+/// Add the pre-written feature XML `features` (i.e: one or more complete
+/// `<feature>` elements) to `xml`, describing its registers to the writer such
+/// that register numbering and "g" packet checks carry on across it.
+///
+/// `in_g_packet` determines which of the registers are part of the "g" packet.
+/// Registers sharing the preceding register's number (i.e: aliases, such as
+/// those in `aarch64/sysregs.xml`) are only described to the writer once.
+pub(crate) fn write_features(
+    xml: &mut TdescWriter<'_, '_>,
+    features: &str,
+    in_g_packet: impl Fn(&XmlReg<'_>) -> bool,
+) -> Result<(), TdescError> {
+    let mut layout = xml.layout();
+    let next_regnum = layout.next_regnum();
+    let mut res = Ok(());
+    let mut prev_regnum = None;
+    let mut scanner = RegScanner::new(|reg: &XmlReg<'_>, _: Range<usize>| {
+        if prev_regnum.replace(reg.regnum) == Some(reg.regnum) {
+            return;
+        }
+        let mut tdesc_reg = Reg::new(reg.name, reg.bitsize).regnum(reg.regnum);
+        if !in_g_packet(reg) {
+            tdesc_reg = tdesc_reg.exclude_from_g_packet();
+        }
+        res = res.and_then(|()| layout.add_reg(&tdesc_reg).map(drop));
+    })
+    .with_next_regnum(next_regnum);
+    scanner.scan(&features);
+    res?;
+
+    xml.feature_xml(features, layout)
+}
+
+/// A `<reg>` element parsed from target description XML.
+pub struct XmlReg<'a> {
+    pub name: &'a str,
+    pub bitsize: usize,
+    pub regnum: usize,
+    pub ty: Option<&'a str>,
+    pub group: Option<&'a str>,
+}
+
+impl<'a> XmlReg<'a> {
+    /// Parse the contents of a `<reg ...>` tag (i.e: excluding the enclosing
+    /// angle brackets), assigning `next_regnum` if the tag doesn't include an
+    /// explicit `regnum` attribute.
+    ///
+    /// Returns `None` if the tag isn't a well-formed `<reg>` element.
+    pub fn parse(tag: &'a str, next_regnum: usize) -> Option<XmlReg<'a>> {
+        let mut attrs = tag.strip_prefix("reg")?;
+        if !attrs.starts_with(|c: char| c.is_ascii_whitespace()) {
+            return None;
+        }
+
+        let mut name = None;
+        let mut bitsize = None;
+        let mut regnum = None;
+        let mut ty = None;
+        let mut group = None;
+
+        loop {
+            attrs = attrs.trim_start();
+            let (key, rest) = match attrs.split_once('=') {
+                Some(kv) => kv,
+                None => break,
+            };
+            let rest = rest.trim_start();
+            let quote = rest.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+            let (val, rest) = rest[1..].split_once(quote)?;
+            attrs = rest;
+
+            match key.trim_end() {
+                "name" => name = Some(val),
+                "bitsize" => bitsize = Some(val.parse().ok()?),
+                "regnum" => regnum = Some(val.parse().ok()?),
+                "type" => ty = Some(val),
+                "group" => group = Some(val),
+                _ => {}
+            }
+        }
+
+        Some(XmlReg {
+            name: name?,
+            bitsize: bitsize?,
+            regnum: regnum.unwrap_or(next_regnum),
+            ty,
+            group,
+        })
+    }
+}
+
+/// Check if the (possibly truncated) tag contents `tag` are a `<reg>` element.
+fn is_reg_tag(tag: &[u8]) -> bool {
+    tag.starts_with(b"reg") && tag.get(3).is_some_and(|b| b.is_ascii_whitespace())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScanState {
+    Text,
+    Tag,
+    Comment { dashes: usize },
+}
+
+/// A `fmt::Write` implementation which scans target description XML for
+/// `<reg>` elements, invoking a callback with each register alongside the
+/// byte range of its tag contents.
+///
+/// As registers are reported as they are encountered, the XML never needs to
+/// be buffered in its entirety.
+pub(crate) struct RegScanner<F> {
+    on_reg: F,
+    state: ScanState,
+    pos: usize,
+    tag: [u8; 256],
+    tag_len: usize,
+    tag_start: usize,
+    next_regnum: usize,
+}
+
+impl<F: FnMut(&XmlReg<'_>, Range<usize>)> RegScanner<F> {
+    pub fn new(on_reg: F) -> Self {
+        RegScanner {
+            on_reg,
+            state: ScanState::Text,
+            pos: 0,
+            tag: [0; 256],
+            tag_len: 0,
+            tag_start: 0,
+            next_regnum: 0,
+        }
+    }
+
+    /// Number registers without an explicit `regnum` attribute following
+    /// `next_regnum` (rather than from 0), e.g: when scanning a fragment of a
+    /// larger document.
+    pub fn with_next_regnum(self, next_regnum: usize) -> Self {
+        RegScanner {
+            next_regnum,
+            ..self
+        }
+    }
+
+    /// Scan a complete XML document (or fragment).
+    ///
+    /// Reported tag ranges are relative to the start of `xml`, while register
+    /// numbering continues on from any previously scanned fragment (matching
+    /// how GDB numbers registers across features).
+    pub fn scan(&mut self, xml: &impl fmt::Display) {
+        self.state = ScanState::Text;
+        self.pos = 0;
+        // `RegScanner` never fails
+        let _ = write!(self, "{}", xml);
+    }
+
+    fn end_tag(&mut self) {
+        if self.tag_len >= self.tag.len() {
+            // only `<reg>` elements are parsed, and those are never this long
+            // in any sane target description
+            debug_assert!(
+                !is_reg_tag(&self.tag),
+                "`<reg>` element longer than {} bytes",
+                self.tag.len()
+            );
+            return;
+        }
+        let tag = &self.tag[..self.tag_len];
+        let tag = match core::str::from_utf8(tag) {
+            Ok(tag) => tag.trim_end_matches('/'),
+            Err(_) => return,
+        };
+        if let Some(reg) = XmlReg::parse(tag, self.next_regnum) {
+            self.next_regnum = reg.regnum + 1;
+            (self.on_reg)(&reg, self.tag_start..self.tag_start + tag.len());
+        }
+    }
+}
+
+impl<F: FnMut(&XmlReg<'_>, Range<usize>)> Write for RegScanner<F> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for &b in s.as_bytes() {
+            self.pos += 1;
+            self.state = match self.state {
+                ScanState::Text if b == b'<' => {
+                    self.tag_len = 0;
+                    self.tag_start = self.pos;
+                    ScanState::Tag
+                }
+                ScanState::Text => ScanState::Text,
+                ScanState::Tag if b == b'>' => {
+                    self.end_tag();
+                    ScanState::Text
+                }
+                ScanState::Tag => {
+                    if let Some(slot) = self.tag.get_mut(self.tag_len) {
+                        *slot = b;
+                    }
+                    self.tag_len += 1;
+                    if self.tag_len == 3 && self.tag[..3] == *b"!--" {
+                        ScanState::Comment { dashes: 0 }
+                    } else {
+                        ScanState::Tag
+                    }
+                }
+                ScanState::Comment { dashes } if b == b'>' && dashes >= 2 => ScanState::Text,
+                ScanState::Comment { dashes } if b == b'-' => {
+                    ScanState::Comment { dashes: dashes + 1 }
+                }
+                ScanState::Comment { .. } => ScanState::Comment { dashes: 0 },
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The location of a `<reg>` element within static target description XML.
+///
+/// See [`index_regs`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RegSpan {
+    /// Offset of the tag contents (i.e: excluding the enclosing angle
+    /// brackets).
+    pub start: u32,
+    pub len: u16,
+    pub regnum: u16,
+    pub bitsize: u16,
+}
+
+impl RegSpan {
+    pub fn range(&self) -> Range<usize> {
+        self.start as usize..self.start as usize + self.len as usize
+    }
+}
+
+/// The `<reg>` elements found by [`index_regs`], with room for up to `M`
+/// registers.
+pub(crate) struct RegIndex<const M: usize> {
+    pub spans: [RegSpan; M],
+    pub len: usize,
+    /// The number of registers found in `xml` (rather than `gaps`).
+    pub xml_regs: usize,
+}
+
+// The following `const fn`s are evaluated at compile time for every arch, and
+// const evaluation is slow enough that the XML is only scanned once, avoiding
+// function calls in hot loops (e.g: by hoisting `len()` calls, and using
+// `is_space!`).
+
+macro_rules! is_space {
+    ($b:expr) => {
+        matches!($b, b' ' | b'\t' | b'\n' | b'\x0c' | b'\r')
+    };
+}
+
+const EMPTY_SPAN: RegSpan = RegSpan {
+    start: 0,
+    len: 0,
+    regnum: 0,
+    bitsize: 0,
+};
+
+/// Locate each `<reg>` element in the static XML `xml`, followed by those in
+/// `gaps`, tokenizing and numbering registers the same way as [`RegScanner`]
+/// and [`XmlReg::parse`].
+///
+/// Intended to be evaluated at compile time, such that malformed `<reg>`
+/// elements result in a compile error (rather than being silently skipped).
+pub(crate) const fn index_regs<const M: usize>(xml: &str, gaps: &str) -> RegIndex<M> {
+    let mut index = RegIndex {
+        spans: [EMPTY_SPAN; M],
+        len: 0,
+        xml_regs: 0,
+    };
+    let srcs = [xml.as_bytes(), gaps.as_bytes()];
+
+    let mut next_regnum = 0;
+    let mut src = 0;
+    while src < srcs.len() {
+        let xml = srcs[src];
+        let len = xml.len();
+        let mut pos = 0;
+        while pos < len {
+            if xml[pos] != b'<' {
+                pos += 1;
+                continue;
+            }
+            pos += 1;
+            let start = pos;
+
+            if starts_with(xml, start, b"!--") {
+                pos = start + 3;
+                while pos < len
+                    && !(xml[pos] == b'>' && pos >= start + 5 && starts_with(xml, pos - 2, b"--"))
+                {
+                    pos += 1;
+                }
+                continue;
+            }
+
+            if !(starts_with(xml, start, b"reg") && start + 3 < len && is_space!(xml[start + 3])) {
+                while pos < len && xml[pos] != b'>' {
+                    pos += 1;
+                }
+                continue;
+            }
+
+            let mut name = false;
+            let mut bitsize = None;
+            let mut regnum = None;
+            pos = start + 3;
+            loop {
+                while pos < len && is_space!(xml[pos]) {
+                    pos += 1;
+                }
+                let key_start = pos;
+                while pos < len && xml[pos] != b'=' && xml[pos] != b'>' {
+                    pos += 1;
+                }
+                if pos == len || xml[pos] == b'>' {
+                    break;
+                }
+                let mut key_end = pos;
+                while key_end > key_start && is_space!(xml[key_end - 1]) {
+                    key_end -= 1;
+                }
+
+                pos += 1;
+                while pos < len && is_space!(xml[pos]) {
+                    pos += 1;
+                }
+                assert!(
+                    pos < len && matches!(xml[pos], b'"' | b'\''),
+                    "`<reg>` attribute value must be quoted"
+                );
+                let quote = xml[pos];
+                pos += 1;
+                let val_start = pos;
+                while pos < len && xml[pos] != quote && xml[pos] != b'>' {
+                    pos += 1;
+                }
+                assert!(
+                    pos < len && xml[pos] == quote,
+                    "unterminated `<reg>` attribute value"
+                );
+
+                match (key_end - key_start, xml[key_start]) {
+                    (4, b'n') if starts_with(xml, key_start, b"name") => name = true,
+                    (7, b'b') if starts_with(xml, key_start, b"bitsize") => {
+                        bitsize = Some(parse_usize(xml, val_start, pos))
+                    }
+                    (6, b'r') if starts_with(xml, key_start, b"regnum") => {
+                        regnum = Some(parse_usize(xml, val_start, pos))
+                    }
+                    _ => {}
+                }
+                pos += 1;
+            }
+            assert!(pos < len, "unterminated `<reg>` element");
+
+            let mut end = pos;
+            while end > start && xml[end - 1] == b'/' {
+                end -= 1;
+            }
+            let bitsize = match bitsize {
+                Some(bitsize) => bitsize,
+                None => usize::MAX,
+            };
+            let regnum = match regnum {
+                Some(regnum) => regnum,
+                None => next_regnum,
+            };
+            assert!(name, "`<reg>` element is missing a name");
+            assert!(
+                bitsize <= u16::MAX as usize,
+                "`<reg>` element has a missing or invalid bitsize"
+            );
+            assert!(regnum <= u16::MAX as usize, "`<reg>` regnum is too large");
+            assert!(start <= u32::MAX as usize && end - start <= u16::MAX as usize);
+            assert!(index.len < M, "too many `<reg>` elements");
+
+            index.spans[index.len] = RegSpan {
+                start: start as u32,
+                len: (end - start) as u16,
+                regnum: regnum as u16,
+                bitsize: bitsize as u16,
+            };
+            index.len += 1;
+            next_regnum = regnum + 1;
+        }
+
+        if src == 0 {
+            index.xml_regs = index.len;
+        }
+        src += 1;
+    }
+    index
+}
+
+/// Copy the first `N` spans out of `spans`.
+pub(crate) const fn truncate<const M: usize, const N: usize>(spans: &[RegSpan; M]) -> [RegSpan; N] {
+    let mut out = [EMPTY_SPAN; N];
+    let mut i = 0;
+    while i < N {
+        out[i] = spans[i];
+        i += 1;
+    }
+    out
+}
+
+const fn starts_with(xml: &[u8], pos: usize, prefix: &[u8]) -> bool {
+    let len = prefix.len();
+    if pos + len > xml.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < len {
+        if xml[pos + i] != prefix[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn parse_usize(xml: &[u8], start: usize, end: usize) -> usize {
+    assert!(start < end, "empty `<reg>` attribute value");
+    let mut n: usize = 0;
+    let mut pos = start;
+    while pos < end {
+        assert!(xml[pos].is_ascii_digit(), "invalid `<reg>` attribute value");
+        n = n * 10 + (xml[pos] - b'0') as usize;
+        pos += 1;
+    }
+    n
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+
+    const XML: &str = r#"<feature name="test">
+        <!-- <reg name="commented" bitsize="32"/> -->
+        <regs name="not_a_reg" bitsize="8"/>
+        <reg name="r0" bitsize="32"/>
+        <reg name = 'r1' bitsize = '32' type="int"></reg>
+        <reg name="pc" bitsize="32" regnum="4" type="code_ptr" />
+        <reg name="sr" bitsize="16"/>
+    </feature>"#;
+
+    // numbering continues on from the registers in `XML`
+    const GAPS: &str =
+        r#"<reg name="gap0" bitsize="64"/><reg name="gap1" bitsize="8" regnum="2"/>"#;
+
+    /// Scan `XML` followed by `GAPS`, returning each register's regnum,
+    /// bitsize, tag range, and whether it was found in `XML`.
+    fn scan() -> Vec<(usize, usize, Range<usize>, bool)> {
+        let mut regs = Vec::new();
+        let in_xml = Cell::new(true);
+        let mut scanner = RegScanner::new(|reg: &XmlReg<'_>, range| {
+            regs.push((reg.regnum, reg.bitsize, range, in_xml.get()))
+        });
+        scanner.scan(&XML);
+        in_xml.set(false);
+        scanner.scan(&GAPS);
+        regs
+    }
+
+    #[test]
+    fn regnums() {
+        let regnums: Vec<_> = scan().into_iter().map(|(regnum, ..)| regnum).collect();
+        assert_eq!(regnums, [0, 1, 4, 5, 6, 2]);
+    }
+
+    #[test]
+    fn index_matches_scanner() {
+        const INDEX: RegIndex<16> = index_regs(XML, GAPS);
+
+        let indexed: Vec<_> = INDEX.spans[..INDEX.len]
+            .iter()
+            .enumerate()
+            .map(|(i, span)| {
+                let in_xml = i < INDEX.xml_regs;
+                (
+                    span.regnum as usize,
+                    span.bitsize as usize,
+                    span.range(),
+                    in_xml,
+                )
+            })
+            .collect();
+        assert_eq!(indexed, scan());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "`<reg>` element longer than 256 bytes")]
+    fn oversized_reg() {
+        let xml = format!(r#"<reg name="{}" bitsize="32"/>"#, "x".repeat(256));
+        RegScanner::new(|_: &XmlReg<'_>, _| {}).scan(&xml);
+    }
+}
@@ -0,0 +1,21 @@
+//! Register metadata for Xtensa.
+
+use crate::meta::reg_index;
+use crate::meta::RegMeta;
+use crate::xml_util::XmlReg;
+use gdbstub::arch::lldb::Generic;
+
+/// DWARF register numbers and generic registers, matching Xtensa GCC.
+pub(crate) fn xtensa_meta(reg: &XmlReg<'_>) -> RegMeta {
+    match reg.name {
+        "pc" => RegMeta::default().generic(Generic::Pc),
+        "ps" => RegMeta::default().generic(Generic::Flags),
+        "a0" => RegMeta::dwarf(0).generic(Generic::Ra),
+        "a1" => RegMeta::dwarf(1).generic(Generic::Sp).alt_name("sp"),
+        name => match reg_index(name, "a") {
+            Some(n @ 2..=7) => RegMeta::dwarf(n).arg(n - 2),
+            Some(n) => RegMeta::dwarf(n),
+            None => RegMeta::default(),
+        },
+    }
+}
@@ -0,0 +1,152 @@
+//! Implementations for the [Xtensa](https://en.wikipedia.org/wiki/Tensilica)
+//! architecture (e.g: Espressif ESP32 / ESP32-S3).
+//!
+//! Xtensa cores are highly configurable, and the GDB client does not ship
+//! with a standard target description for them. As such, the [`Xtensa`] arch
+//! does not include a built-in `target.xml`, and targets should instead
+//! implement the [`TargetDescriptionXmlOverride`] IDET using [`XtensaXml`],
+//! with an [`XtensaConfig`] matching the core being debugged.
+//!
+//! Only the core register set is supported (i.e: `pc`, the physical AR
+//! register file, the loop option registers, `sar`, the windowed register
+//! option registers, `ps`, and the `a0`-`a15` windowed views of the AR file).
+//!
+//! [`TargetDescriptionXmlOverride`]:
+//!     gdbstub::target::ext::target_description_xml_override::TargetDescriptionXmlOverride
+
+use gdbstub::arch::Arch;
+
+pub mod reg;
+
+mod meta;
+mod xml;
+
+pub use xml::XtensaXml;
+
+/// Core configuration options which affect the Xtensa register layout.
+///
+/// Defaults to the configuration used by the Espressif ESP32 (64 physical AR
+/// registers, with the loop option).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "XtensaConfigRepr")
+)]
+pub struct XtensaConfig {
+    num_aregs: u8,
+    loops: bool,
+}
+
+impl Default for XtensaConfig {
+    fn default() -> Self {
+        XtensaConfig {
+            num_aregs: 64,
+            loops: true,
+        }
+    }
+}
+
+impl XtensaConfig {
+    /// Create a new configuration with the specified number of physical AR
+    /// registers, without the loop option.
+    ///
+    /// Returns `None` if `num_aregs` is not one of 16 (call0 ABI only), 32, or
+    /// 64 (windowed register option).
+    pub fn new(num_aregs: usize) -> Option<Self> {
+        match num_aregs {
+            16 | 32 | 64 => Some(XtensaConfig {
+                num_aregs: num_aregs as u8,
+                loops: false,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Enable the loop option (`lbeg`, `lend`, and `lcount` registers).
+    pub fn with_loops(self) -> Self {
+        XtensaConfig {
+            loops: true,
+            ..self
+        }
+    }
+
+    /// The number of physical AR registers.
+    pub fn num_aregs(&self) -> usize {
+        self.num_aregs as usize
+    }
+
+    /// Whether the core implements the loop option.
+    pub fn has_loops(&self) -> bool {
+        self.loops
+    }
+
+    /// Whether the core implements the windowed register option (`windowbase`
+    /// and `windowstart` registers).
+    ///
+    /// Only cores with more than 16 physical AR registers implement register
+    /// windowing.
+    pub fn is_windowed(&self) -> bool {
+        self.num_aregs > 16
+    }
+}
+
+/// Unvalidated [`XtensaConfig`], used to reject invalid configurations when
+/// deserializing.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct XtensaConfigRepr {
+    num_aregs: u8,
+    loops: bool,
+}
+
+#[cfg(feature = "serde")]
+impl core::convert::TryFrom<XtensaConfigRepr> for XtensaConfig {
+    type Error = &'static str;
+
+    fn try_from(repr: XtensaConfigRepr) -> Result<Self, Self::Error> {
+        let config = XtensaConfig::new(repr.num_aregs as usize)
+            .ok_or("num_aregs must be one of 16, 32, or 64")?;
+        Ok(if repr.loops {
+            config.with_loops()
+        } else {
+            config
+        })
+    }
+}
+
+/// Implements `Arch` for Xtensa cores.
+///
+/// **NOTE:** As Xtensa cores are configurable, this arch does _not_ include a
+/// built-in `target.xml` implementation. Targets should implement
+/// [`TargetDescriptionXmlOverride`] using [`XtensaXml`].
+///
+/// [`TargetDescriptionXmlOverride`]:
+///     gdbstub::target::ext::target_description_xml_override::TargetDescriptionXmlOverride
+pub enum Xtensa {}
+
+impl Arch for Xtensa {
+    type Usize = u32;
+    type Registers = reg::XtensaCoreRegs;
+    type RegId = reg::id::XtensaRegId;
+    type BreakpointKind = usize;
+
+    fn target_description_xml() -> Option<&'static str> {
+        None
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_round_trip() {
+        let config = XtensaConfig::new(16).unwrap().with_loops();
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(serde_json::from_str::<XtensaConfig>(&json).unwrap(), config);
+
+        let json = r#"{"num_aregs":100,"loops":false}"#;
+        assert!(serde_json::from_str::<XtensaConfig>(json).is_err());
+    }
+}
@@ -0,0 +1,80 @@
+use core::num::NonZeroUsize;
+use gdbstub::arch::RegId;
+
+/// Xtensa register identifier.
+///
+/// Register numbers are fixed across all core configurations, and match those
+/// used by [`XtensaXml`](crate::xtensa::XtensaXml). The GDB client will never
+/// request registers which are not implemented by the configured core.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum XtensaRegId {
+    /// Program counter
+    Pc,
+    /// Physical AR registers (ar0-ar63)
+    Ar(u8),
+    /// Loop begin
+    Lbeg,
+    /// Loop end
+    Lend,
+    /// Loop count
+    Lcount,
+    /// Shift amount register
+    Sar,
+    /// Register window base
+    Windowbase,
+    /// Register window start
+    Windowstart,
+    /// Processor state
+    Ps,
+    /// Windowed address registers (a0-a15)
+    A(u8),
+}
+
+impl RegId for XtensaRegId {
+    fn from_raw_id(id: usize) -> Option<(Self, Option<NonZeroUsize>)> {
+        let reg = match id {
+            0 => Self::Pc,
+            1..=64 => Self::Ar((id - 1) as u8),
+            65 => Self::Lbeg,
+            66 => Self::Lend,
+            67 => Self::Lcount,
+            68 => Self::Sar,
+            69 => Self::Windowbase,
+            70 => Self::Windowstart,
+            71 => Self::Ps,
+            72..=87 => Self::A((id - 72) as u8),
+            _ => return None,
+        };
+
+        Some((reg, Some(NonZeroUsize::new(4)?)))
+    }
+
+    fn to_raw_id(&self) -> Option<usize> {
+        let id = match *self {
+            Self::Pc => 0,
+            Self::Ar(n @ 0..=63) => 1 + n as usize,
+            Self::Lbeg => 65,
+            Self::Lend => 66,
+            Self::Lcount => 67,
+            Self::Sar => 68,
+            Self::Windowbase => 69,
+            Self::Windowstart => 70,
+            Self::Ps => 71,
+            Self::A(n @ 0..=15) => 72 + n as usize,
+            _ => return None,
+        };
+        Some(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gdbstub::arch::RegId;
+
+    #[test]
+    fn test_xtensa_esp32_out_of_range() {
+        assert!(super::XtensaRegId::from_raw_id(88).is_none());
+    }
+}
@@ -0,0 +1,8 @@
+//! `Register` structs for Xtensa cores.
+
+/// `RegId` definitions for Xtensa cores.
+pub mod id;
+
+mod xtensa;
+
+pub use xtensa::XtensaCoreRegs;
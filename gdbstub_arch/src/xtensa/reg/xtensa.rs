@@ -0,0 +1,260 @@
+use crate::xtensa::XtensaConfig;
+use core::convert::TryInto;
+use gdbstub::arch::Registers;
+
+/// Xtensa core registers.
+///
+/// The set of registers sent to / received from the GDB client is determined
+/// by `config`, which _must_ match the configuration advertised via
+/// [`XtensaXml`](crate::xtensa::XtensaXml). Storage is reserved for the
+/// maximum number of physical AR registers (64), but only the first
+/// `config.num_aregs()` are used.
+///
+/// The `a0`-`a15` registers are not stored separately, and are instead
+/// derived from the physical AR register file using the current value of
+/// `windowbase` (see [`XtensaCoreRegs::a`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct XtensaCoreRegs {
+    /// Core configuration
+    pub config: XtensaConfig,
+    /// Program counter
+    pub pc: u32,
+    /// Physical AR registers (ar0-ar63)
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_array"))]
+    pub ar: [u32; 64],
+    /// Loop begin
+    pub lbeg: u32,
+    /// Loop end
+    pub lend: u32,
+    /// Loop count
+    pub lcount: u32,
+    /// Shift amount register
+    pub sar: u32,
+    /// Register window base (in units of 4 registers)
+    pub windowbase: u32,
+    /// Register window start
+    pub windowstart: u32,
+    /// Processor state
+    pub ps: u32,
+}
+
+impl Default for XtensaCoreRegs {
+    fn default() -> Self {
+        XtensaCoreRegs::new(XtensaConfig::default())
+    }
+}
+
+impl XtensaCoreRegs {
+    /// Create a zeroed register file for the specified core configuration.
+    pub fn new(config: XtensaConfig) -> Self {
+        XtensaCoreRegs {
+            config,
+            pc: 0,
+            ar: [0; 64],
+            lbeg: 0,
+            lend: 0,
+            lcount: 0,
+            sar: 0,
+            windowbase: 0,
+            windowstart: 0,
+            ps: 0,
+        }
+    }
+
+    /// Index into the physical AR register file of windowed register `a{n}`.
+    fn ar_index(&self, n: usize) -> usize {
+        let base = if self.config.is_windowed() {
+            self.windowbase as usize * 4
+        } else {
+            0
+        };
+        (base + n) % self.config.num_aregs()
+    }
+
+    /// Returns the value of windowed register `a{n}` (0-15).
+    pub fn a(&self, n: usize) -> u32 {
+        self.ar[self.ar_index(n)]
+    }
+
+    /// Sets the value of windowed register `a{n}` (0-15).
+    pub fn set_a(&mut self, n: usize, val: u32) {
+        let i = self.ar_index(n);
+        self.ar[i] = val;
+    }
+
+    /// Length (in bytes) of the serialized register file.
+    fn len(&self) -> usize {
+        let mut regs = 1 + self.config.num_aregs() + 2 + 16;
+        if self.config.has_loops() {
+            regs += 3;
+        }
+        if self.config.is_windowed() {
+            regs += 2;
+        }
+        regs * 4
+    }
+}
+
+impl Registers for XtensaCoreRegs {
+    type ProgramCounter = u32;
+
+    fn pc(&self) -> Self::ProgramCounter {
+        self.pc
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        macro_rules! write_bytes {
+            ($var: expr) => {
+                for b in $var.to_le_bytes() {
+                    write_byte(Some(b))
+                }
+            };
+        }
+
+        write_bytes!(self.pc);
+        for reg in self.ar[..self.config.num_aregs()].iter() {
+            write_bytes!(reg);
+        }
+        if self.config.has_loops() {
+            write_bytes!(self.lbeg);
+            write_bytes!(self.lend);
+            write_bytes!(self.lcount);
+        }
+        write_bytes!(self.sar);
+        if self.config.is_windowed() {
+            write_bytes!(self.windowbase);
+            write_bytes!(self.windowstart);
+        }
+        write_bytes!(self.ps);
+        for n in 0..16 {
+            write_bytes!(self.a(n));
+        }
+    }
+
+    fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        if bytes.len() != self.len() {
+            return Err(());
+        }
+
+        let mut regs = bytes
+            .chunks_exact(4)
+            .map(|c| u32::from_le_bytes(c.try_into().unwrap()));
+
+        let mut new = XtensaCoreRegs::new(self.config);
+        new.pc = regs.next().ok_or(())?;
+        for reg in new.ar[..self.config.num_aregs()].iter_mut() {
+            *reg = regs.next().ok_or(())?;
+        }
+        if self.config.has_loops() {
+            new.lbeg = regs.next().ok_or(())?;
+            new.lend = regs.next().ok_or(())?;
+            new.lcount = regs.next().ok_or(())?;
+        }
+        new.sar = regs.next().ok_or(())?;
+        if self.config.is_windowed() {
+            new.windowbase = regs.next().ok_or(())?;
+            new.windowstart = regs.next().ok_or(())?;
+        }
+        new.ps = regs.next().ok_or(())?;
+
+        // `a0`-`a15` alias the AR registers, so only apply the windowed
+        // registers whose value differs from the corresponding AR register
+        // (i.e: those the GDB client has modified via their `a` name).
+        for n in 0..16 {
+            let val = regs.next().ok_or(())?;
+            if val != new.a(n) {
+                new.set_a(n, val);
+            }
+        }
+
+        *self = new;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xtensa_round_trip() {
+        for config in [
+            XtensaConfig::default(),
+            XtensaConfig::new(32).unwrap(),
+            XtensaConfig::new(16).unwrap().with_loops(),
+        ] {
+            let mut regs_before = XtensaCoreRegs {
+                pc: 0x4008_1234,
+                lbeg: 1,
+                lend: 2,
+                lcount: 3,
+                sar: 4,
+                ps: 0x0006_0020,
+                ..XtensaCoreRegs::new(config)
+            };
+            if config.is_windowed() {
+                regs_before.windowbase = 3;
+                regs_before.windowstart = 0x9;
+            }
+            for i in 0..config.num_aregs() {
+                regs_before.ar[i] = 0x100 + i as u32;
+            }
+            if !config.has_loops() {
+                regs_before.lbeg = 0;
+                regs_before.lend = 0;
+                regs_before.lcount = 0;
+            }
+
+            let mut data = vec![];
+            regs_before.gdb_serialize(|x| {
+                data.push(x.unwrap_or(b'x'));
+            });
+
+            assert_eq!(data.len(), regs_before.len());
+
+            let mut regs_after = XtensaCoreRegs::new(config);
+            regs_after.gdb_deserialize(&data).unwrap();
+
+            assert_eq!(regs_before, regs_after);
+        }
+    }
+
+    #[test]
+    fn xtensa_windowed_a_regs() {
+        let mut regs = XtensaCoreRegs {
+            windowbase: 15,
+            ..Default::default()
+        };
+        regs.ar[60] = 1;
+        regs.ar[3] = 2;
+        assert_eq!(regs.a(0), 1);
+        assert_eq!(regs.a(7), 2);
+
+        let mut data = vec![];
+        regs.gdb_serialize(|x| data.push(x.unwrap_or(b'x')));
+
+        // modify `a1` (i.e: `ar61`) via its windowed name
+        let a1_off = data.len() - 15 * 4;
+        data[a1_off..a1_off + 4].copy_from_slice(&0x1234_5678u32.to_le_bytes());
+
+        regs.gdb_deserialize(&data).unwrap();
+        assert_eq!(regs.ar[61], 0x1234_5678);
+        assert_eq!(regs.a(0), 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let mut regs = XtensaCoreRegs::new(XtensaConfig::new(32).unwrap());
+        regs.ar[31] = 0x1234;
+
+        let json = serde_json::to_string(&regs).unwrap();
+        assert_eq!(serde_json::from_str::<XtensaCoreRegs>(&json).unwrap(), regs);
+
+        // `ar` must contain exactly 64 registers
+        let short = json.replacen("[0,", "[", 1);
+        assert!(serde_json::from_str::<XtensaCoreRegs>(&short).is_err());
+    }
+}
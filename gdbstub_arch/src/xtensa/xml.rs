@@ -0,0 +1,157 @@
+//! Runtime-generated target description XML for configurable Xtensa cores.
+
+use super::meta;
+use super::XtensaConfig;
+use crate::meta::RegMeta;
+use crate::target_xml::sealed::RegSource;
+use crate::target_xml::TargetXml;
+use crate::xml_util::indexed_name;
+use crate::xml_util::XmlReg;
+use core::fmt;
+use gdbstub::arch::tdesc::Reg;
+use gdbstub::arch::tdesc::TdescError;
+use gdbstub::arch::tdesc::TdescWriter;
+
+/// Target description XML for an Xtensa core with a runtime-specified
+/// [`XtensaConfig`].
+///
+/// The generated XML contains a single `org.gnu.gdb.xtensa.core` feature,
+/// whose registers use the fixed register numbers defined by
+/// [`XtensaRegId`](super::reg::id::XtensaRegId). Registers which are not
+/// implemented by the configured core are omitted.
+///
+/// _Note:_ LLDB stops querying registers at the first unused register number,
+/// so on cores without all 64 physical AR registers and the loop / windowed
+/// register options, only a subset of the registers are reported via
+/// [`TargetXml::lldb_register_info`].
+///
+/// # Example
+///
+/// ```
+/// use gdbstub_arch::target_xml::TargetXml;
+/// use gdbstub_arch::xtensa::XtensaConfig;
+/// use gdbstub_arch::xtensa::XtensaXml;
+///
+/// // in `TargetDescriptionXmlOverride::target_description_xml`
+/// # let (annex, offset, length, mut buf) = (b"target.xml", 0, 0x1000, [0; 0x1000]);
+/// let xml = XtensaXml::new(XtensaConfig::new(32).unwrap().with_loops());
+/// let n = match annex {
+///     b"target.xml" => xml.read_range(offset, length, &mut buf),
+///     _ => 0, // return Err(TargetError::NonFatal)
+/// };
+/// assert!(n > 0);
+/// ```
+pub struct XtensaXml {
+    config: XtensaConfig,
+}
+
+impl XtensaXml {
+    /// Create a new target description for the specified core configuration.
+    pub fn new(config: XtensaConfig) -> Self {
+        XtensaXml { config }
+    }
+
+    /// The core configuration.
+    pub fn config(&self) -> XtensaConfig {
+        self.config
+    }
+}
+
+impl TargetXml for XtensaXml {}
+
+impl RegSource for XtensaXml {
+    fn reg_meta(&self, reg: &XmlReg<'_>) -> RegMeta {
+        meta::xtensa_meta(reg)
+    }
+}
+
+impl XtensaXml {
+    fn write_tdesc(&self, mut xml: TdescWriter<'_, '_>) -> Result<(), TdescError> {
+        xml.architecture("xtensa")?;
+        xml.feature("org.gnu.gdb.xtensa.core")?;
+        xml.reg(Reg::new("pc", 32).regnum(0).ty("code_ptr"))?;
+
+        let mut name = [0; 4];
+        for i in 0..self.config.num_aregs() {
+            xml.reg(Reg::new(indexed_name(&mut name, "ar", i), 32).regnum(1 + i))?;
+        }
+
+        let special = [
+            ("lbeg", 65, "code_ptr", self.config.has_loops()),
+            ("lend", 66, "code_ptr", self.config.has_loops()),
+            ("lcount", 67, "uint32", self.config.has_loops()),
+            ("sar", 68, "uint32", true),
+            ("windowbase", 69, "uint32", self.config.is_windowed()),
+            ("windowstart", 70, "uint32", self.config.is_windowed()),
+            ("ps", 71, "uint32", true),
+        ];
+        for (name, regnum, ty, present) in special {
+            if present {
+                xml.reg(Reg::new(name, 32).regnum(regnum).ty(ty))?;
+            }
+        }
+
+        for i in 0..16 {
+            let ty = match i {
+                1 => "data_ptr",
+                _ => "uint32",
+            };
+            let reg = Reg::new(indexed_name(&mut name, "a", i), 32)
+                .regnum(72 + i)
+                .ty(ty)
+                .group("general");
+            xml.reg(reg)?;
+        }
+
+        xml.finish().map(drop)
+    }
+}
+
+impl fmt::Display for XtensaXml {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_tdesc(TdescWriter::target_fmt(f))
+            .map_err(|_| fmt::Error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xtensa_xml() {
+        let xml = XtensaXml::new(XtensaConfig::default()).to_string();
+        assert!(xml.contains(r#"<reg name="ar63" bitsize="32" regnum="64"/>"#));
+        assert!(xml.contains(r#"<reg name="lcount" bitsize="32" regnum="67""#));
+        assert!(xml.contains(r#"<reg name="windowstart" bitsize="32" regnum="70""#));
+        assert!(xml.contains(r#"<reg name="a15" bitsize="32" regnum="87""#));
+        assert!(xml.ends_with("</feature></target>"));
+
+        let xml = XtensaXml::new(XtensaConfig::new(16).unwrap()).to_string();
+        assert!(xml.contains(r#"<reg name="ar15" bitsize="32" regnum="16"/>"#));
+        assert!(!xml.contains("ar16"));
+        assert!(!xml.contains("lbeg"));
+        assert!(!xml.contains("windowbase"));
+        assert!(xml.contains(r#"<reg name="ps" bitsize="32" regnum="71""#));
+    }
+
+    #[test]
+    fn xtensa_xml_read_range() {
+        let xml = XtensaXml::new(XtensaConfig::default());
+        let expected = xml.to_string();
+
+        let mut buf = [0; 0x100];
+        let mut offset = 0;
+        let mut data = Vec::new();
+        loop {
+            let n = xml.read_range(offset, 0xff, &mut buf);
+            if n == 0 {
+                break;
+            }
+            data.extend_from_slice(&buf[..n]);
+            offset += n as u64;
+        }
+
+        assert_eq!(data, expected.as_bytes());
+    }
+}
@@ -24,6 +24,8 @@ use num_traits::FromPrimitive;
 use num_traits::PrimInt;
 use num_traits::Unsigned;
 
+pub mod tdesc;
+
 /// Register identifier for target registers.
 ///
 /// These identifiers are used by GDB to signal which register to read/wite when
@@ -90,6 +92,43 @@ pub trait Registers: Default + Debug + Clone + PartialEq {
     fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()>;
 }
 
+/// Metadata describing a single architectural register.
+///
+/// Returned by [`Arch::reg_info`], and intended for tools (e.g: `monitor`
+/// commands, tracers) that need to print or parse registers without
+/// hard-coding per-architecture tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegInfo<'a> {
+    /// The register's name, as used in the arch's target description XML.
+    pub name: &'a str,
+    /// An alternate name for the register (e.g: an ABI name such as `sp`).
+    pub alt_name: Option<&'a str>,
+    /// The raw GDB register number, as accepted by [`RegId::from_raw_id`].
+    pub regnum: usize,
+    /// Size of the register, in bits.
+    pub bitsize: usize,
+    /// The register group this register belongs to (e.g: `general`,
+    /// `float`, `vector`, `system`).
+    pub group: &'a str,
+    /// The DWARF register number for this register.
+    pub dwarf: Option<usize>,
+    /// The generic role this register plays (e.g: stack pointer), if any.
+    pub generic: Option<lldb::Generic>,
+}
+
+impl RegInfo<'_> {
+    /// Size of the register, in bytes (rounded up).
+    pub fn size(&self) -> usize {
+        self.bitsize.div_ceil(8)
+    }
+
+    /// Check if `name` matches either the register's primary or alternate
+    /// name.
+    pub fn matches(&self, name: &str) -> bool {
+        self.name == name || self.alt_name == Some(name)
+    }
+}
+
 /// Breakpoint kind for specific architectures.
 ///
 /// This trait corresponds to the _kind_ field of the "z" and "Z" breakpoint
@@ -203,6 +242,37 @@ pub trait Arch {
         let _ = reg_id;
         None
     }
+
+    /// (optional) Return metadata for the `idx`-th register of the
+    /// architecture.
+    ///
+    /// Registers are enumerated using dense indices starting at `0`, and
+    /// enumeration ends at the first index that returns `None`. Note that `idx`
+    /// is _not_ the raw GDB register number, as an arch's register numbering
+    /// may contain gaps. Use [`RegInfo::regnum`] to obtain the register number.
+    ///
+    /// This method is not used by `gdbstub` itself, and is provided for the
+    /// benefit of tools (e.g: `monitor` commands) which need to display or
+    /// parse registers in an architecture-agnostic manner.
+    #[inline(always)]
+    fn reg_info(idx: usize) -> Option<RegInfo<'static>> {
+        let _ = idx;
+        None
+    }
+
+    /// (optional) Resolve a register name into the arch's `RegId` and
+    /// optional register size.
+    ///
+    /// The default implementation performs a linear search over
+    /// [`Arch::reg_info`], matching against both primary and alternate
+    /// register names. Implementations with a more efficient lookup strategy
+    /// may override this method.
+    fn reg_id_from_name(name: &str) -> Option<(Self::RegId, Option<NonZeroUsize>)> {
+        let info = (0..)
+            .map_while(Self::reg_info)
+            .find(|info| info.matches(name))?;
+        Self::RegId::from_raw_id(info.regnum)
+    }
 }
 
 /// LLDB-specific types supporting [`Arch::lldb_register_info`] and
@@ -296,6 +366,7 @@ pub mod lldb {
     }
 
     /// Describes the generic types that most CPUs have.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[non_exhaustive]
     pub enum Generic {
         /// Program counter register
@@ -0,0 +1,998 @@
+//! A typed, `no_std` builder for target description XML.
+//!
+//! Rather than writing target description XML by hand, targets can use
+//! [`TdescWriter`] to generate it into a caller-supplied buffer (or any
+//! [`fmt::Write`] implementation), with each
+//! register's number and "g" packet placement tracked along the way. The
+//! resulting [`RegLayout`] can then be checked against the target's
+//! [`Registers`] implementation, ensuring that the XML reported to the GDB
+//! client can't silently diverge from `Registers::gdb_serialize`.
+//!
+//! ```
+//! use gdbstub::arch::tdesc::{Field, Reg, TdescWriter, Type};
+//!
+//! let mut buf = [0; 1024];
+//! let mut xml = TdescWriter::target(&mut buf);
+//! xml.architecture("arm")?;
+//! xml.feature("org.gnu.gdb.arm.core")?;
+//! xml.ty(Type::Flags {
+//!     id: "cpsr_flags",
+//!     size: 4,
+//!     fields: &[Field::bits("M", 0, 4), Field::bit("T", 5), Field::bit("N", 31)],
+//! })?;
+//! for name in &["r0", "r1", "r2", "r3"] {
+//!     xml.reg(Reg::new(name, 32))?;
+//! }
+//! xml.reg(Reg::new("pc", 32).regnum(15).ty("code_ptr"))?;
+//! xml.reg(Reg::new("cpsr", 32).regnum(25).ty("cpsr_flags"))?;
+//! let (len, layout) = xml.finish()?;
+//!
+//! assert_eq!(layout.g_packet_len(), 6 * 4);
+//! assert_eq!(layout.next_regnum(), 26);
+//! assert!(core::str::from_utf8(&buf[..len])
+//!     .unwrap()
+//!     .ends_with(r#"<reg name="cpsr" bitsize="32" regnum="25" type="cpsr_flags"/></feature></target>"#));
+//! # Ok::<(), gdbstub::arch::tdesc::TdescError>(())
+//! ```
+//!
+//! Refer to the GDB docs on [Target Descriptions] for details on the
+//! underlying XML format.
+//!
+//! [Target Descriptions]:
+//!     https://sourceware.org/gdb/current/onlinedocs/gdb.html/Target-Descriptions.html
+
+use super::Registers;
+use core::fmt;
+use core::fmt::Write;
+
+/// Errors which may occur when generating a target description.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TdescError {
+    /// The output buffer is too small to hold the generated XML.
+    BufferTooSmall,
+    /// The underlying `fmt::Write` implementation returned an error.
+    Fmt,
+    /// A register or type was added outside of a feature.
+    NoFeature,
+    /// An element was added out of order (e.g: `<architecture>` after a
+    /// `<feature>`, or `<architecture>` in a standalone feature document).
+    OutOfOrder,
+    /// The register's size is zero, or is not a multiple of 8 bits.
+    InvalidBitsize,
+    /// The register's number is not greater than the previously added
+    /// register's number.
+    NonMonotonicRegnum,
+    /// A register included in the "g" packet was added after a register
+    /// excluded from the "g" packet.
+    GPacketAfterExcluded,
+    /// The size of the "g" packet described by the target description does
+    /// not match the output of `Registers::gdb_serialize`.
+    GPacketMismatch {
+        /// "g" packet size (in bytes) described by the target description.
+        expected: usize,
+        /// "g" packet size (in bytes) generated by `Registers::gdb_serialize`.
+        actual: usize,
+    },
+}
+
+impl fmt::Display for TdescError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TdescError::BufferTooSmall => f.write_str("output buffer too small"),
+            TdescError::Fmt => f.write_str("error writing to output"),
+            TdescError::NoFeature => f.write_str("register or type added outside of a feature"),
+            TdescError::OutOfOrder => f.write_str("element added out of order"),
+            TdescError::InvalidBitsize => {
+                f.write_str("register size is not a whole number of bytes")
+            }
+            TdescError::NonMonotonicRegnum => f.write_str("register numbers must be increasing"),
+            TdescError::GPacketAfterExcluded => f.write_str(
+                "\"g\" packet register added after a register excluded from the \"g\" packet",
+            ),
+            TdescError::GPacketMismatch { expected, actual } => write!(
+                f,
+                "\"g\" packet size mismatch: expected {} bytes, got {} bytes",
+                expected, actual
+            ),
+        }
+    }
+}
+
+/// A register definition (i.e: a `<reg>` element).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reg<'a> {
+    name: &'a str,
+    bitsize: usize,
+    regnum: Option<usize>,
+    ty: Option<&'a str>,
+    group: Option<&'a str>,
+    in_g_packet: bool,
+}
+
+impl<'a> Reg<'a> {
+    /// Create a new register with the specified name and size (in bits).
+    ///
+    /// By default, the register is assigned the register number following
+    /// the previously added register, and is included in the "g" packet.
+    pub fn new(name: &'a str, bitsize: usize) -> Self {
+        Reg {
+            name,
+            bitsize,
+            regnum: None,
+            ty: None,
+            group: None,
+            in_g_packet: true,
+        }
+    }
+
+    /// Set an explicit register number.
+    pub fn regnum(self, regnum: usize) -> Self {
+        Reg {
+            regnum: Some(regnum),
+            ..self
+        }
+    }
+
+    /// Set the register's type (e.g: `"int"`, `"code_ptr"`, `"ieee_double"`,
+    /// or the `id` of a type defined via [`TdescWriter::ty`]).
+    pub fn ty(self, ty: &'a str) -> Self {
+        Reg {
+            ty: Some(ty),
+            ..self
+        }
+    }
+
+    /// Set the register group the register belongs to (e.g: `"general"`,
+    /// `"float"`, `"vector"`).
+    pub fn group(self, group: &'a str) -> Self {
+        Reg {
+            group: Some(group),
+            ..self
+        }
+    }
+
+    /// Exclude the register from the "g" packet, requiring the GDB client to
+    /// access it individually via `read_register` / `write_register`.
+    ///
+    /// Registers excluded from the "g" packet must have higher register
+    /// numbers than all registers included in the "g" packet.
+    pub fn exclude_from_g_packet(self) -> Self {
+        Reg {
+            in_g_packet: false,
+            ..self
+        }
+    }
+}
+
+/// A field of a `<union>`, `<struct>`, or `<flags>` type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Field<'a> {
+    name: &'a str,
+    ty: Option<&'a str>,
+    bits: Option<(usize, usize)>,
+}
+
+impl<'a> Field<'a> {
+    /// A field of the specified type.
+    pub fn new(name: &'a str, ty: &'a str) -> Self {
+        Field {
+            name,
+            ty: Some(ty),
+            bits: None,
+        }
+    }
+
+    /// A bitfield spanning bits `start..=end`.
+    pub fn bits(name: &'a str, start: usize, end: usize) -> Self {
+        Field {
+            name,
+            ty: None,
+            bits: Some((start, end)),
+        }
+    }
+
+    /// A single-bit field.
+    pub fn bit(name: &'a str, bit: usize) -> Self {
+        Field::bits(name, bit, bit)
+    }
+
+    /// Set the type of a bitfield (e.g: `"bool"`, `"uint8"`).
+    pub fn ty(self, ty: &'a str) -> Self {
+        Field {
+            ty: Some(ty),
+            ..self
+        }
+    }
+}
+
+/// A type definition, which may be referenced by subsequent registers (and
+/// types) via its `id`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Type<'a> {
+    /// A `<vector>` of `count` elements of type `ty`.
+    Vector {
+        /// The type's `id`.
+        id: &'a str,
+        /// The type of each element.
+        ty: &'a str,
+        /// The number of elements.
+        count: usize,
+    },
+    /// A `<union>` of the specified fields.
+    Union {
+        /// The type's `id`.
+        id: &'a str,
+        /// The union's fields. Bitfields are not permitted.
+        fields: &'a [Field<'a>],
+    },
+    /// A `<struct>` of the specified fields.
+    ///
+    /// If `size` is specified, the struct's fields must all be bitfields.
+    Struct {
+        /// The type's `id`.
+        id: &'a str,
+        /// The struct's size (in bytes).
+        size: Option<usize>,
+        /// The struct's fields.
+        fields: &'a [Field<'a>],
+    },
+    /// A set of `<flags>` stored in a `size` byte register.
+    Flags {
+        /// The type's `id`.
+        id: &'a str,
+        /// The flags' size (in bytes).
+        size: usize,
+        /// The flags' fields. All fields must be bitfields.
+        fields: &'a [Field<'a>],
+    },
+}
+
+/// The register numbering and "g" packet layout described by a (possibly
+/// partial) target description.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RegLayout {
+    next_regnum: usize,
+    num_regs: usize,
+    g_packet_len: usize,
+    g_packet_closed: bool,
+}
+
+impl RegLayout {
+    /// The register number assigned to the next register without an explicit
+    /// register number.
+    pub fn next_regnum(&self) -> usize {
+        self.next_regnum
+    }
+
+    /// The number of registers described.
+    pub fn num_regs(&self) -> usize {
+        self.num_regs
+    }
+
+    /// The total size (in bytes) of the "g" packet.
+    pub fn g_packet_len(&self) -> usize {
+        self.g_packet_len
+    }
+
+    /// Check that `regs` serializes to a "g" packet of the size described by
+    /// the target description.
+    pub fn check_registers(&self, regs: &impl Registers) -> Result<(), TdescError> {
+        let mut actual = 0;
+        regs.gdb_serialize(|_| actual += 1);
+        if actual != self.g_packet_len {
+            return Err(TdescError::GPacketMismatch {
+                expected: self.g_packet_len,
+                actual,
+            });
+        }
+        Ok(())
+    }
+
+    /// Add a register to the layout (without generating any XML), returning
+    /// its register number.
+    ///
+    /// On error, the layout is left unchanged.
+    pub fn add_reg(&mut self, reg: &Reg<'_>) -> Result<usize, TdescError> {
+        if reg.bitsize == 0 || (reg.bitsize / 8) * 8 != reg.bitsize {
+            return Err(TdescError::InvalidBitsize);
+        }
+
+        let regnum = match reg.regnum {
+            Some(regnum) if regnum < self.next_regnum => {
+                return Err(TdescError::NonMonotonicRegnum)
+            }
+            Some(regnum) => regnum,
+            None => self.next_regnum,
+        };
+
+        if reg.in_g_packet {
+            if self.g_packet_closed {
+                return Err(TdescError::GPacketAfterExcluded);
+            }
+            self.g_packet_len += reg.bitsize / 8;
+        } else {
+            self.g_packet_closed = true;
+        }
+
+        self.next_regnum = regnum + 1;
+        self.num_regs += 1;
+        Ok(regnum)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Nothing has been written yet.
+    Start,
+    /// The document header (and any `<architecture>`, `<osabi>`, or
+    /// `<compatible>` elements) have been written.
+    Header,
+    /// A `<feature>` element is open.
+    Feature,
+    /// A `<feature>` has been closed by an `<xi:include>` (or a pre-written
+    /// `<feature>` element).
+    Body,
+}
+
+/// Where the generated XML is written to.
+enum Output<'b> {
+    Buf(&'b mut [u8]),
+    Fmt(&'b mut dyn Write),
+}
+
+impl fmt::Debug for Output<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Output::Buf(buf) => f.debug_tuple("Buf").field(buf).finish(),
+            Output::Fmt(_) => f.write_str("Fmt(..)"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind<'a> {
+    /// A complete `<target>` document (i.e: `target.xml`).
+    Target,
+    /// A standalone `<feature>` document, suitable for `<xi:include>`.
+    Feature(&'a str),
+}
+
+/// A target description XML writer.
+///
+/// Elements are written into the caller-supplied buffer (or `fmt::Write`
+/// implementation) as they are added, and the document is closed by
+/// [`finish`](Self::finish).
+///
+/// Registers are numbered in the order they are added, and must be added in
+/// increasing register number order (gaps are permitted). Registers are laid
+/// out in the "g" packet in the same order, matching the order in which
+/// `Registers::gdb_serialize` must emit them.
+#[derive(Debug)]
+pub struct TdescWriter<'a, 'b> {
+    out: Output<'b>,
+    len: usize,
+    kind: Kind<'a>,
+    state: State,
+    layout: RegLayout,
+}
+
+impl<'a, 'b> TdescWriter<'a, 'b> {
+    /// Create a writer for a complete `<target>` document (i.e: the root
+    /// `target.xml` annex).
+    pub fn target(buf: &'b mut [u8]) -> Self {
+        Self::new(Output::Buf(buf), Kind::Target, RegLayout::default())
+    }
+
+    /// Create a writer for a complete `<target>` document, which streams the
+    /// generated XML into `out` (e.g: a `fmt::Formatter`).
+    pub fn target_fmt(out: &'b mut dyn Write) -> Self {
+        Self::new(Output::Fmt(out), Kind::Target, RegLayout::default())
+    }
+
+    fn new(out: Output<'b>, kind: Kind<'a>, layout: RegLayout) -> Self {
+        TdescWriter {
+            out,
+            len: 0,
+            kind,
+            state: State::Start,
+            layout,
+        }
+    }
+
+    /// Create a writer for a standalone `<feature>` document, to be pulled
+    /// into a target description via [`include`](Self::include).
+    ///
+    /// The feature's registers are numbered following the registers
+    /// described by `start`, which should be the [`layout`](Self::layout) of
+    /// the including document at the point of inclusion.
+    pub fn feature_doc(buf: &'b mut [u8], name: &'a str, start: RegLayout) -> Self {
+        Self::new(Output::Buf(buf), Kind::Feature(name), start)
+    }
+
+    /// Create a writer for a standalone `<feature>` document, which streams
+    /// the generated XML into `out` (e.g: a `fmt::Formatter`).
+    pub fn feature_doc_fmt(out: &'b mut dyn Write, name: &'a str, start: RegLayout) -> Self {
+        Self::new(Output::Fmt(out), Kind::Feature(name), start)
+    }
+
+    /// Set the target's `<architecture>` (e.g: `"riscv:rv64"`,
+    /// `"i386:x86-64"`).
+    pub fn architecture(&mut self, architecture: &str) -> Result<(), TdescError> {
+        self.header_elem("architecture", architecture)
+    }
+
+    /// Set the target's `<osabi>` (e.g: `"GNU/Linux"`).
+    pub fn osabi(&mut self, osabi: &str) -> Result<(), TdescError> {
+        self.header_elem("osabi", osabi)
+    }
+
+    /// Add a `<compatible>` architecture.
+    pub fn compatible(&mut self, architecture: &str) -> Result<(), TdescError> {
+        self.header_elem("compatible", architecture)
+    }
+
+    /// Start a new `<feature>`, closing the previous one (if any). All
+    /// subsequently added registers and types will belong to this feature.
+    pub fn feature(&mut self, name: &str) -> Result<(), TdescError> {
+        if self.kind != Kind::Target {
+            return Err(TdescError::OutOfOrder);
+        }
+        self.begin()?;
+        if self.state == State::Feature {
+            self.write_str("</feature>")?;
+        }
+        self.write_str("<feature name=\"")?;
+        self.write_escaped(name)?;
+        self.write_str("\">")?;
+        self.state = State::Feature;
+        Ok(())
+    }
+
+    /// Add an `<xi:include>` of the feature document at `href`, closing the
+    /// current feature (if any).
+    ///
+    /// `included` is the layout returned by
+    /// [`finish`](Self::finish)ing the included document's writer, which
+    /// must have been created using this writer's current
+    /// [`layout`](Self::layout).
+    pub fn include(&mut self, href: &str, included: RegLayout) -> Result<(), TdescError> {
+        self.end_feature(included)?;
+        self.write_str("<xi:include href=\"")?;
+        self.write_escaped(href)?;
+        self.write_str("\"/>")?;
+        self.layout = included;
+        Ok(())
+    }
+
+    /// Add a complete, pre-written `<feature>` element (e.g: one of the
+    /// feature files bundled with GDB), closing the current feature (if
+    /// any).
+    ///
+    /// `described` is the layout after adding the feature's registers to
+    /// this writer's current [`layout`](Self::layout) (e.g: via
+    /// [`RegLayout::add_reg`]). `xml` is written as-is, and is not validated
+    /// against `described`.
+    pub fn feature_xml(&mut self, xml: &str, described: RegLayout) -> Result<(), TdescError> {
+        self.end_feature(described)?;
+        self.write_str(xml)?;
+        self.layout = described;
+        Ok(())
+    }
+
+    /// Add a type definition to the current feature.
+    pub fn ty(&mut self, ty: Type<'_>) -> Result<(), TdescError> {
+        self.in_feature()?;
+        match ty {
+            Type::Vector { id, ty, count } => {
+                self.write_str("<vector id=\"")?;
+                self.write_escaped(id)?;
+                self.write_str("\" type=\"")?;
+                self.write_escaped(ty)?;
+                self.write_fmt(format_args!("\" count=\"{}\"/>", count))
+            }
+            Type::Union { id, fields } => self.compound("union", id, None, fields),
+            Type::Struct { id, size, fields } => self.compound("struct", id, size, fields),
+            Type::Flags { id, size, fields } => self.compound("flags", id, Some(size), fields),
+        }
+    }
+
+    /// Add raw type definitions (e.g: `<vector>`, `<union>`, or `<flags>`
+    /// elements) to the current feature.
+    ///
+    /// `xml` is written as-is, and is not validated.
+    pub fn types_xml(&mut self, xml: &str) -> Result<(), TdescError> {
+        self.in_feature()?;
+        self.write_str(xml)
+    }
+
+    /// Add a register to the current feature, returning its register number.
+    pub fn reg(&mut self, reg: Reg<'_>) -> Result<usize, TdescError> {
+        self.in_feature()?;
+
+        let mut layout = self.layout;
+        let regnum = layout.add_reg(&reg)?;
+
+        self.write_str("<reg name=\"")?;
+        self.write_escaped(reg.name)?;
+        self.write_fmt(format_args!(
+            "\" bitsize=\"{}\" regnum=\"{}\"",
+            reg.bitsize, regnum
+        ))?;
+        if let Some(ty) = reg.ty {
+            self.write_str(" type=\"")?;
+            self.write_escaped(ty)?;
+            self.write_str("\"")?;
+        }
+        if let Some(group) = reg.group {
+            self.write_str(" group=\"")?;
+            self.write_escaped(group)?;
+            self.write_str("\"")?;
+        }
+        self.write_str("/>")?;
+
+        self.layout = layout;
+        Ok(regnum)
+    }
+
+    /// The register layout described by the document so far.
+    pub fn layout(&self) -> RegLayout {
+        self.layout
+    }
+
+    /// Close the document, returning the number of bytes written into the
+    /// buffer (or `fmt::Write` implementation), alongside the described
+    /// register layout.
+    pub fn finish(mut self) -> Result<(usize, RegLayout), TdescError> {
+        self.begin()?;
+        match self.kind {
+            Kind::Target => {
+                if self.state == State::Feature {
+                    self.write_str("</feature>")?;
+                }
+                self.write_str("</target>")?;
+            }
+            Kind::Feature(_) => self.write_str("</feature>")?,
+        }
+        Ok((self.len, self.layout))
+    }
+
+    fn begin(&mut self) -> Result<(), TdescError> {
+        if self.state != State::Start {
+            return Ok(());
+        }
+
+        self.write_str(r#"<?xml version="1.0"?>"#)?;
+        match self.kind {
+            Kind::Target => {
+                self.write_str(concat!(
+                    r#"<!DOCTYPE target SYSTEM "gdb-target.dtd">"#,
+                    r#"<target version="1.0">"#
+                ))?;
+                self.state = State::Header;
+            }
+            Kind::Feature(name) => {
+                self.write_str(r#"<!DOCTYPE feature SYSTEM "gdb-target.dtd"><feature name=""#)?;
+                self.write_escaped(name)?;
+                self.write_str("\">")?;
+                self.state = State::Feature;
+            }
+        }
+        Ok(())
+    }
+
+    fn header_elem(&mut self, tag: &str, val: &str) -> Result<(), TdescError> {
+        if self.kind != Kind::Target {
+            return Err(TdescError::OutOfOrder);
+        }
+        self.begin()?;
+        if self.state != State::Header {
+            return Err(TdescError::OutOfOrder);
+        }
+        self.write_fmt(format_args!("<{}>", tag))?;
+        self.write_escaped(val)?;
+        self.write_fmt(format_args!("</{}>", tag))
+    }
+
+    /// Close the current feature (if any) ahead of a feature described
+    /// elsewhere, checking that `next` follows on from the current layout.
+    fn end_feature(&mut self, next: RegLayout) -> Result<(), TdescError> {
+        if self.kind != Kind::Target {
+            return Err(TdescError::OutOfOrder);
+        }
+        if next.next_regnum < self.layout.next_regnum || next.num_regs < self.layout.num_regs {
+            return Err(TdescError::NonMonotonicRegnum);
+        }
+        if next.g_packet_len < self.layout.g_packet_len
+            || (self.layout.g_packet_closed && next.g_packet_len != self.layout.g_packet_len)
+        {
+            return Err(TdescError::GPacketAfterExcluded);
+        }
+
+        self.begin()?;
+        if self.state == State::Feature {
+            self.write_str("</feature>")?;
+        }
+        self.state = State::Body;
+        Ok(())
+    }
+
+    fn in_feature(&mut self) -> Result<(), TdescError> {
+        self.begin()?;
+        if self.state != State::Feature {
+            return Err(TdescError::NoFeature);
+        }
+        Ok(())
+    }
+
+    fn compound(
+        &mut self,
+        tag: &str,
+        id: &str,
+        size: Option<usize>,
+        fields: &[Field<'_>],
+    ) -> Result<(), TdescError> {
+        self.write_fmt(format_args!("<{} id=\"", tag))?;
+        self.write_escaped(id)?;
+        self.write_str("\"")?;
+        if let Some(size) = size {
+            self.write_fmt(format_args!(" size=\"{}\"", size))?;
+        }
+        self.write_str(">")?;
+        for field in fields {
+            self.write_str("<field name=\"")?;
+            self.write_escaped(field.name)?;
+            self.write_str("\"")?;
+            if let Some((start, end)) = field.bits {
+                self.write_fmt(format_args!(" start=\"{}\" end=\"{}\"", start, end))?;
+            }
+            if let Some(ty) = field.ty {
+                self.write_str(" type=\"")?;
+                self.write_escaped(ty)?;
+                self.write_str("\"")?;
+            }
+            self.write_str("/>")?;
+        }
+        self.write_fmt(format_args!("</{}>", tag))
+    }
+
+    fn write_escaped(&mut self, s: &str) -> Result<(), TdescError> {
+        let mut last = 0;
+        for (i, c) in s.match_indices(&['&', '<', '>', '"'][..]) {
+            self.write_str(&s[last..i])?;
+            self.write_str(match c {
+                "&" => "&amp;",
+                "<" => "&lt;",
+                ">" => "&gt;",
+                _ => "&quot;",
+            })?;
+            last = i + c.len();
+        }
+        self.write_str(&s[last..])
+    }
+
+    fn write_str(&mut self, s: &str) -> Result<(), TdescError> {
+        match &mut self.out {
+            Output::Buf(buf) => {
+                let dst = buf
+                    .get_mut(self.len..self.len + s.len())
+                    .ok_or(TdescError::BufferTooSmall)?;
+                dst.copy_from_slice(s.as_bytes());
+            }
+            Output::Fmt(out) => out.write_str(s).map_err(|_| TdescError::Fmt)?,
+        }
+        self.len += s.len();
+        Ok(())
+    }
+
+    fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> Result<(), TdescError> {
+        struct Adapter<'w, 'a, 'b> {
+            writer: &'w mut TdescWriter<'a, 'b>,
+            err: Option<TdescError>,
+        }
+
+        impl Write for Adapter<'_, '_, '_> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.writer.write_str(s).map_err(|e| {
+                    self.err = Some(e);
+                    fmt::Error
+                })
+            }
+        }
+
+        let mut adapter = Adapter {
+            writer: self,
+            err: None,
+        };
+        adapter
+            .write_fmt(args)
+            .map_err(|_| adapter.err.unwrap_or(TdescError::Fmt))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default, Clone, PartialEq)]
+    struct TestRegs {
+        r: [u32; 4],
+        pc: u32,
+    }
+
+    impl Registers for TestRegs {
+        type ProgramCounter = u32;
+
+        fn pc(&self) -> u32 {
+            self.pc
+        }
+
+        fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+            for reg in self.r.iter().chain(core::iter::once(&self.pc)) {
+                for b in reg.to_le_bytes() {
+                    write_byte(Some(b))
+                }
+            }
+        }
+
+        fn gdb_deserialize(&mut self, _bytes: &[u8]) -> Result<(), ()> {
+            Ok(())
+        }
+    }
+
+    fn as_str(buf: &[u8], len: usize) -> &str {
+        core::str::from_utf8(&buf[..len]).unwrap()
+    }
+
+    #[test]
+    fn target_doc() {
+        let mut buf = [0; 1024];
+        let mut xml = TdescWriter::target(&mut buf);
+        xml.architecture("test").unwrap();
+        xml.osabi("GNU/Linux").unwrap();
+        xml.feature("org.test.core").unwrap();
+        xml.ty(Type::Vector {
+            id: "v4i",
+            ty: "int32",
+            count: 4,
+        })
+        .unwrap();
+        xml.ty(Type::Union {
+            id: "vec",
+            fields: &[Field::new("i", "v4i"), Field::new("q", "uint128")],
+        })
+        .unwrap();
+        xml.ty(Type::Struct {
+            id: "pair",
+            size: None,
+            fields: &[Field::new("lo", "uint32"), Field::new("hi", "uint32")],
+        })
+        .unwrap();
+        assert_eq!(xml.reg(Reg::new("r0", 32)).unwrap(), 0);
+        assert_eq!(xml.reg(Reg::new("pc", 32).ty("code_ptr")).unwrap(), 1);
+        xml.feature("org.test.vec").unwrap();
+        assert_eq!(
+            xml.reg(Reg::new("v0", 128).regnum(8).ty("vec").group("vector"))
+                .unwrap(),
+            8
+        );
+        let (len, layout) = xml.finish().unwrap();
+
+        assert_eq!(
+            as_str(&buf, len),
+            concat!(
+                r#"<?xml version="1.0"?>"#,
+                r#"<!DOCTYPE target SYSTEM "gdb-target.dtd">"#,
+                r#"<target version="1.0">"#,
+                r#"<architecture>test</architecture>"#,
+                r#"<osabi>GNU/Linux</osabi>"#,
+                r#"<feature name="org.test.core">"#,
+                r#"<vector id="v4i" type="int32" count="4"/>"#,
+                r#"<union id="vec"><field name="i" type="v4i"/><field name="q" type="uint128"/></union>"#,
+                r#"<struct id="pair"><field name="lo" type="uint32"/><field name="hi" type="uint32"/></struct>"#,
+                r#"<reg name="r0" bitsize="32" regnum="0"/>"#,
+                r#"<reg name="pc" bitsize="32" regnum="1" type="code_ptr"/>"#,
+                r#"</feature>"#,
+                r#"<feature name="org.test.vec">"#,
+                r#"<reg name="v0" bitsize="128" regnum="8" type="vec" group="vector"/>"#,
+                r#"</feature>"#,
+                r#"</target>"#,
+            )
+        );
+        assert_eq!(layout.num_regs(), 3);
+        assert_eq!(layout.next_regnum(), 9);
+        assert_eq!(layout.g_packet_len(), 24);
+    }
+
+    #[test]
+    fn flags_and_escaping() {
+        let mut buf = [0; 256];
+        let mut xml = TdescWriter::target(&mut buf);
+        xml.feature("a&b").unwrap();
+        xml.ty(Type::Flags {
+            id: "f",
+            size: 4,
+            fields: &[Field::bit("Z", 30), Field::bits("M", 0, 3).ty("uint8")],
+        })
+        .unwrap();
+        let (len, _) = xml.finish().unwrap();
+
+        assert!(as_str(&buf, len).ends_with(concat!(
+            r#"<feature name="a&amp;b">"#,
+            r#"<flags id="f" size="4">"#,
+            r#"<field name="Z" start="30" end="30"/>"#,
+            r#"<field name="M" start="0" end="3" type="uint8"/>"#,
+            r#"</flags></feature></target>"#,
+        )));
+    }
+
+    #[test]
+    fn include_feature_doc() {
+        let mut target_buf = [0; 512];
+        let mut feature_buf = [0; 512];
+
+        let mut xml = TdescWriter::target(&mut target_buf);
+        xml.feature("org.test.core").unwrap();
+        xml.reg(Reg::new("r0", 64)).unwrap();
+
+        let mut fpu = TdescWriter::feature_doc(&mut feature_buf, "org.test.fpu", xml.layout());
+        assert_eq!(fpu.reg(Reg::new("f0", 64)).unwrap(), 1);
+        assert_eq!(fpu.architecture("test"), Err(TdescError::OutOfOrder));
+        let (fpu_len, fpu_layout) = fpu.finish().unwrap();
+
+        xml.include("fpu.xml", fpu_layout).unwrap();
+        assert_eq!(xml.architecture("test"), Err(TdescError::OutOfOrder));
+        assert_eq!(xml.reg(Reg::new("r1", 64)), Err(TdescError::NoFeature));
+        let (len, layout) = xml.finish().unwrap();
+
+        assert_eq!(
+            as_str(&feature_buf, fpu_len),
+            concat!(
+                r#"<?xml version="1.0"?>"#,
+                r#"<!DOCTYPE feature SYSTEM "gdb-target.dtd">"#,
+                r#"<feature name="org.test.fpu">"#,
+                r#"<reg name="f0" bitsize="64" regnum="1"/>"#,
+                r#"</feature>"#,
+            )
+        );
+        assert!(as_str(&target_buf, len)
+            .ends_with(r#"</feature><xi:include href="fpu.xml"/></target>"#));
+        assert_eq!(layout.next_regnum(), 2);
+        assert_eq!(layout.g_packet_len(), 16);
+    }
+
+    #[test]
+    fn include_stale_layout() {
+        let mut buf = [0; 512];
+        let mut xml = TdescWriter::target(&mut buf);
+        xml.feature("org.test.core").unwrap();
+        xml.reg(Reg::new("r0", 64)).unwrap();
+        assert_eq!(
+            xml.include("fpu.xml", RegLayout::default()),
+            Err(TdescError::NonMonotonicRegnum)
+        );
+    }
+
+    #[test]
+    fn prewritten_feature() {
+        let mut buf = [0; 512];
+        let mut xml = TdescWriter::target(&mut buf);
+        xml.feature("org.test.core").unwrap();
+        xml.reg(Reg::new("r0", 64)).unwrap();
+
+        let mut sys = xml.layout();
+        sys.add_reg(&Reg::new("sys", 64).regnum(8).exclude_from_g_packet())
+            .unwrap();
+        xml.feature_xml(
+            r#"<feature name="org.test.sys"><reg name="sys" bitsize="64" regnum="8"/></feature>"#,
+            sys,
+        )
+        .unwrap();
+        assert_eq!(xml.reg(Reg::new("r1", 64)), Err(TdescError::NoFeature));
+        assert_eq!(
+            xml.feature_xml("", RegLayout::default()),
+            Err(TdescError::NonMonotonicRegnum)
+        );
+
+        let mut late = xml.layout();
+        assert_eq!(
+            late.add_reg(&Reg::new("r1", 64)),
+            Err(TdescError::GPacketAfterExcluded)
+        );
+        late.g_packet_len += 8;
+        assert_eq!(
+            xml.feature_xml("", late),
+            Err(TdescError::GPacketAfterExcluded)
+        );
+
+        let (len, layout) = xml.finish().unwrap();
+        assert!(as_str(&buf, len).ends_with(concat!(
+            r#"<reg name="r0" bitsize="64" regnum="0"/></feature>"#,
+            r#"<feature name="org.test.sys"><reg name="sys" bitsize="64" regnum="8"/></feature>"#,
+            r#"</target>"#,
+        )));
+        assert_eq!(layout.next_regnum(), 9);
+        assert_eq!(layout.g_packet_len(), 8);
+    }
+
+    #[test]
+    fn regnum_checks() {
+        let mut buf = [0; 512];
+        let mut xml = TdescWriter::target(&mut buf);
+        assert_eq!(xml.reg(Reg::new("r0", 32)), Err(TdescError::NoFeature));
+        xml.feature("org.test.core").unwrap();
+        assert_eq!(xml.architecture("test"), Err(TdescError::OutOfOrder));
+        assert_eq!(xml.reg(Reg::new("r0", 0)), Err(TdescError::InvalidBitsize));
+        assert_eq!(xml.reg(Reg::new("r0", 12)), Err(TdescError::InvalidBitsize));
+        xml.reg(Reg::new("r0", 32).regnum(4)).unwrap();
+        assert_eq!(
+            xml.reg(Reg::new("r1", 32).regnum(4)),
+            Err(TdescError::NonMonotonicRegnum)
+        );
+        xml.reg(Reg::new("sys", 32).exclude_from_g_packet())
+            .unwrap();
+        assert_eq!(
+            xml.reg(Reg::new("r1", 32)),
+            Err(TdescError::GPacketAfterExcluded)
+        );
+
+        // failed registers don't affect the layout
+        let layout = xml.layout();
+        assert_eq!(layout.num_regs(), 2);
+        assert_eq!(layout.next_regnum(), 6);
+        assert_eq!(layout.g_packet_len(), 4);
+    }
+
+    #[test]
+    fn fmt_output() {
+        let mut buf = [0; 256];
+        let mut xml = TdescWriter::target(&mut buf);
+        xml.feature("org.test.core").unwrap();
+        xml.types_xml(r#"<vector id="v2" type="uint8" count="2"/>"#)
+            .unwrap();
+        xml.reg(Reg::new("r0", 16).ty("v2")).unwrap();
+        let (len, layout) = xml.finish().unwrap();
+
+        let mut s = String::new();
+        let mut xml = TdescWriter::target_fmt(&mut s);
+        assert_eq!(xml.types_xml(""), Err(TdescError::NoFeature));
+        xml.feature("org.test.core").unwrap();
+        xml.types_xml(r#"<vector id="v2" type="uint8" count="2"/>"#)
+            .unwrap();
+        xml.reg(Reg::new("r0", 16).ty("v2")).unwrap();
+        assert_eq!(xml.finish(), Ok((len, layout)));
+        assert_eq!(s, as_str(&buf, len));
+    }
+
+    #[test]
+    fn buffer_too_small() {
+        let mut buf = [0; 64];
+        let mut xml = TdescWriter::target(&mut buf);
+        assert_eq!(
+            xml.feature("org.test.core"),
+            Err(TdescError::BufferTooSmall)
+        );
+    }
+
+    #[test]
+    fn check_registers() {
+        let mut buf = [0; 512];
+        let mut xml = TdescWriter::target(&mut buf);
+        xml.feature("org.test.core").unwrap();
+        for name in &["r0", "r1", "r2", "r3"] {
+            xml.reg(Reg::new(name, 32)).unwrap();
+        }
+        let layout = xml.layout();
+        assert_eq!(
+            layout.check_registers(&TestRegs::default()),
+            Err(TdescError::GPacketMismatch {
+                expected: 16,
+                actual: 20
+            })
+        );
+
+        xml.reg(Reg::new("pc", 32).ty("code_ptr")).unwrap();
+        let (_, layout) = xml.finish().unwrap();
+        assert_eq!(layout.check_registers(&TestRegs::default()), Ok(()));
+    }
+}
@@ -0,0 +1,109 @@
+use crate::conn::AsyncConnection;
+use crate::conn::Connection;
+use crate::util::managed_vec::ManagedVec;
+use core::fmt::Display;
+use core::fmt::{self};
+use managed::ManagedSlice;
+
+/// An error which may occur while using a [`BufferedAsyncConnection`].
+#[derive(Debug)]
+pub enum AsyncConnectionError<E> {
+    /// The underlying [`AsyncConnection`] returned an error.
+    Connection(E),
+    /// The outgoing data did not fit in the provided buffer.
+    BufferOverflow,
+}
+
+impl<E: Display> Display for AsyncConnectionError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AsyncConnectionError::Connection(e) => write!(f, "{}", e),
+            AsyncConnectionError::BufferOverflow => write!(
+                f,
+                "outgoing data did not fit in the `BufferedAsyncConnection`'s buffer"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: fmt::Debug + Display> std::error::Error for AsyncConnectionError<E> {}
+
+/// Adapts an [`AsyncConnection`] into a [`Connection`], for use with
+/// [`GdbStub::run_async`](crate::stub::GdbStub::run_async).
+///
+/// The GDB stub writes its responses synchronously, so outgoing data is
+/// buffered in-memory, and only written out to the underlying
+/// `AsyncConnection` by `GdbStub::run_async` once the stub is waiting for new
+/// data.
+pub struct BufferedAsyncConnection<'a, C> {
+    conn: C,
+    buf: ManagedSlice<'a, u8>,
+    len: usize,
+}
+
+impl<'a, C: AsyncConnection> BufferedAsyncConnection<'a, C> {
+    /// Wrap an `AsyncConnection`, buffering outgoing data in a growable,
+    /// heap-allocated buffer.
+    #[cfg(feature = "alloc")]
+    pub fn new(conn: C) -> BufferedAsyncConnection<'static, C> {
+        BufferedAsyncConnection {
+            conn,
+            buf: ManagedSlice::Owned(alloc::vec::Vec::new()),
+            len: 0,
+        }
+    }
+
+    /// Wrap an `AsyncConnection`, buffering outgoing data in a pre-allocated
+    /// buffer (instead of heap-allocating).
+    ///
+    /// The buffer must be large enough to fit all the data sent in response to
+    /// a single incoming packet / stop reason (typically a bit more than the
+    /// packet buffer's size). If it isn't, `GdbStub::run_async` will return an
+    /// [`AsyncConnectionError::BufferOverflow`] error.
+    pub fn with_buffer(conn: C, buf: &'a mut [u8]) -> Self {
+        BufferedAsyncConnection {
+            conn,
+            buf: ManagedSlice::Borrowed(buf),
+            len: 0,
+        }
+    }
+
+    /// Return a mutable reference to the underlying `AsyncConnection`.
+    pub fn get_mut(&mut self) -> &mut C {
+        &mut self.conn
+    }
+
+    /// Unwrap the underlying `AsyncConnection`, discarding any buffered data.
+    pub fn into_inner(self) -> C {
+        self.conn
+    }
+
+    /// Write out any buffered data to the underlying `AsyncConnection`.
+    pub(crate) async fn flush_buffered(&mut self) -> Result<(), C::Error> {
+        if self.len == 0 {
+            return Ok(());
+        }
+
+        let len = core::mem::take(&mut self.len);
+        self.conn.write_all(&self.buf[..len]).await?;
+        self.conn.flush().await
+    }
+}
+
+impl<C: AsyncConnection> Connection for BufferedAsyncConnection<'_, C> {
+    type Error = AsyncConnectionError<C::Error>;
+
+    fn write(&mut self, byte: u8) -> Result<(), Self::Error> {
+        ManagedVec::new_with_idx(&mut self.buf, self.len)
+            .push(byte)
+            .map_err(|_| AsyncConnectionError::BufferOverflow)?;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        // buffered data is written out by `GdbStub::run_async`
+        Ok(())
+    }
+}
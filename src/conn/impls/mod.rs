@@ -10,8 +10,44 @@ mod tcpstream;
 #[cfg(all(feature = "std", unix))]
 mod unixstream;
 
+use crate::conn::AsyncConnection;
 use crate::conn::Connection;
 use crate::conn::ConnectionExt;
+use core::future::Future;
+use core::time::Duration;
+
+/// Shared implementation of [`ConnectionExt::read_timeout`] for sockets which
+/// support `SO_RCVTIMEO` (i.e: `TcpStream` and `UnixStream`).
+///
+/// The socket's existing read timeout is restored afterwards, so as not to
+/// clobber any timeout configured by the user.
+#[cfg(feature = "std")]
+fn read_byte_with_timeout<S: std::io::Read>(
+    stream: &mut S,
+    timeout: Duration,
+    get_read_timeout: impl Fn(&S) -> std::io::Result<Option<Duration>>,
+    set_read_timeout: impl Fn(&S, Option<Duration>) -> std::io::Result<()>,
+) -> std::io::Result<Option<u8>> {
+    let prev_timeout = get_read_timeout(stream)?;
+    // `set_read_timeout` rejects zero-length timeouts
+    set_read_timeout(stream, Some(timeout.max(Duration::from_nanos(1))))?;
+
+    let mut buf = [0u8];
+    let res = stream.read_exact(&mut buf);
+    set_read_timeout(stream, prev_timeout)?;
+    match res {
+        Ok(_) => Ok(Some(buf[0])),
+        Err(ref e)
+            if matches!(
+                e.kind(),
+                std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+            ) =>
+        {
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
 
 impl<E> Connection for &mut dyn Connection<Error = E> {
     type Error = E;
@@ -61,4 +97,52 @@ impl<E> ConnectionExt for &mut dyn ConnectionExt<Error = E> {
     fn peek(&mut self) -> Result<Option<u8>, Self::Error> {
         (**self).peek()
     }
+
+    fn read_timeout(&mut self, timeout: Duration) -> Result<Option<u8>, Self::Error> {
+        (**self).read_timeout(timeout)
+    }
+}
+
+impl<C: AsyncConnection + ?Sized> AsyncConnection for &mut C {
+    type Error = C::Error;
+
+    fn read(&mut self) -> impl Future<Output = Result<u8, Self::Error>> {
+        (**self).read()
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> impl Future<Output = Result<(), Self::Error>> {
+        (**self).write_all(buf)
+    }
+
+    fn flush(&mut self) -> impl Future<Output = Result<(), Self::Error>> {
+        (**self).flush()
+    }
+
+    fn read_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<Option<u8>, Self::Error>> {
+        (**self).read_timeout(timeout)
+    }
+
+    fn on_session_start(&mut self) -> impl Future<Output = Result<(), Self::Error>> {
+        (**self).on_session_start()
+    }
+}
+
+#[cfg(all(test, feature = "std", unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixStream;
+
+    #[test]
+    fn read_timeout_preserves_socket_timeout() {
+        let (mut conn, _peer) = UnixStream::pair().unwrap();
+        let user_timeout = Some(Duration::from_secs(5));
+        conn.set_read_timeout(user_timeout).unwrap();
+
+        let res = ConnectionExt::read_timeout(&mut conn, Duration::from_millis(10));
+        assert_eq!(res.unwrap(), None);
+        assert_eq!(conn.read_timeout().unwrap(), user_timeout);
+    }
 }
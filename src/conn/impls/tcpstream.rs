@@ -1,5 +1,6 @@
 use crate::conn::Connection;
 use crate::conn::ConnectionExt;
+use core::time::Duration;
 use std::net::TcpStream;
 
 impl Connection for TcpStream {
@@ -52,4 +53,14 @@ impl ConnectionExt for TcpStream {
             Err(e) => Err(e),
         }
     }
+
+    fn read_timeout(&mut self, timeout: Duration) -> Result<Option<u8>, Self::Error> {
+        self.set_nonblocking(false)?;
+        super::read_byte_with_timeout(
+            self,
+            timeout,
+            TcpStream::read_timeout,
+            TcpStream::set_read_timeout,
+        )
+    }
 }
@@ -1,5 +1,6 @@
 use crate::conn::Connection;
 use crate::conn::ConnectionExt;
+use core::time::Duration;
 use std::io;
 use std::os::unix::net::UnixStream;
 
@@ -99,4 +100,14 @@ impl ConnectionExt for UnixStream {
             Err(e) => Err(e),
         }
     }
+
+    fn read_timeout(&mut self, timeout: Duration) -> Result<Option<u8>, Self::Error> {
+        self.set_nonblocking(false)?;
+        super::read_byte_with_timeout(
+            self,
+            timeout,
+            UnixStream::read_timeout,
+            UnixStream::set_read_timeout,
+        )
+    }
 }
@@ -1,12 +1,34 @@
 //! Traits to perform in-order, serial, byte-wise I/O.
 
+use core::future::Future;
+use core::time::Duration;
+
+pub use buffered_async::AsyncConnectionError;
+pub use buffered_async::BufferedAsyncConnection;
+#[cfg(feature = "std")]
+pub use pipe::PipeConnection;
+#[cfg(all(feature = "serial", unix))]
+pub use serial::Parity;
+#[cfg(all(feature = "serial", unix))]
+pub use serial::SerialPort;
+
+mod buffered_async;
 mod impls;
+#[cfg(feature = "std")]
+mod pipe;
+#[cfg(all(feature = "serial", unix))]
+mod serial;
 
 /// A trait to perform in-order, serial, byte-wise I/O.
 ///
 /// When the `std` feature is enabled, this trait is automatically implemented
 /// for [`TcpStream`](std::net::TcpStream) and
-/// [`UnixStream`](std::os::unix::net::UnixStream) (on unix systems).
+/// [`UnixStream`](std::os::unix::net::UnixStream) (on unix systems), and the
+/// [`PipeConnection`] type can be used to communicate over a separate reader /
+/// writer pair (e.g: stdin / stdout).
+///
+/// When the `serial` feature is enabled, the `SerialPort` type can be used to
+/// connect to a GDB client over a serial port / tty (on unix systems).
 pub trait Connection {
     /// Transport-specific error type.
     type Error;
@@ -70,4 +92,69 @@ pub trait ConnectionExt: Connection {
     /// Returns a byte (if one is available) without removing that byte from the
     /// queue. Subsequent calls to `peek` MUST return the same byte.
     fn peek(&mut self) -> Result<Option<u8>, Self::Error>;
+
+    /// Read a single byte, blocking for at most `timeout`. Returns `None` if
+    /// no byte arrived in time.
+    ///
+    /// This method is used by `GdbStub::run_blocking` to re-transmit packets
+    /// which the GDB client has not acknowledged (see
+    /// [`GdbStubBuilder::ack_timeout`](crate::stub::GdbStubBuilder::ack_timeout)).
+    ///
+    /// This method's default implementation calls `self.read()`, and never
+    /// times out.
+    fn read_timeout(&mut self, timeout: Duration) -> Result<Option<u8>, Self::Error> {
+        let _ = timeout;
+        self.read().map(Some)
+    }
+}
+
+/// A trait to perform in-order, serial, byte-wise I/O asynchronously.
+///
+/// This trait is used as part of `gdbstub`'s
+/// [`GdbStub::run_async`](crate::stub::GdbStub::run_async) API, alongside
+/// [`BufferedAsyncConnection`].
+///
+/// `AsyncConnection` is runtime-agnostic, and is typically implemented via a
+/// thin wrapper around an async runtime's IO types (e.g: a `tokio` TCP stream,
+/// or an `embassy` UART).
+///
+/// Implementations are free to use `async fn` when implementing this trait.
+pub trait AsyncConnection {
+    /// Transport-specific error type.
+    type Error;
+
+    /// Read a single byte.
+    fn read(&mut self) -> impl Future<Output = Result<u8, Self::Error>>;
+
+    /// Write the entire buffer.
+    fn write_all(&mut self, buf: &[u8]) -> impl Future<Output = Result<(), Self::Error>>;
+
+    /// Flush this Connection, ensuring that all intermediately buffered
+    /// contents reach their destination.
+    fn flush(&mut self) -> impl Future<Output = Result<(), Self::Error>>;
+
+    /// Read a single byte, waiting for at most `timeout`. Returns `None` if no
+    /// byte arrived in time.
+    ///
+    /// This method is used by `GdbStub::run_async` to re-transmit packets
+    /// which the GDB client has not acknowledged (see
+    /// [`GdbStubBuilder::ack_timeout`](crate::stub::GdbStubBuilder::ack_timeout)).
+    ///
+    /// This method's default implementation calls `self.read()`, and never
+    /// times out.
+    fn read_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<Option<u8>, Self::Error>> {
+        let _ = timeout;
+        async move { self.read().await.map(Some) }
+    }
+
+    /// Called at the start of a debugging session _before_ any GDB packets have
+    /// been sent/received.
+    ///
+    /// This method's default implementation is a no-op.
+    fn on_session_start(&mut self) -> impl Future<Output = Result<(), Self::Error>> {
+        async { Ok(()) }
+    }
 }
@@ -0,0 +1,220 @@
+use crate::conn::Connection;
+use crate::conn::ConnectionExt;
+use core::time::Duration;
+use std::collections::VecDeque;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::sync::mpsc;
+use std::thread;
+
+/// A [`Connection`] over a separate reader / writer pair, such as the
+/// process's stdin / stdout, or a child process's pipes.
+///
+/// This enables running a GDB stub as a subprocess of the GDB client, without
+/// having to allocate a TCP port / socket path (i.e: via `target remote |
+/// ./my-emulator`).
+///
+/// Arbitrary [`Read`] implementations don't support non-blocking reads, so
+/// `PipeConnection` spawns a background thread which continuously reads from
+/// the reader, and forwards any received data to the `PipeConnection`. The
+/// thread exits once the reader reaches EOF / returns an error, or after the
+/// `PipeConnection` is dropped and the next read completes.
+///
+/// _Note:_ When communicating over stdout, make sure that nothing else in the
+/// process writes to stdout during the debugging session (e.g: use a logger
+/// which outputs to stderr)!
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use gdbstub::conn::PipeConnection;
+///
+/// // launched via `target remote | ./my-emulator`
+/// let conn = PipeConnection::stdio()?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct PipeConnection<W> {
+    writer: W,
+    rx: mpsc::Receiver<io::Result<Vec<u8>>>,
+    pending: VecDeque<u8>,
+}
+
+impl PipeConnection<io::Stdout> {
+    /// Communicate over the process's stdin / stdout.
+    pub fn stdio() -> io::Result<PipeConnection<io::Stdout>> {
+        PipeConnection::new(io::stdin(), io::stdout())
+    }
+}
+
+impl<W: Write> PipeConnection<W> {
+    /// Create a new `PipeConnection`, receiving data from `reader`, and
+    /// sending data to `writer`.
+    pub fn new<R>(mut reader: R, writer: W) -> io::Result<PipeConnection<W>>
+    where
+        R: Read + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        thread::Builder::new()
+            .name("gdbstub-pipe-reader".into())
+            .spawn(move || {
+                let mut buf = [0; 1024];
+                loop {
+                    let res = match reader.read(&mut buf) {
+                        Ok(0) => Err(io::ErrorKind::UnexpectedEof.into()),
+                        Ok(n) => Ok(buf[..n].to_vec()),
+                        Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                        Err(e) => Err(e),
+                    };
+                    let done = res.is_err();
+                    if tx.send(res).is_err() || done {
+                        break;
+                    }
+                }
+            })?;
+
+        Ok(PipeConnection {
+            writer,
+            rx,
+            pending: VecDeque::new(),
+        })
+    }
+
+    /// Return a mutable reference to the underlying writer.
+    pub fn writer_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    fn on_recv(&mut self, res: io::Result<Vec<u8>>) -> io::Result<()> {
+        self.pending.extend(res?);
+        Ok(())
+    }
+}
+
+/// The reader thread exits after reporting EOF / an error, so any subsequent
+/// reads report the connection as closed.
+fn disconnected() -> io::Error {
+    io::ErrorKind::UnexpectedEof.into()
+}
+
+impl<W: Write> Connection for PipeConnection<W> {
+    type Error = std::io::Error;
+
+    fn write(&mut self, byte: u8) -> Result<(), Self::Error> {
+        Write::write_all(&mut self.writer, &[byte])
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        Write::write_all(&mut self.writer, buf)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Write::flush(&mut self.writer)
+    }
+}
+
+impl<W: Write> ConnectionExt for PipeConnection<W> {
+    fn read(&mut self) -> Result<u8, Self::Error> {
+        loop {
+            if let Some(byte) = self.pending.pop_front() {
+                return Ok(byte);
+            }
+            let res = self.rx.recv().map_err(|_| disconnected())?;
+            self.on_recv(res)?;
+        }
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>, Self::Error> {
+        if self.pending.is_empty() {
+            match self.rx.try_recv() {
+                Ok(res) => self.on_recv(res)?,
+                Err(mpsc::TryRecvError::Empty) => {}
+                Err(mpsc::TryRecvError::Disconnected) => return Err(disconnected()),
+            }
+        }
+        Ok(self.pending.front().copied())
+    }
+
+    fn read_timeout(&mut self, timeout: Duration) -> Result<Option<u8>, Self::Error> {
+        if self.pending.is_empty() {
+            match self.rx.recv_timeout(timeout) {
+                Ok(res) => self.on_recv(res)?,
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => return Err(disconnected()),
+            }
+        }
+        Ok(self.pending.pop_front())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn read_write() {
+        let mut conn = PipeConnection::new(&b"$g#67"[..], Vec::new()).unwrap();
+
+        let mut data = Vec::new();
+        for _ in 0..5 {
+            data.push(conn.read().unwrap());
+        }
+        assert_eq!(data, b"$g#67");
+        assert_eq!(
+            conn.read().unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+        assert_eq!(
+            conn.peek().unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+
+        conn.write_all(b"+$OK#9a").unwrap();
+        conn.flush().unwrap();
+        assert_eq!(conn.writer_mut(), b"+$OK#9a");
+    }
+
+    #[test]
+    fn peek() {
+        let (reader, mut writer) = io::pipe().unwrap();
+        let mut conn = PipeConnection::new(reader, io::sink()).unwrap();
+
+        assert_eq!(conn.peek().unwrap(), None);
+
+        writer.write_all(&[0x03]).unwrap();
+        // data is forwarded by the reader thread asynchronously
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while conn.peek().unwrap().is_none() {
+            assert!(Instant::now() < deadline);
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        assert_eq!(conn.peek().unwrap(), Some(0x03));
+        assert_eq!(conn.read().unwrap(), 0x03);
+        assert_eq!(conn.peek().unwrap(), None);
+    }
+
+    #[test]
+    fn read_timeout() {
+        let (reader, mut writer) = io::pipe().unwrap();
+        let mut conn = PipeConnection::new(reader, io::sink()).unwrap();
+
+        assert_eq!(conn.read_timeout(Duration::from_millis(10)).unwrap(), None);
+
+        writer.write_all(b"+").unwrap();
+        assert_eq!(
+            conn.read_timeout(Duration::from_secs(5)).unwrap(),
+            Some(b'+')
+        );
+
+        // closing the write end of the pipe closes the connection
+        drop(writer);
+        assert_eq!(
+            conn.read_timeout(Duration::from_secs(5))
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+}
@@ -0,0 +1,578 @@
+use crate::conn::Connection;
+use crate::conn::ConnectionExt;
+use core::time::Duration;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+use std::time::Instant;
+
+/// The interrupt byte GDB sends to halt a running target (i.e: Ctrl-C).
+const INTERRUPT: u8 = 0x03;
+
+/// Parity checking mode used by a [`SerialPort`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parity {
+    /// No parity bit.
+    None,
+    /// Odd parity.
+    Odd,
+    /// Even parity.
+    Even,
+}
+
+/// A [`Connection`] over a serial port / tty device (e.g: `/dev/ttyUSB0`, or
+/// the pty allocated by QEMU's `-serial pty` option).
+///
+/// The tty is put into raw mode with 8 data bits, no parity, and 1 stop bit
+/// (which can be changed via [`SerialPort::set_parity`]). The tty's original
+/// settings are restored once the `SerialPort` is dropped.
+///
+/// Serial lines don't have an out-of-band channel for Ctrl-C, so GDB can
+/// instead be configured to interrupt the target by sending a break condition
+/// (via `set remote interrupt-sequence BREAK`). Any break conditions received
+/// by `SerialPort` are reported as a regular Ctrl-C interrupt (i.e: a `0x03`
+/// byte), while bytes received with a parity / framing error are dropped.
+///
+/// _Note:_ Configuring the tty requires `unsafe` FFI calls into `libc`, so
+/// when the `paranoid_unsafe` feature is enabled, `SerialPort::open` always
+/// fails with an [`io::ErrorKind::Unsupported`] error.
+pub struct SerialPort {
+    file: File,
+    original: libc::termios,
+    rx: VecDeque<u8>,
+    marks: MarkDecoder,
+}
+
+impl SerialPort {
+    /// Open the tty at `path`, configuring it to communicate at the given
+    /// `baud_rate`.
+    ///
+    /// Any stale data in the tty's input / output queues is discarded.
+    pub fn open<P: AsRef<Path>>(path: P, baud_rate: u32) -> io::Result<SerialPort> {
+        // opening a modem line without `O_NONBLOCK` blocks until the carrier
+        // is detected (DCD), which may never happen
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NOCTTY | libc::O_NONBLOCK)
+            .open(path)?;
+
+        let original = sys::tcgetattr(&file)?;
+
+        let mut termios = original;
+        sys::cfmakeraw(&mut termios);
+        termios.c_cflag |= libc::CLOCAL | libc::CREAD;
+        termios.c_cflag &= !libc::CSTOPB;
+        // report break conditions and parity / framing errors in-band, using
+        // `\xff\x00` escape sequences (see `MarkDecoder`)
+        termios.c_iflag &= !(libc::IGNBRK | libc::BRKINT | libc::IGNPAR | libc::INPCK);
+        termios.c_iflag |= libc::PARMRK;
+        termios.c_cc[libc::VMIN] = 1;
+        termios.c_cc[libc::VTIME] = 0;
+        set_speed(&mut termios, baud_rate)?;
+        sys::tcsetattr(&file, &termios)?;
+
+        // with `CLOCAL` set, blocking I/O no longer waits on the carrier
+        sys::clear_nonblock(&file)?;
+        sys::tcflush(&file)?;
+
+        Ok(SerialPort {
+            file,
+            original,
+            rx: VecDeque::new(),
+            marks: MarkDecoder::Normal,
+        })
+    }
+
+    /// Change the tty's baud rate.
+    ///
+    /// On Linux, only the standard baud rates (e.g: 9600, 115200, etc...) are
+    /// supported.
+    pub fn set_baud_rate(&mut self, baud_rate: u32) -> io::Result<()> {
+        let mut termios = sys::tcgetattr(&self.file)?;
+        set_speed(&mut termios, baud_rate)?;
+        sys::tcsetattr(&self.file, &termios)
+    }
+
+    /// Change the tty's parity checking mode.
+    pub fn set_parity(&mut self, parity: Parity) -> io::Result<()> {
+        let mut termios = sys::tcgetattr(&self.file)?;
+        termios.c_cflag &= !(libc::PARENB | libc::PARODD);
+        termios.c_iflag &= !libc::INPCK;
+        match parity {
+            Parity::None => {}
+            Parity::Odd => {
+                termios.c_cflag |= libc::PARENB | libc::PARODD;
+                termios.c_iflag |= libc::INPCK;
+            }
+            Parity::Even => {
+                termios.c_cflag |= libc::PARENB;
+                termios.c_iflag |= libc::INPCK;
+            }
+        }
+        sys::tcsetattr(&self.file, &termios)
+    }
+
+    /// Wait up to `timeout` (or indefinitely, if `None`) for data to arrive,
+    /// decoding any received data into `self.rx`.
+    ///
+    /// Returns `false` if no data was received.
+    fn fill(&mut self, timeout: Option<Duration>) -> io::Result<bool> {
+        let timeout_ms = match timeout {
+            Some(timeout) => timeout
+                .as_nanos()
+                .div_ceil(1_000_000)
+                .min(libc::c_int::MAX as u128) as libc::c_int,
+            None => -1,
+        };
+
+        match sys::poll(&self.file, timeout_ms) {
+            Ok(0) => return Ok(false),
+            Ok(_) => {}
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => return Ok(false),
+            Err(e) => return Err(e),
+        }
+
+        // the fd is readable, so this won't block
+        let mut buf = [0; 256];
+        let n = match self.file.read(&mut buf) {
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => n,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        for &byte in &buf[..n] {
+            if let Some(byte) = self.marks.decode(byte) {
+                self.rx.push_back(byte)
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl Drop for SerialPort {
+    fn drop(&mut self) {
+        if let Err(e) = sys::tcsetattr(&self.file, &self.original) {
+            warn!("failed to restore tty settings: {}", e);
+        }
+    }
+}
+
+/// Decodes the escape sequences used to report errors when `PARMRK` is set:
+///
+/// - `\xff\xff` is a literal `\xff` byte
+/// - `\xff\x00\x00` is a break condition
+/// - `\xff\x00 X` is a byte `X` received with a parity / framing error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MarkDecoder {
+    Normal,
+    Escape,
+    Error,
+}
+
+impl MarkDecoder {
+    fn decode(&mut self, byte: u8) -> Option<u8> {
+        let (next, res) = match (*self, byte) {
+            (MarkDecoder::Normal, 0xff) => (MarkDecoder::Escape, None),
+            (MarkDecoder::Normal, byte) => (MarkDecoder::Normal, Some(byte)),
+            (MarkDecoder::Escape, 0x00) => (MarkDecoder::Error, None),
+            (MarkDecoder::Escape, byte) => (MarkDecoder::Normal, Some(byte)),
+            (MarkDecoder::Error, 0x00) => {
+                trace!("received break condition, reporting as interrupt");
+                (MarkDecoder::Normal, Some(INTERRUPT))
+            }
+            (MarkDecoder::Error, byte) => {
+                warn!(
+                    "dropping byte {:#04x} received with a parity / framing error",
+                    byte
+                );
+                (MarkDecoder::Normal, None)
+            }
+        };
+        *self = next;
+        res
+    }
+}
+
+/// Thin wrappers around the `libc` tty APIs used by `SerialPort`.
+#[cfg(not(feature = "paranoid_unsafe"))]
+mod sys {
+    use std::fs::File;
+    use std::io;
+    use std::os::unix::io::AsRawFd;
+
+    pub fn cvt(t: libc::c_int) -> io::Result<libc::c_int> {
+        if t == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(t)
+        }
+    }
+
+    pub fn tcgetattr(file: &File) -> io::Result<libc::termios> {
+        // SAFETY: `libc::termios` is a plain C struct, for which all-zeroes is
+        // a valid bit pattern
+        let mut termios = unsafe { core::mem::zeroed() };
+        // SAFETY: `file` is an open file descriptor, and `termios` is a valid
+        // `libc::termios`
+        cvt(unsafe { libc::tcgetattr(file.as_raw_fd(), &mut termios) })?;
+        Ok(termios)
+    }
+
+    pub fn tcsetattr(file: &File, termios: &libc::termios) -> io::Result<()> {
+        // SAFETY: `file` is an open file descriptor, and `termios` is a valid
+        // `libc::termios`
+        cvt(unsafe { libc::tcsetattr(file.as_raw_fd(), libc::TCSANOW, termios) })?;
+        Ok(())
+    }
+
+    pub fn tcflush(file: &File) -> io::Result<()> {
+        // SAFETY: `file` is an open file descriptor
+        cvt(unsafe { libc::tcflush(file.as_raw_fd(), libc::TCIOFLUSH) })?;
+        Ok(())
+    }
+
+    pub fn cfmakeraw(termios: &mut libc::termios) {
+        // SAFETY: `termios` is a valid, initialized `libc::termios`
+        unsafe { libc::cfmakeraw(termios) }
+    }
+
+    pub fn cfsetspeed(termios: &mut libc::termios, speed: libc::speed_t) -> io::Result<()> {
+        // SAFETY: `termios` is a valid `libc::termios`
+        cvt(unsafe { libc::cfsetispeed(termios, speed) })?;
+        // SAFETY: `termios` is a valid `libc::termios`
+        cvt(unsafe { libc::cfsetospeed(termios, speed) })?;
+        Ok(())
+    }
+
+    pub fn clear_nonblock(file: &File) -> io::Result<()> {
+        // SAFETY: `file` is an open file descriptor
+        let flags = cvt(unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GETFL) })?;
+        // SAFETY: `file` is an open file descriptor
+        cvt(unsafe { libc::fcntl(file.as_raw_fd(), libc::F_SETFL, flags & !libc::O_NONBLOCK) })?;
+        Ok(())
+    }
+
+    pub fn poll(file: &File, timeout_ms: libc::c_int) -> io::Result<libc::c_int> {
+        let mut pollfd = libc::pollfd {
+            fd: file.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: `pollfd` is a valid array of 1 `libc::pollfd`
+        cvt(unsafe { libc::poll(&mut pollfd, 1, timeout_ms) })
+    }
+}
+
+#[cfg(feature = "paranoid_unsafe")]
+mod sys {
+    use std::fs::File;
+    use std::io;
+
+    fn unsupported() -> io::Error {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            "cannot use `SerialPort` with `paranoid_unsafe`, as configuring a tty requires calling into `libc`",
+        )
+    }
+
+    pub fn tcgetattr(_file: &File) -> io::Result<libc::termios> {
+        Err(unsupported())
+    }
+
+    pub fn tcsetattr(_file: &File, _termios: &libc::termios) -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    pub fn tcflush(_file: &File) -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    pub fn cfmakeraw(_termios: &mut libc::termios) {
+        // unreachable, as `tcgetattr` always fails
+    }
+
+    pub fn cfsetspeed(_termios: &mut libc::termios, _speed: libc::speed_t) -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    pub fn clear_nonblock(_file: &File) -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    pub fn poll(_file: &File, _timeout_ms: libc::c_int) -> io::Result<libc::c_int> {
+        Err(unsupported())
+    }
+}
+
+fn set_speed(termios: &mut libc::termios, baud_rate: u32) -> io::Result<()> {
+    let speed = baud_rate_to_speed(baud_rate).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("unsupported baud rate: {}", baud_rate),
+        )
+    })?;
+    sys::cfsetspeed(termios, speed)
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn baud_rate_to_speed(baud_rate: u32) -> Option<libc::speed_t> {
+    let speed = match baud_rate {
+        50 => libc::B50,
+        75 => libc::B75,
+        110 => libc::B110,
+        134 => libc::B134,
+        150 => libc::B150,
+        200 => libc::B200,
+        300 => libc::B300,
+        600 => libc::B600,
+        1200 => libc::B1200,
+        1800 => libc::B1800,
+        2400 => libc::B2400,
+        4800 => libc::B4800,
+        9600 => libc::B9600,
+        19200 => libc::B19200,
+        38400 => libc::B38400,
+        57600 => libc::B57600,
+        115200 => libc::B115200,
+        230400 => libc::B230400,
+        460800 => libc::B460800,
+        500000 => libc::B500000,
+        576000 => libc::B576000,
+        921600 => libc::B921600,
+        1000000 => libc::B1000000,
+        1152000 => libc::B1152000,
+        1500000 => libc::B1500000,
+        2000000 => libc::B2000000,
+        2500000 => libc::B2500000,
+        3000000 => libc::B3000000,
+        3500000 => libc::B3500000,
+        4000000 => libc::B4000000,
+        _ => return None,
+    };
+    Some(speed)
+}
+
+// on other unix platforms, `speed_t` values are the baud rate itself
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn baud_rate_to_speed(baud_rate: u32) -> Option<libc::speed_t> {
+    match baud_rate {
+        0 => None,
+        _ => Some(baud_rate as libc::speed_t),
+    }
+}
+
+impl Connection for SerialPort {
+    type Error = std::io::Error;
+
+    fn write(&mut self, byte: u8) -> Result<(), Self::Error> {
+        Write::write_all(&mut self.file, &[byte])
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        Write::write_all(&mut self.file, buf)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Write::flush(&mut self.file)
+    }
+}
+
+impl ConnectionExt for SerialPort {
+    fn read(&mut self) -> Result<u8, Self::Error> {
+        loop {
+            if let Some(byte) = self.rx.pop_front() {
+                return Ok(byte);
+            }
+            self.fill(None)?;
+        }
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>, Self::Error> {
+        if self.rx.is_empty() {
+            self.fill(Some(Duration::ZERO))?;
+        }
+        Ok(self.rx.front().copied())
+    }
+
+    fn read_timeout(&mut self, timeout: Duration) -> Result<Option<u8>, Self::Error> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(byte) = self.rx.pop_front() {
+                return Ok(Some(byte));
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if !self.fill(Some(remaining))? && remaining.is_zero() {
+                return Ok(None);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, not(feature = "paranoid_unsafe")))]
+mod tests {
+    use super::sys::cvt;
+    use super::*;
+    use std::ffi::CStr;
+    use std::os::unix::io::AsRawFd;
+    use std::os::unix::io::FromRawFd;
+    use std::sync::Mutex;
+
+    /// Allocate a pseudo-terminal, returning the master end of the pty, and a
+    /// `SerialPort` connected to the slave end of the pty.
+    fn pty_pair() -> (File, SerialPort) {
+        // `ptsname` isn't thread-safe
+        static PTSNAME: Mutex<()> = Mutex::new(());
+
+        // SAFETY: the fd returned by `posix_openpt` is checked, and owned by
+        // the returned `File`
+        let master = unsafe {
+            let fd = cvt(libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY)).unwrap();
+            File::from_raw_fd(fd)
+        };
+        let path = {
+            let _guard = PTSNAME.lock().unwrap();
+            // SAFETY: `master` is an open pty master, and the string returned
+            // by `ptsname` is copied while holding `PTSNAME`
+            unsafe {
+                cvt(libc::grantpt(master.as_raw_fd())).unwrap();
+                cvt(libc::unlockpt(master.as_raw_fd())).unwrap();
+                let name = libc::ptsname(master.as_raw_fd());
+                assert!(!name.is_null());
+                CStr::from_ptr(name).to_str().unwrap().to_owned()
+            }
+        };
+
+        let port = SerialPort::open(path, 115200).unwrap();
+        (master, port)
+    }
+
+    fn cfgetospeed(port: &SerialPort) -> libc::speed_t {
+        let termios = sys::tcgetattr(&port.file).unwrap();
+        // SAFETY: `termios` is a valid `libc::termios`
+        unsafe { libc::cfgetospeed(&termios) }
+    }
+
+    #[test]
+    fn open_not_a_tty() {
+        assert!(SerialPort::open("/dev/null", 115200).is_err());
+    }
+
+    #[test]
+    fn pty_read_write() {
+        let (mut master, mut port) = pty_pair();
+
+        master.write_all(b"$g#67").unwrap();
+        let mut data = Vec::new();
+        for _ in 0..5 {
+            data.push(port.read().unwrap());
+        }
+        assert_eq!(data, b"$g#67");
+
+        port.write_all(b"+$OK#9a").unwrap();
+        port.flush().unwrap();
+        let mut buf = [0; 7];
+        master.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"+$OK#9a");
+    }
+
+    #[test]
+    fn pty_peek() {
+        let (mut master, mut port) = pty_pair();
+
+        assert_eq!(port.peek().unwrap(), None);
+
+        master.write_all(&[INTERRUPT]).unwrap();
+        // data written to the pty master is delivered asynchronously
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while port.peek().unwrap().is_none() {
+            assert!(Instant::now() < deadline);
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        assert_eq!(port.peek().unwrap(), Some(INTERRUPT));
+        assert_eq!(port.read().unwrap(), INTERRUPT);
+        assert_eq!(port.peek().unwrap(), None);
+    }
+
+    #[test]
+    fn pty_read_timeout() {
+        let (mut master, mut port) = pty_pair();
+
+        assert_eq!(port.read_timeout(Duration::from_millis(10)).unwrap(), None);
+
+        master.write_all(b"+").unwrap();
+        assert_eq!(
+            port.read_timeout(Duration::from_secs(5)).unwrap(),
+            Some(b'+')
+        );
+    }
+
+    #[test]
+    fn pty_escaped_0xff() {
+        let (mut master, mut port) = pty_pair();
+
+        // with `PARMRK` set, the line discipline escapes `\xff` as `\xff\xff`
+        master.write_all(&[0xff, 0x00, 0x00, b'a']).unwrap();
+        let mut data = Vec::new();
+        for _ in 0..4 {
+            data.push(port.read().unwrap());
+        }
+        assert_eq!(data, [0xff, 0x00, 0x00, b'a']);
+    }
+
+    #[test]
+    fn pty_configure() {
+        let (_master, mut port) = pty_pair();
+
+        port.set_baud_rate(9600).unwrap();
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        assert_eq!(
+            port.set_baud_rate(12345).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+        assert_eq!(cfgetospeed(&port), baud_rate_to_speed(9600).unwrap());
+
+        // ptys don't support parity bits, but do keep parity checking enabled
+        port.set_parity(Parity::Even).unwrap();
+        assert_ne!(sys::tcgetattr(&port.file).unwrap().c_iflag & libc::INPCK, 0);
+        port.set_parity(Parity::None).unwrap();
+        assert_eq!(sys::tcgetattr(&port.file).unwrap().c_iflag & libc::INPCK, 0);
+    }
+
+    #[test]
+    fn marks() {
+        let decode = |data: &[u8]| {
+            let mut marks = MarkDecoder::Normal;
+            data.iter()
+                .filter_map(|&b| marks.decode(b))
+                .collect::<Vec<u8>>()
+        };
+
+        assert_eq!(decode(b"$g#67"), b"$g#67");
+        assert_eq!(decode(&[0xff, 0xff, b'a']), [0xff, b'a']);
+        // break condition
+        assert_eq!(
+            decode(&[b'a', 0xff, 0x00, 0x00, b'b']),
+            [b'a', INTERRUPT, b'b']
+        );
+        // parity error
+        assert_eq!(decode(&[b'a', 0xff, 0x00, b'x', b'b']), [b'a', b'b']);
+    }
+}
+
+#[cfg(all(test, feature = "paranoid_unsafe"))]
+mod paranoid_tests {
+    use super::*;
+
+    #[test]
+    fn open_unsupported() {
+        let err = SerialPort::open("/dev/null", 115200).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+    }
+}
@@ -21,9 +21,14 @@
 //! - `std` (implies `alloc`)
 //!     - Implement `Connection` for [`TcpStream`](std::net::TcpStream) and
 //!       [`UnixStream`](std::os::unix::net::UnixStream).
+//!     - Add a [`conn::PipeConnection`] type, which implements `Connection`
+//!       over stdin / stdout (or any other reader / writer pair).
 //!     - Implement [`std::error::Error`] for `gdbstub::Error`.
 //!     - Add a `TargetError::Io` variant to simplify `std::io::Error` handling
 //!       from Target methods.
+//! - `serial` (implies `std`)
+//!     - Add a `conn::SerialPort` type, which implements `Connection` over a
+//!       serial port / tty device (on unix systems).
 //! - `paranoid_unsafe`
 //!     - Please refer to the [`unsafe` in `gdbstub`](https://github.com/daniel5151/gdbstub#unsafe-in-gdbstub)
 //!       section of the README.md for more details.
@@ -296,6 +301,14 @@
 //! See the [`GdbStubStateMachine`] docs for more details on how to use this
 //! API.
 //!
+//! Alternatively, projects using `async/await` can use the
+//! [`GdbStub::run_async`](stub::GdbStub::run_async) API, which drives the
+//! `GdbStubStateMachine` on the implementation's behalf. It works just like
+//! `GdbStub::run_blocking`, except that it requires an [`AsyncEventLoop`]
+//! implementation and an [`AsyncConnection`](conn::AsyncConnection) (wrapped
+//! in a [`BufferedAsyncConnection`](conn::BufferedAsyncConnection)). The API
+//! is runtime-agnostic, and can be used in `no_std` environments as well.
+//!
 //! <br>
 //!
 //! * * *
@@ -311,6 +324,7 @@
 //!
 //! [`GdbStubStateMachine`]: stub::state_machine::GdbStubStateMachine
 //! [`BlockingEventLoop`]: stub::run_blocking::BlockingEventLoop
+//! [`AsyncEventLoop`]: stub::run_async::AsyncEventLoop
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![cfg_attr(feature = "paranoid_unsafe", forbid(unsafe_code))]
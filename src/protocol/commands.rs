@@ -315,6 +315,12 @@ commands! {
         "qXfer:memory-map:read" => _qXfer_memory_map::qXferMemoryMapRead<'a>,
     }
 
+    memory_tagging use 'a {
+        "qMemTags" => _qMemTags::qMemTags<'a>,
+        "QMemTags" => _QMemTags_upcase::QMemTags<'a>,
+        "qIsAddressTagged" => _qIsAddressTagged::qIsAddressTagged<'a>,
+    }
+
     flash_operations use 'a {
         "vFlashErase" => _vFlashErase::vFlashErase<'a>,
         "vFlashWrite" => _vFlashWrite::vFlashWrite<'a>,
@@ -0,0 +1,86 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct QMemTags<'a> {
+    pub addr: &'a [u8],
+    pub len: usize,
+    pub tag_type: i32,
+    pub tags: &'a [u8],
+}
+
+impl<'a> ParseCommand<'a> for QMemTags<'a> {
+    #[inline(always)]
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = match buf.into_body() {
+            [b':', body @ ..] => body,
+            _ => return None,
+        };
+
+        let mut body = body.splitn_mut(2, |b| *b == b',');
+        let addr = decode_hex_buf(body.next()?).ok()?;
+
+        let mut body = body.next()?.splitn_mut(3, |b| *b == b':');
+        let len = decode_hex(body.next()?).ok()?;
+        // the tag type is sent as a hex-encoded 32-bit two's complement integer
+        let tag_type = decode_hex::<u32>(body.next()?).ok()? as i32;
+        let tags = decode_hex_buf(body.next()?).ok()?;
+
+        Some(QMemTags {
+            addr,
+            len,
+            tag_type,
+            tags,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! test_buf {
+        ($bufname:ident, $body:literal) => {
+            let mut test = $body.to_vec();
+            let mut buf = PacketBuf::new_with_raw_body(&mut test).unwrap();
+            if !buf.strip_prefix(b"QMemTags") {
+                panic!("invalid test");
+            }
+            let $bufname = buf;
+        };
+    }
+
+    #[test]
+    fn valid_QMemTags() {
+        test_buf!(buf, b"QMemTags:ffff00001000,20:1:0a0b");
+
+        let pkt = QMemTags::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.addr, [0xff, 0xff, 0x00, 0x00, 0x10, 0x00]);
+        assert_eq!(pkt.len, 0x20);
+        assert_eq!(pkt.tag_type, 1);
+        assert_eq!(pkt.tags, [0x0a, 0x0b]);
+    }
+
+    #[test]
+    fn valid_QMemTags_no_tags() {
+        test_buf!(buf, b"QMemTags:1000,10:1:");
+
+        let pkt = QMemTags::from_packet(buf).unwrap();
+
+        assert!(pkt.tags.is_empty());
+    }
+
+    #[test]
+    fn invalid_QMemTags_missing_tags() {
+        test_buf!(buf, b"QMemTags:1000,10:1");
+
+        assert!(QMemTags::from_packet(buf).is_none())
+    }
+
+    #[test]
+    fn invalid_QMemTags_bad_tags() {
+        test_buf!(buf, b"QMemTags:1000,10:1:xyz");
+
+        assert!(QMemTags::from_packet(buf).is_none())
+    }
+}
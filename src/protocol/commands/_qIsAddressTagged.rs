@@ -0,0 +1,18 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct qIsAddressTagged<'a> {
+    pub addr: &'a [u8],
+}
+
+impl<'a> ParseCommand<'a> for qIsAddressTagged<'a> {
+    #[inline(always)]
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let addr = match buf.into_body() {
+            [b':', addr @ ..] => decode_hex_buf(addr).ok().filter(|a| !a.is_empty())?,
+            _ => return None,
+        };
+
+        Some(qIsAddressTagged { addr })
+    }
+}
@@ -0,0 +1,105 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct qMemTags<'a> {
+    pub addr: &'a [u8],
+    pub len: usize,
+    pub tag_type: i32,
+
+    pub buf: &'a mut [u8],
+}
+
+impl<'a> ParseCommand<'a> for qMemTags<'a> {
+    #[inline(always)]
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        // Similar to the `m` packet, the decoded `addr` is left in-place, and the
+        // remainder of the packet buffer is used to store the read tags.
+        let (buf, body_range) = buf.into_raw_buf();
+        let body = buf.get_mut(body_range.start..body_range.end)?;
+
+        let body = match body {
+            [b':', body @ ..] => body,
+            _ => return None,
+        };
+
+        let mut body = body.split_mut(|b| *b == b',' || *b == b':');
+
+        let addr = decode_hex_buf(body.next()?).ok()?;
+        let addr_len = addr.len();
+        let len = decode_hex(body.next()?).ok()?;
+        // the tag type is sent as a hex-encoded 32-bit two's complement integer
+        let tag_type = decode_hex::<u32>(body.next()?).ok()? as i32;
+
+        if body.next().is_some() {
+            return None;
+        }
+
+        let addr_start = body_range.start + 1;
+
+        // ensures that `split_at_mut` doesn't panic
+        if buf.len() < addr_start + addr_len {
+            return None;
+        }
+
+        let (addr, buf) = buf.split_at_mut(addr_start + addr_len);
+        let addr = addr.get(addr_start..)?;
+
+        Some(qMemTags {
+            addr,
+            len,
+            tag_type,
+            buf,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! test_buf {
+        ($bufname:ident, $body:literal) => {
+            let mut test = $body.to_vec();
+            let mut buf = PacketBuf::new_with_raw_body(&mut test).unwrap();
+            if !buf.strip_prefix(b"qMemTags") {
+                panic!("invalid test");
+            }
+            let $bufname = buf;
+        };
+    }
+
+    #[test]
+    fn valid_qMemTags() {
+        test_buf!(buf, b"qMemTags:ffff00001000,20:1");
+
+        let pkt = qMemTags::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.addr, [0xff, 0xff, 0x00, 0x00, 0x10, 0x00]);
+        assert_eq!(pkt.len, 0x20);
+        assert_eq!(pkt.tag_type, 1);
+        assert!(!pkt.buf.is_empty());
+    }
+
+    #[test]
+    fn valid_qMemTags_negative_type() {
+        test_buf!(buf, b"qMemTags:1000,10:ffffffff");
+
+        let pkt = qMemTags::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.tag_type, -1);
+    }
+
+    #[test]
+    fn invalid_qMemTags_missing_type() {
+        test_buf!(buf, b"qMemTags:1000,10");
+
+        assert!(qMemTags::from_packet(buf).is_none())
+    }
+
+    #[test]
+    fn invalid_qMemTags_missing_colon() {
+        test_buf!(buf, b"qMemTags1000,10:1");
+
+        assert!(qMemTags::from_packet(buf).is_none())
+    }
+}
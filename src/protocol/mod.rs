@@ -18,5 +18,7 @@ pub(crate) use common::thread_id::IdKind;
 pub(crate) use common::thread_id::SpecificIdKind;
 pub(crate) use common::thread_id::SpecificThreadId;
 pub(crate) use packet::Packet;
+pub(crate) use response_writer::clear_last_packet;
+pub(crate) use response_writer::last_packet;
 pub(crate) use response_writer::Error as ResponseWriterError;
 pub(crate) use response_writer::ResponseWriter;
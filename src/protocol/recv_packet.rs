@@ -30,6 +30,16 @@ impl RecvPacketStateMachine {
         packet_buffer: &'b mut ManagedSlice<'_, u8>,
         byte: u8,
     ) -> Result<Option<&'b mut [u8]>, CapacityError<u8>> {
+        // '$' can never appear inside a packet, so receiving one mid-packet
+        // indicates that the rest of the current packet was lost in transit
+        // (e.g: over a lossy serial link). Discard the truncated packet, and
+        // start receiving the new one.
+        if byte == b'$' && !matches!(self.state, State::Ready) {
+            debug!("discarding truncated packet");
+            self.state = State::Ready;
+            self.idx = 0;
+        }
+
         let mut buf = ManagedVec::new_with_idx(packet_buffer, self.idx);
         buf.push(byte)?;
         self.idx += 1;
@@ -64,3 +74,37 @@ impl RecvPacketStateMachine {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pump_all<'b>(
+        recv: &mut RecvPacketStateMachine,
+        buf: &'b mut ManagedSlice<'_, u8>,
+        data: &[u8],
+    ) -> Option<&'b mut [u8]> {
+        let (last, rest) = data.split_last().unwrap();
+        for &b in rest {
+            assert!(recv.pump(buf, b).unwrap().is_none());
+        }
+        recv.pump(buf, *last).unwrap()
+    }
+
+    #[test]
+    fn packet() {
+        let mut recv = RecvPacketStateMachine::new();
+        let mut buf = ManagedSlice::Owned(alloc::vec![0; 16]);
+        let pkt = pump_all(&mut recv, &mut buf, b"$g#67").unwrap();
+        assert_eq!(&pkt[..5], b"$g#67");
+    }
+
+    #[test]
+    fn truncated_packet() {
+        let mut recv = RecvPacketStateMachine::new();
+        let mut buf = ManagedSlice::Owned(alloc::vec![0; 16]);
+        // the first packet's '#' and checksum were dropped
+        let pkt = pump_all(&mut recv, &mut buf, b"$m0,4$g#67").unwrap();
+        assert_eq!(&pkt[..5], b"$g#67");
+    }
+}
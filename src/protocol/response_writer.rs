@@ -27,6 +27,12 @@ pub struct ResponseWriter<'a, C: Connection> {
     rle_char: u8,
     rle_repeat: u8,
 
+    // buffer which retains a copy of the outgoing packet, so that it can be
+    // re-transmitted if the client NAKs it. empty if re-transmission is
+    // disabled.
+    resend_buf: &'a mut [u8],
+    resend_idx: usize,
+
     // buffer to log outgoing packets. only allocates if logging is enabled.
     #[cfg(feature = "trace-pkt")]
     msg: Vec<u8>,
@@ -35,6 +41,16 @@ pub struct ResponseWriter<'a, C: Connection> {
 impl<'a, C: Connection + 'a> ResponseWriter<'a, C> {
     /// Creates a new ResponseWriter
     pub fn new(inner: &'a mut C, rle_enabled: bool) -> Self {
+        Self::new_with_resend_buf(inner, rle_enabled, &mut [])
+    }
+
+    /// Creates a new ResponseWriter, which retains a copy of the packet in
+    /// `resend_buf` (see [`last_packet`]).
+    pub fn new_with_resend_buf(
+        inner: &'a mut C,
+        rle_enabled: bool,
+        resend_buf: &'a mut [u8],
+    ) -> Self {
         Self {
             inner,
             started: false,
@@ -44,6 +60,9 @@ impl<'a, C: Connection + 'a> ResponseWriter<'a, C> {
             rle_char: 0,
             rle_repeat: 0,
 
+            resend_buf,
+            resend_idx: 0,
+
             #[cfg(feature = "trace-pkt")]
             msg: Vec::new(),
         }
@@ -70,6 +89,13 @@ impl<'a, C: Connection + 'a> ResponseWriter<'a, C> {
             self.write(0)?;
         }
 
+        // the packet has been recorded in its entirety, and can now be resent
+        if self.resend_idx <= self.resend_buf.len() {
+            if let Some(b) = self.resend_buf.first_mut() {
+                *b = b'$';
+            }
+        }
+
         #[cfg(feature = "trace-pkt")]
         trace!("--> ${}", String::from_utf8_lossy(&self.msg));
 
@@ -105,7 +131,20 @@ impl<'a, C: Connection + 'a> ResponseWriter<'a, C> {
         if !self.started {
             self.started = true;
             self.inner.write(b'$').map_err(Error)?;
+
+            // invalidate the previously sent packet. the leading '$' is only
+            // written once the packet has been flushed
+            if let Some(b) = self.resend_buf.first_mut() {
+                *b = 0;
+            }
+            self.resend_idx = 1;
+        }
+
+        // packets which don't fit in the buffer are never marked as valid
+        if let Some(b) = self.resend_buf.get_mut(self.resend_idx) {
+            *b = byte;
         }
+        self.resend_idx = self.resend_idx.saturating_add(1);
 
         self.checksum = self.checksum.wrapping_add(byte);
         self.inner.write(byte).map_err(Error)
@@ -288,6 +327,31 @@ impl<'a, C: Connection + 'a> ResponseWriter<'a, C> {
     }
 }
 
+/// Returns the packet retained in a `ResponseWriter`'s `resend_buf`, if there
+/// is one.
+///
+/// To avoid tracking the packet's length separately, a retained packet is
+/// stored as-sent (i.e: `$<body>#<checksum>`), and is considered valid if (and
+/// only if) it starts with '$'. The '#' separating the body from the checksum
+/// can never appear in the body itself (see `rle_avoids_hash`), and is always
+/// followed by exactly two checksum digits.
+pub fn last_packet(resend_buf: &[u8]) -> Option<&[u8]> {
+    if resend_buf.first() != Some(&b'$') {
+        return None;
+    }
+
+    let end = resend_buf.iter().position(|b| *b == b'#')? + 3;
+    resend_buf.get(..end)
+}
+
+/// Discard the packet retained in a `ResponseWriter`'s `resend_buf` (e.g: once
+/// the client has acknowledged it).
+pub fn clear_last_packet(resend_buf: &mut [u8]) {
+    if let Some(b) = resend_buf.first_mut() {
+        *b = 0;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -353,4 +417,50 @@ mod tests {
         writer.flush().unwrap();
         assert_no_special_chars_in_body(&conn.data);
     }
+
+    #[test]
+    fn resend_buf() {
+        let mut conn = MockConnection::new();
+        let mut resend_buf = [0; 16];
+
+        let mut writer = ResponseWriter::new_with_resend_buf(&mut conn, true, &mut resend_buf);
+        writer.write_str("00000000").unwrap();
+        writer.flush().unwrap();
+        assert_eq!(last_packet(&resend_buf), Some(conn.data.as_slice()));
+
+        // a shorter packet must not pick up the previous packet's checksum
+        conn.data.clear();
+        let mut writer = ResponseWriter::new_with_resend_buf(&mut conn, true, &mut resend_buf);
+        writer.write_str("OK").unwrap();
+        writer.flush().unwrap();
+        assert_eq!(last_packet(&resend_buf), Some(&b"$OK#9a"[..]));
+        assert_eq!(conn.data, b"$OK#9a");
+    }
+
+    #[test]
+    fn resend_buf_overflow() {
+        let mut conn = MockConnection::new();
+        let mut resend_buf = [0; 8];
+
+        let mut writer = ResponseWriter::new_with_resend_buf(&mut conn, false, &mut resend_buf);
+        writer.write_str("0123456789").unwrap();
+        writer.flush().unwrap();
+        assert_eq!(last_packet(&resend_buf), None);
+    }
+
+    #[test]
+    fn resend_buf_unflushed() {
+        let mut conn = MockConnection::new();
+        let mut resend_buf = [0; 16];
+
+        let mut writer = ResponseWriter::new_with_resend_buf(&mut conn, false, &mut resend_buf);
+        writer.write_str("OK").unwrap();
+        writer.flush().unwrap();
+
+        {
+            let mut writer = ResponseWriter::new_with_resend_buf(&mut conn, false, &mut resend_buf);
+            writer.write_str("E01").unwrap();
+        }
+        assert_eq!(last_packet(&resend_buf), None);
+    }
 }
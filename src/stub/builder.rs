@@ -5,6 +5,7 @@ use crate::target::Target;
 use core::fmt::Display;
 use core::fmt::{self};
 use core::marker::PhantomData;
+use core::time::Duration;
 use managed::ManagedSlice;
 
 /// An error which may occur when building a [`GdbStub`].
@@ -40,6 +41,10 @@ pub struct GdbStubBuilder<'a, T: Target, C: Connection> {
     conn: C,
     packet_buffer: Option<&'a mut [u8]>,
     packet_buffer_size: Option<usize>,
+    resend_buffer: Option<&'a mut [u8]>,
+    resend_buffer_size: Option<usize>,
+    max_retries: u8,
+    ack_timeout: Option<Duration>,
 
     _target: PhantomData<T>,
 }
@@ -51,6 +56,10 @@ impl<'a, T: Target, C: Connection> GdbStubBuilder<'a, T, C> {
             conn,
             packet_buffer: None,
             packet_buffer_size: None,
+            resend_buffer: None,
+            resend_buffer_size: None,
+            max_retries: 3,
+            ack_timeout: None,
 
             _target: PhantomData,
         }
@@ -73,6 +82,70 @@ impl<'a, T: Target, C: Connection> GdbStubBuilder<'a, T, C> {
         self
     }
 
+    /// Use a pre-allocated buffer to retain a copy of the last packet sent to
+    /// the GDB client (instead of heap-allocating).
+    ///
+    /// If the client NAKs a packet (e.g: because it was corrupted in transit),
+    /// the retained copy is re-transmitted. Packets which don't fit in the
+    /// buffer cannot be re-transmitted.
+    ///
+    /// Re-transmission is disabled by default (as it requires retaining a
+    /// second copy of each packet), in which case a NAK from the client will
+    /// end the debugging session.
+    pub fn with_resend_buffer(mut self, resend_buffer: &'a mut [u8]) -> Self {
+        self.resend_buffer = Some(resend_buffer);
+        self
+    }
+
+    /// Enable re-transmission of NAK'd packets, heap-allocating a resend
+    /// buffer of the given `size` (see [`with_resend_buffer`]).
+    ///
+    /// Packets longer than `size` cannot be re-transmitted, so consider using
+    /// the same size as the packet buffer.
+    ///
+    /// [`with_resend_buffer`]: Self::with_resend_buffer
+    #[cfg(feature = "alloc")]
+    pub fn resend_buffer_size(mut self, size: usize) -> Self {
+        self.resend_buffer_size = Some(size);
+        self
+    }
+
+    /// Specify the maximum number of consecutive re-transmissions before
+    /// giving up on the connection. Defaults to 3.
+    ///
+    /// Both outgoing packets NAK'd by the client and incoming packets NAK'd by
+    /// the stub (due to a checksum mismatch) count towards this limit.
+    pub fn max_retries(mut self, max_retries: u8) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Re-transmit a packet if the GDB client has not acknowledged it after
+    /// `timeout`. Disabled by default.
+    ///
+    /// Requires a resend buffer (see [`with_resend_buffer`]).
+    ///
+    /// This helps recover from lossy connections which drop bytes outright
+    /// (rather than corrupting them). The timeout is enforced by
+    /// [`ConnectionExt::read_timeout`] when using
+    /// [`GdbStub::run_blocking`], [`AsyncConnection::read_timeout`] when using
+    /// [`GdbStub::run_async`], and via
+    /// [`pending_ack_timeout`](super::state_machine::GdbStubStateMachineInner::pending_ack_timeout)
+    /// when using the state machine API directly.
+    ///
+    /// Note that once "no ack mode" has been negotiated (see
+    /// [`Target::use_no_ack_mode`]), packets are no longer acknowledged, and
+    /// this timeout has no effect.
+    ///
+    /// [`with_resend_buffer`]: Self::with_resend_buffer
+    /// [`ConnectionExt::read_timeout`]: crate::conn::ConnectionExt::read_timeout
+    /// [`AsyncConnection::read_timeout`]: crate::conn::AsyncConnection::read_timeout
+    /// [`Target::use_no_ack_mode`]: crate::target::Target::use_no_ack_mode
+    pub fn ack_timeout(mut self, timeout: Duration) -> Self {
+        self.ack_timeout = Some(timeout);
+        self
+    }
+
     /// Build the GdbStub, returning an error if something went wrong.
     pub fn build(self) -> Result<GdbStub<'a, T, C>, GdbStubBuilderError> {
         let packet_buffer = match self.packet_buffer {
@@ -104,10 +177,21 @@ impl<'a, T: Target, C: Connection> GdbStubBuilder<'a, T, C> {
             }
         };
 
+        let resend_buffer = match (self.resend_buffer, self.resend_buffer_size) {
+            (Some(buf), _) => ManagedSlice::Borrowed(buf),
+            #[cfg(feature = "alloc")]
+            (None, Some(len)) => {
+                use alloc::vec;
+                ManagedSlice::Owned(vec![0; len])
+            }
+            _ => ManagedSlice::Borrowed(&mut []),
+        };
+
         Ok(GdbStub {
             conn: self.conn,
             packet_buffer,
-            inner: GdbStubImpl::new(),
+            resend_buffer,
+            inner: GdbStubImpl::new(self.max_retries, self.ack_timeout),
         })
     }
 }
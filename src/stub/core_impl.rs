@@ -1,14 +1,18 @@
 use crate::common::Signal;
 use crate::common::Tid;
 use crate::conn::Connection;
+use crate::protocol::clear_last_packet;
 use crate::protocol::commands::Command;
+use crate::protocol::last_packet;
 use crate::protocol::Packet;
+use crate::protocol::PacketParseError;
 use crate::protocol::ResponseWriter;
 use crate::protocol::SpecificIdKind;
 use crate::stub::error::InternalError;
 use crate::target::Target;
 use crate::SINGLE_THREAD_TID;
 use core::marker::PhantomData;
+use core::time::Duration;
 
 /// Common imports used by >50% of all extensions.
 ///
@@ -36,6 +40,7 @@ mod host_process_info;
 mod libraries;
 mod lldb_register_info;
 mod memory_map;
+mod memory_tagging;
 mod monitor_cmd;
 mod no_ack_mode;
 mod resume;
@@ -109,6 +114,11 @@ pub(crate) struct GdbStubImpl<T: Target, C: Connection> {
     current_mem_tid: Tid,
     current_resume_tid: SpecificIdKind,
     features: ProtocolFeatures,
+
+    // number of consecutive re-transmissions (in either direction)
+    retries: u8,
+    max_retries: u8,
+    ack_timeout: Option<Duration>,
 }
 
 pub enum HandlerStatus {
@@ -119,7 +129,7 @@ pub enum HandlerStatus {
 }
 
 impl<T: Target, C: Connection> GdbStubImpl<T, C> {
-    pub fn new() -> GdbStubImpl<T, C> {
+    pub fn new(max_retries: u8, ack_timeout: Option<Duration>) -> GdbStubImpl<T, C> {
         GdbStubImpl {
             _target: PhantomData,
             _connection: PhantomData,
@@ -135,29 +145,120 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
             current_mem_tid: SINGLE_THREAD_TID,
             current_resume_tid: SpecificIdKind::WithId(SINGLE_THREAD_TID),
             features: ProtocolFeatures::empty(),
+
+            retries: 0,
+            max_retries,
+            ack_timeout,
         }
     }
 
+    /// Create a `ResponseWriter` which retains a copy of the response in
+    /// `resend_buf` (unless it will never need to be re-transmitted).
+    pub fn response_writer<'w>(
+        &self,
+        conn: &'w mut C,
+        resend_buf: &'w mut [u8],
+        target: &mut T,
+    ) -> ResponseWriter<'w, C> {
+        let resend_buf = if self.features.no_ack_mode() {
+            &mut []
+        } else {
+            resend_buf
+        };
+        ResponseWriter::new_with_resend_buf(conn, target.use_rle(), resend_buf)
+    }
+
+    /// If the stub is waiting for the client to acknowledge the last packet it
+    /// sent, returns how long to wait before re-transmitting it.
+    pub fn pending_ack_timeout(&self, resend_buf: &[u8]) -> Option<Duration> {
+        if self.features.no_ack_mode() || last_packet(resend_buf).is_none() {
+            return None;
+        }
+        self.ack_timeout
+    }
+
+    /// Re-transmit the last packet sent to the client.
+    pub fn resend_last_packet(
+        &mut self,
+        conn: &mut C,
+        resend_buf: &[u8],
+    ) -> Result<(), InternalError<T::Error, C::Error>> {
+        self.retry()?;
+
+        match last_packet(resend_buf) {
+            Some(packet) => {
+                debug!("--> re-transmitting last packet");
+                conn.write_all(packet).map_err(InternalError::conn_write)?;
+                conn.flush().map_err(InternalError::conn_write)?;
+            }
+            // re-transmission is disabled
+            None if resend_buf.is_empty() => return Err(InternalError::ClientSentNack),
+            None => warn!("no packet to re-transmit (it may have been too large to retain)"),
+        }
+
+        Ok(())
+    }
+
+    fn retry(&mut self) -> Result<(), InternalError<T::Error, C::Error>> {
+        self.retries = self.retries.saturating_add(1);
+        if self.retries > self.max_retries {
+            return Err(InternalError::RetryLimitExceeded);
+        }
+        Ok(())
+    }
+
     pub fn handle_packet(
         &mut self,
         target: &mut T,
         conn: &mut C,
-        packet: Packet<'_>,
+        resend_buf: &mut [u8],
+        packet_buf: &mut [u8],
     ) -> Result<State, InternalError<T::Error, C::Error>> {
+        let packet = match Packet::from_buf(target, packet_buf) {
+            Ok(packet) => packet,
+            // ask the client to re-transmit corrupted packets
+            Err(PacketParseError::ChecksumMismatched { .. }) if !self.features.no_ack_mode() => {
+                debug!("<-- packet with bad checksum");
+                self.retry()?;
+                conn.write(b'-').map_err(InternalError::conn_write)?;
+                conn.flush().map_err(InternalError::conn_write)?;
+                return Ok(State::Pump);
+            }
+            // stray bytes (e.g: the tail end of a packet whose header was lost in
+            // transit) can safely be ignored
+            Err(PacketParseError::UnexpectedHeader(b)) => {
+                debug!("<-- ignoring unexpected byte {:#04x}", b);
+                return Ok(State::Pump);
+            }
+            Err(e) => return Err(InternalError::PacketParse(e)),
+        };
+
         match packet {
-            Packet::Ack => Ok(State::Pump),
-            Packet::Nack => Err(InternalError::ClientSentNack),
+            Packet::Ack => {
+                self.retries = 0;
+                clear_last_packet(resend_buf);
+                Ok(State::Pump)
+            }
+            Packet::Nack => {
+                self.resend_last_packet(conn, resend_buf)?;
+                Ok(State::Pump)
+            }
             Packet::Interrupt => {
                 debug!("<-- interrupt packet");
                 Ok(State::CtrlCInterrupt)
             }
             Packet::Command(command) => {
+                // the client wouldn't have sent a new command if it hadn't
+                // received the last response
+                self.retries = 0;
+                clear_last_packet(resend_buf);
+
                 // Acknowledge the command
                 if !self.features.no_ack_mode() {
                     conn.write(b'+').map_err(InternalError::conn_write)?;
                 }
 
-                let mut res = ResponseWriter::new(conn, target.use_rle());
+                let mut res = self.response_writer(conn, resend_buf, target);
                 let disconnect_reason = match self.handle_command(&mut res, target, command) {
                     Ok(HandlerStatus::Handled) => None,
                     Ok(HandlerStatus::NeedsOk) => {
@@ -218,6 +319,7 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
             Command::ReverseCont(cmd) => self.handle_reverse_cont(res, target, cmd),
             Command::ReverseStep(cmd) => self.handle_reverse_step(res, target, cmd),
             Command::MemoryMap(cmd) => self.handle_memory_map(res, target, cmd),
+            Command::MemoryTagging(cmd) => self.handle_memory_tagging(res, target, cmd),
             Command::FlashOperations(cmd) => self.handle_flash_operations(res, target, cmd),
             Command::HostIo(cmd) => self.handle_host_io(res, target, cmd),
             Command::ExecFile(cmd) => self.handle_exec_file(res, target, cmd),
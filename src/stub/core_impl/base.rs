@@ -74,10 +74,11 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                     .then_some(SpecificIdKind::WithId(self.get_current_pid(target)?)),
                 tid: SpecificIdKind::WithId(tid),
             })?;
+            res.write_str(";")?;
+            Self::write_tdesc(res, target, Some(tid))?;
         } else {
-            res.write_str("W00")?;
+            res.write_str("W00;")?;
         }
-        res.write_str(";")?;
         Ok(HandlerStatus::Handled)
     }
 
@@ -226,6 +227,10 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                     res.write_str(";qXfer:memory-map:read+")?;
                 }
 
+                if target.support_memory_tagging().is_some() {
+                    res.write_str(";memory-tagging+")?;
+                }
+
                 if target.support_exec_file().is_some() {
                     res.write_str(";qXfer:exec-file:read+")?;
                 }
@@ -0,0 +1,193 @@
+use super::prelude::*;
+use crate::arch::Arch;
+use crate::protocol::commands::ext::MemoryTagging;
+
+impl<T: Target, C: Connection> GdbStubImpl<T, C> {
+    pub(crate) fn handle_memory_tagging(
+        &mut self,
+        res: &mut ResponseWriter<'_, C>,
+        target: &mut T,
+        command: MemoryTagging<'_>,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        let ops = match target.support_memory_tagging() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
+
+        crate::__dead_code_marker!("memory_tagging", "impl");
+
+        let handler_status = match command {
+            MemoryTagging::qMemTags(cmd) => {
+                let addr = <T::Arch as Arch>::Usize::from_be_bytes(cmd.addr)
+                    .ok_or(Error::TargetMismatch)?;
+
+                let ret = ops
+                    .read_memory_tags(addr, cmd.len, cmd.tag_type, cmd.buf)
+                    .handle_error()?;
+
+                res.write_str("m")?;
+                res.write_hex_buf(cmd.buf.get(..ret).ok_or(Error::PacketBufferOverflow)?)?;
+                HandlerStatus::Handled
+            }
+            MemoryTagging::QMemTags(cmd) => {
+                let addr = <T::Arch as Arch>::Usize::from_be_bytes(cmd.addr)
+                    .ok_or(Error::TargetMismatch)?;
+
+                ops.write_memory_tags(addr, cmd.len, cmd.tag_type, cmd.tags)
+                    .handle_error()?;
+                HandlerStatus::NeedsOk
+            }
+            MemoryTagging::qIsAddressTagged(cmd) => {
+                let addr = <T::Arch as Arch>::Usize::from_be_bytes(cmd.addr)
+                    .ok_or(Error::TargetMismatch)?;
+
+                let region_tagged = ops
+                    .support_memory_map()
+                    .and_then(|map| map.is_region_tagged(addr));
+                let tagged = match region_tagged {
+                    Some(tagged) => tagged,
+                    None => ops.is_address_tagged(addr).handle_error()?,
+                };
+                res.write_str(if tagged { "01" } else { "00" })?;
+                HandlerStatus::Handled
+            }
+        };
+
+        Ok(handler_status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stub::mock::exchange;
+    use crate::stub::mock::MockArch;
+    use crate::stub::mock::MockRegs;
+    use crate::target::ext::base::singlethread::SingleThreadBase;
+    use crate::target::ext::base::BaseOps;
+    use crate::target::ext::memory_map::MemoryMap;
+    use crate::target::ext::memory_map::MemoryMapOps;
+    use crate::target::ext::memory_tagging::MemoryTagging;
+    use crate::target::ext::memory_tagging::MemoryTaggingOps;
+    use crate::target::Target;
+    use crate::target::TargetResult;
+
+    /// Tags the memory range `0x1000..0x2000`, either via the memory map, or
+    /// via `MemoryTagging::is_address_tagged`.
+    struct TaggedTarget {
+        memory_map: bool,
+    }
+
+    fn tagged(addr: u32) -> bool {
+        (0x1000..0x2000).contains(&addr)
+    }
+
+    impl Target for TaggedTarget {
+        type Arch = MockArch;
+        type Error = ();
+
+        fn base_ops(&mut self) -> BaseOps<'_, MockArch, ()> {
+            BaseOps::SingleThread(self)
+        }
+
+        fn guard_rail_implicit_sw_breakpoints(&self) -> bool {
+            true
+        }
+
+        fn support_memory_map(&mut self) -> Option<MemoryMapOps<'_, Self>> {
+            if self.memory_map {
+                Some(self)
+            } else {
+                None
+            }
+        }
+
+        fn support_memory_tagging(&mut self) -> Option<MemoryTaggingOps<'_, Self>> {
+            Some(self)
+        }
+    }
+
+    impl SingleThreadBase for TaggedTarget {
+        fn read_registers(&mut self, _regs: &mut MockRegs) -> TargetResult<(), Self> {
+            Ok(())
+        }
+
+        fn write_registers(&mut self, _regs: &MockRegs) -> TargetResult<(), Self> {
+            Ok(())
+        }
+
+        fn read_addrs(&mut self, _start_addr: u32, _data: &mut [u8]) -> TargetResult<usize, Self> {
+            Ok(0)
+        }
+
+        fn write_addrs(&mut self, _start_addr: u32, _data: &[u8]) -> TargetResult<(), Self> {
+            Ok(())
+        }
+    }
+
+    impl MemoryMap for TaggedTarget {
+        fn memory_map_xml(
+            &self,
+            _offset: u64,
+            _length: usize,
+            _buf: &mut [u8],
+        ) -> TargetResult<usize, Self> {
+            Ok(0)
+        }
+
+        fn is_region_tagged(&self, addr: u32) -> Option<bool> {
+            // only covers the lower half of the address space
+            if addr < 0x8000_0000 {
+                Some(tagged(addr))
+            } else {
+                None
+            }
+        }
+    }
+
+    impl MemoryTagging for TaggedTarget {
+        fn read_memory_tags(
+            &mut self,
+            _start_addr: u32,
+            _len: usize,
+            _tag_type: i32,
+            _data: &mut [u8],
+        ) -> TargetResult<usize, Self> {
+            Ok(0)
+        }
+
+        fn write_memory_tags(
+            &mut self,
+            _start_addr: u32,
+            _len: usize,
+            _tag_type: i32,
+            _tags: &[u8],
+        ) -> TargetResult<(), Self> {
+            Ok(())
+        }
+
+        fn is_address_tagged(&mut self, addr: u32) -> TargetResult<bool, Self> {
+            // the memory map takes precedence for the addresses it covers
+            assert!(!self.memory_map || addr >= 0x8000_0000);
+            Ok(tagged(addr) || addr == 0x8000_0000)
+        }
+    }
+
+    #[test]
+    fn is_address_tagged() {
+        for memory_map in [false, true] {
+            let mut target = TaggedTarget { memory_map };
+            assert_eq!(
+                exchange(&mut target, b"$qIsAddressTagged:1800#42"),
+                b"+$01#61"
+            );
+            assert_eq!(
+                exchange(&mut target, b"$qIsAddressTagged:2000#3b"),
+                b"+$00#60"
+            );
+            assert_eq!(
+                exchange(&mut target, b"$qIsAddressTagged:80000000#01"),
+                b"+$01#61"
+            );
+        }
+    }
+}
@@ -9,6 +9,7 @@ use crate::protocol::SpecificIdKind;
 use crate::protocol::SpecificThreadId;
 use crate::stub::MultiThreadStopReason;
 use crate::target::ext::base::reverse_exec::ReplayLogPosition;
+use crate::target::ext::base::BaseOps;
 use crate::target::ext::base::ResumeOps;
 use crate::target::ext::catch_syscalls::CatchSyscallPosition;
 
@@ -309,6 +310,35 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
             res.write_str(";")?;
         }
 
+        Self::write_tdesc(res, target, tid)
+    }
+
+    /// Write out the `tdesc:<annex>;` stop reply field, if the target reports
+    /// a non-default target description for the stopped thread.
+    pub(crate) fn write_tdesc(
+        res: &mut ResponseWriter<'_, C>,
+        target: &mut T,
+        tid: Option<Tid>,
+    ) -> Result<(), Error<T::Error, C::Error>> {
+        let annex = match target.base_ops() {
+            BaseOps::SingleThread(ops) => match ops.support_thread_target_description() {
+                Some(ops) => ops.thread_target_description(()),
+                None => return Ok(()),
+            },
+            BaseOps::MultiThread(ops) => match (ops.support_thread_target_description(), tid) {
+                (Some(ops), Some(tid)) => ops.thread_target_description(tid),
+                _ => return Ok(()),
+            },
+        };
+
+        crate::__dead_code_marker!("thread_target_description", "stop_reason");
+
+        if let Some(annex) = annex.map_err(Error::TargetError)? {
+            res.write_str("tdesc:")?;
+            res.write_str(annex)?;
+            res.write_str(";")?;
+        }
+
         Ok(())
     }
 
@@ -513,3 +543,303 @@ pub(crate) enum FinishExecStatus {
     Handled,
     Disconnect(DisconnectReason),
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::common::Signal;
+    use crate::common::Tid;
+    use crate::stub::mock::exchange;
+    use crate::stub::mock::packet;
+    use crate::stub::mock::MockArch;
+    use crate::stub::mock::MockConnection;
+    use crate::stub::mock::MockRegs;
+    use crate::stub::state_machine::GdbStubStateMachine;
+    use crate::stub::GdbStub;
+    use crate::stub::IntoStopReason;
+    use crate::stub::MultiThreadStopReason;
+    use crate::stub::SingleThreadStopReason;
+    use crate::target::ext::base::multithread::MultiThreadBase;
+    use crate::target::ext::base::multithread::MultiThreadResume;
+    use crate::target::ext::base::multithread::MultiThreadResumeOps;
+    use crate::target::ext::base::singlethread::SingleThreadBase;
+    use crate::target::ext::base::singlethread::SingleThreadResume;
+    use crate::target::ext::base::singlethread::SingleThreadResumeOps;
+    use crate::target::ext::base::BaseOps;
+    use crate::target::ext::target_description_xml_override::TargetDescriptionXmlOverride;
+    use crate::target::ext::target_description_xml_override::TargetDescriptionXmlOverrideOps;
+    use crate::target::ext::thread_target_description::ThreadTargetDescription;
+    use crate::target::ext::thread_target_description::ThreadTargetDescriptionOps;
+    use crate::target::Target;
+    use crate::target::TargetResult;
+    use alloc::string::String;
+
+    const AARCH32_XML: &str = "<target><architecture>arm</architecture></target>";
+
+    fn tid(tid: usize) -> Tid {
+        Tid::new(tid).unwrap()
+    }
+
+    /// Continues the target, reporting `reason` once it stops.
+    fn stop_reply<T: Target<Error = ()>>(target: &mut T, reason: impl IntoStopReason<T>) -> String {
+        let mut gdb = GdbStub::new(MockConnection::default())
+            .run_state_machine(target)
+            .unwrap();
+        for &b in packet(b"vCont;c").iter() {
+            gdb = match gdb {
+                GdbStubStateMachine::Idle(gdb) => gdb.incoming_data(target, b).unwrap(),
+                gdb => gdb,
+            };
+        }
+        let gdb = match gdb {
+            GdbStubStateMachine::Running(gdb) => Some(gdb),
+            _ => None,
+        };
+        let gdb = gdb.expect("target should be running");
+        match gdb.report_stop(target, reason).unwrap() {
+            GdbStubStateMachine::Idle(mut gdb) => {
+                String::from_utf8(core::mem::take(&mut gdb.borrow_conn().data)).unwrap()
+            }
+            _ => String::new(),
+        }
+    }
+
+    /// Thread 1 runs AArch32 code, while thread 2 uses the default target
+    /// description.
+    struct MultiArchTarget;
+
+    impl Target for MultiArchTarget {
+        type Arch = MockArch;
+        type Error = ();
+
+        fn base_ops(&mut self) -> BaseOps<'_, MockArch, ()> {
+            BaseOps::MultiThread(self)
+        }
+
+        fn guard_rail_implicit_sw_breakpoints(&self) -> bool {
+            true
+        }
+
+        fn support_target_description_xml_override(
+            &mut self,
+        ) -> Option<TargetDescriptionXmlOverrideOps<'_, Self>> {
+            Some(self)
+        }
+    }
+
+    impl MultiThreadBase for MultiArchTarget {
+        fn read_registers(&mut self, _regs: &mut MockRegs, _tid: Tid) -> TargetResult<(), Self> {
+            Ok(())
+        }
+
+        fn write_registers(&mut self, _regs: &MockRegs, _tid: Tid) -> TargetResult<(), Self> {
+            Ok(())
+        }
+
+        fn read_addrs(
+            &mut self,
+            _start_addr: u32,
+            _data: &mut [u8],
+            _tid: Tid,
+        ) -> TargetResult<usize, Self> {
+            Ok(0)
+        }
+
+        fn write_addrs(
+            &mut self,
+            _start_addr: u32,
+            _data: &[u8],
+            _tid: Tid,
+        ) -> TargetResult<(), Self> {
+            Ok(())
+        }
+
+        fn list_active_threads(
+            &mut self,
+            thread_is_active: &mut dyn FnMut(Tid),
+        ) -> Result<(), Self::Error> {
+            thread_is_active(tid(1));
+            thread_is_active(tid(2));
+            Ok(())
+        }
+
+        fn support_resume(&mut self) -> Option<MultiThreadResumeOps<'_, Self>> {
+            Some(self)
+        }
+
+        fn support_thread_target_description(
+            &mut self,
+        ) -> Option<ThreadTargetDescriptionOps<'_, Tid, Self>> {
+            Some(self)
+        }
+    }
+
+    impl MultiThreadResume for MultiArchTarget {
+        fn resume(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn clear_resume_actions(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn set_resume_action_continue(
+            &mut self,
+            _tid: Tid,
+            _signal: Option<Signal>,
+        ) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    impl ThreadTargetDescription<Tid> for MultiArchTarget {
+        fn thread_target_description(&mut self, tid: Tid) -> Result<Option<&str>, ()> {
+            Ok((tid.get() == 1).then_some("aarch32.xml"))
+        }
+    }
+
+    impl TargetDescriptionXmlOverride for MultiArchTarget {
+        fn target_description_xml(
+            &self,
+            annex: &[u8],
+            offset: u64,
+            length: usize,
+            buf: &mut [u8],
+        ) -> TargetResult<usize, Self> {
+            let xml = match annex {
+                b"aarch32.xml" => AARCH32_XML.as_bytes(),
+                _ => return Err(crate::target::TargetError::NonFatal),
+            };
+            let data = xml.get(offset as usize..).unwrap_or(&[]);
+            let n = data.len().min(length).min(buf.len());
+            buf[..n].copy_from_slice(&data[..n]);
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn tdesc_stop_reply() {
+        let reply = stop_reply(
+            &mut MultiArchTarget,
+            MultiThreadStopReason::SignalWithThread {
+                tid: tid(1),
+                signal: Signal::SIGTRAP,
+            },
+        );
+        assert!(
+            reply.contains("T05thread:01;tdesc:aarch32.xml;#"),
+            "{}",
+            reply
+        );
+
+        let reply = stop_reply(
+            &mut MultiArchTarget,
+            MultiThreadStopReason::SignalWithThread {
+                tid: tid(2),
+                signal: Signal::SIGTRAP,
+            },
+        );
+        assert!(reply.contains("T05thread:02;#"), "{}", reply);
+
+        let reply = exchange(&mut MultiArchTarget, &packet(b"?"));
+        let reply = String::from_utf8(reply).unwrap();
+        assert!(
+            reply.contains("T05thread:01;tdesc:aarch32.xml;#"),
+            "{}",
+            reply
+        );
+    }
+
+    #[test]
+    fn tdesc_qxfer_annex() {
+        let reply = exchange(
+            &mut MultiArchTarget,
+            &packet(b"qXfer:features:read:aarch32.xml:0,fff"),
+        );
+        let reply = String::from_utf8(reply).unwrap();
+        assert!(
+            reply.contains(&alloc::format!("$m{}#", AARCH32_XML)),
+            "{}",
+            reply
+        );
+    }
+
+    /// A single threaded target which has switched to AArch32 mode.
+    struct SingleThreadTarget;
+
+    impl Target for SingleThreadTarget {
+        type Arch = MockArch;
+        type Error = ();
+
+        fn base_ops(&mut self) -> BaseOps<'_, MockArch, ()> {
+            BaseOps::SingleThread(self)
+        }
+
+        fn guard_rail_implicit_sw_breakpoints(&self) -> bool {
+            true
+        }
+    }
+
+    impl SingleThreadBase for SingleThreadTarget {
+        fn read_registers(&mut self, _regs: &mut MockRegs) -> TargetResult<(), Self> {
+            Ok(())
+        }
+
+        fn write_registers(&mut self, _regs: &MockRegs) -> TargetResult<(), Self> {
+            Ok(())
+        }
+
+        fn read_addrs(&mut self, _start_addr: u32, _data: &mut [u8]) -> TargetResult<usize, Self> {
+            Ok(0)
+        }
+
+        fn write_addrs(&mut self, _start_addr: u32, _data: &[u8]) -> TargetResult<(), Self> {
+            Ok(())
+        }
+
+        fn support_resume(&mut self) -> Option<SingleThreadResumeOps<'_, Self>> {
+            Some(self)
+        }
+
+        fn support_thread_target_description(
+            &mut self,
+        ) -> Option<ThreadTargetDescriptionOps<'_, (), Self>> {
+            Some(self)
+        }
+    }
+
+    impl SingleThreadResume for SingleThreadTarget {
+        fn resume(&mut self, _signal: Option<Signal>) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    impl ThreadTargetDescription<()> for SingleThreadTarget {
+        fn thread_target_description(&mut self, _tid: ()) -> Result<Option<&str>, ()> {
+            Ok(Some("aarch32.xml"))
+        }
+    }
+
+    #[test]
+    fn tdesc_single_thread() {
+        let reply = stop_reply(
+            &mut SingleThreadTarget,
+            SingleThreadStopReason::SignalWithThread {
+                tid: (),
+                signal: Signal::SIGTRAP,
+            },
+        );
+        assert!(
+            reply.contains("T05thread:01;tdesc:aarch32.xml;#"),
+            "{}",
+            reply
+        );
+
+        let reply = exchange(&mut SingleThreadTarget, &packet(b"?"));
+        let reply = String::from_utf8(reply).unwrap();
+        assert!(
+            reply.contains("T05thread:01;tdesc:aarch32.xml;#"),
+            "{}",
+            reply
+        );
+    }
+}
@@ -29,6 +29,7 @@ pub(crate) enum InternalError<T, C> {
     TargetError(T),
 
     ClientSentNack,
+    RetryLimitExceeded,
     PacketBufferOverflow,
     PacketParse(PacketParseError),
     PacketUnexpected,
@@ -136,7 +137,8 @@ where
             Connection(e, ConnectionErrorKind::Init) => write!(f, "Connection Error while initializing the session: {}", e),
             Connection(e, ConnectionErrorKind::Read) => write!(f, "Connection Error while reading request: {}", e),
             Connection(e, ConnectionErrorKind::Write) => write!(f, "Connection Error while writing response: {}", e),
-            ClientSentNack => write!(f, "Client nack'd the last packet, but `gdbstub` doesn't implement re-transmission."),
+            ClientSentNack => write!(f, "Client nack'd the last packet, but re-transmission is disabled. See `GdbStubBuilder::with_resend_buffer` for more information"),
+            RetryLimitExceeded => write!(f, "Exceeded the maximum number of consecutive re-transmissions. See `GdbStubBuilder::max_retries` for more information"),
             PacketBufferOverflow => write!(f, "Received an oversized packet (did not fit in provided packet buffer)"),
             PacketParse(e) => write!(f, "Failed to parse packet into a valid command: {:?}", e),
             PacketUnexpected => write!(f, "Client sent an unexpected packet. This should never happen! Please re-run with `log` trace-level logging enabled and file an issue at https://github.com/daniel5151/gdbstub/issues"),
@@ -0,0 +1,126 @@
+//! Mock `Target` / `Connection` implementations used to test the stub.
+
+use crate::arch::Arch;
+use crate::arch::Registers;
+use crate::conn::Connection;
+use crate::stub::state_machine::GdbStubStateMachine;
+use crate::stub::GdbStub;
+use crate::target::ext::base::singlethread::SingleThreadBase;
+use crate::target::ext::base::BaseOps;
+use crate::target::Target;
+use crate::target::TargetResult;
+use alloc::vec::Vec;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MockRegs {
+    pc: u32,
+}
+
+impl Registers for MockRegs {
+    type ProgramCounter = u32;
+
+    fn pc(&self) -> u32 {
+        self.pc
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        for b in self.pc.to_le_bytes().iter() {
+            write_byte(Some(*b))
+        }
+    }
+
+    fn gdb_deserialize(&mut self, _bytes: &[u8]) -> Result<(), ()> {
+        Err(())
+    }
+}
+
+pub enum MockArch {}
+
+impl Arch for MockArch {
+    type Usize = u32;
+    type Registers = MockRegs;
+    type BreakpointKind = ();
+    type RegId = ();
+}
+
+pub struct MockTarget;
+
+impl Target for MockTarget {
+    type Arch = MockArch;
+    type Error = ();
+
+    fn base_ops(&mut self) -> BaseOps<'_, MockArch, ()> {
+        BaseOps::SingleThread(self)
+    }
+
+    fn guard_rail_implicit_sw_breakpoints(&self) -> bool {
+        true
+    }
+}
+
+impl SingleThreadBase for MockTarget {
+    fn read_registers(&mut self, _regs: &mut MockRegs) -> TargetResult<(), Self> {
+        Ok(())
+    }
+
+    fn write_registers(&mut self, _regs: &MockRegs) -> TargetResult<(), Self> {
+        Ok(())
+    }
+
+    fn read_addrs(&mut self, _start_addr: u32, _data: &mut [u8]) -> TargetResult<usize, Self> {
+        Ok(0)
+    }
+
+    fn write_addrs(&mut self, _start_addr: u32, _data: &[u8]) -> TargetResult<(), Self> {
+        Ok(())
+    }
+}
+
+/// A mock connection that captures all written bytes
+#[derive(Default)]
+pub struct MockConnection {
+    pub data: Vec<u8>,
+}
+
+impl Connection for MockConnection {
+    type Error = ();
+
+    fn write(&mut self, byte: u8) -> Result<(), ()> {
+        self.data.push(byte);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), ()> {
+        Ok(())
+    }
+}
+
+/// Feed `incoming` to a fresh GDB stub debugging `target`, returning all the
+/// data the stub sent back.
+pub fn exchange<T: Target<Error = ()>>(target: &mut T, incoming: &[u8]) -> Vec<u8> {
+    let mut gdb = GdbStub::new(MockConnection::default())
+        .run_state_machine(target)
+        .unwrap();
+    for &b in incoming {
+        gdb = match gdb {
+            GdbStubStateMachine::Idle(gdb) => gdb.incoming_data(target, b).unwrap(),
+            gdb => gdb,
+        };
+    }
+    match &mut gdb {
+        GdbStubStateMachine::Idle(gdb) => core::mem::take(&mut gdb.borrow_conn().data),
+        GdbStubStateMachine::Running(gdb) => core::mem::take(&mut gdb.borrow_conn().data),
+        GdbStubStateMachine::CtrlCInterrupt(gdb) => core::mem::take(&mut gdb.borrow_conn().data),
+        GdbStubStateMachine::Disconnected(gdb) => core::mem::take(&mut gdb.borrow_conn().data),
+    }
+}
+
+/// Frame `payload` as a GDB packet (i.e: `$<payload>#<checksum>`).
+pub fn packet(payload: &[u8]) -> Vec<u8> {
+    let checksum = payload.iter().fold(0u8, |a, b| a.wrapping_add(*b));
+    let mut packet = Vec::new();
+    packet.push(b'$');
+    packet.extend_from_slice(payload);
+    packet.extend_from_slice(alloc::format!("#{:02x}", checksum).as_bytes());
+    packet
+}
@@ -13,11 +13,16 @@ pub use stop_reason::SingleThreadStopReason;
 mod builder;
 mod core_impl;
 mod error;
+#[cfg(test)]
+mod mock;
 mod stop_reason;
 
 pub mod state_machine;
 
 use self::error::InternalError;
+use crate::conn::AsyncConnection;
+use crate::conn::AsyncConnectionError;
+use crate::conn::BufferedAsyncConnection;
 use crate::conn::Connection;
 use crate::conn::ConnectionExt;
 use crate::target::Target;
@@ -106,11 +111,76 @@ pub mod run_blocking {
     }
 }
 
+/// Types and traits related to the [`GdbStub::run_async`] interface.
+pub mod run_async {
+    use super::*;
+    use core::future::Future;
+
+    pub use super::run_blocking::Event;
+    pub use super::run_blocking::WaitForStopReasonError;
+
+    /// A set of user-provided methods required to run a GDB debugging session
+    /// using the [`GdbStub::run_async`] method.
+    ///
+    /// This is the async counterpart to
+    /// [`BlockingEventLoop`](super::run_blocking::BlockingEventLoop).
+    /// Implementations are free to use `async fn` when implementing this
+    /// trait.
+    pub trait AsyncEventLoop {
+        /// The Target being driven.
+        type Target: Target;
+        /// Connection being used to drive the target.
+        type Connection: AsyncConnection;
+
+        /// Which variant of the `StopReason` type should be used. Single
+        /// threaded targets should use [`SingleThreadStopReason`], whereas
+        /// multi threaded targets should use [`MultiThreadStopReason`].
+        ///
+        /// [`SingleThreadStopReason`]: crate::stub::SingleThreadStopReason
+        /// [`MultiThreadStopReason`]: crate::stub::MultiThreadStopReason
+        type StopReason: IntoStopReason<Self::Target>;
+
+        /// Invoked immediately after the target's `resume` method has been
+        /// called. The returned future should resolve once either the target
+        /// reports a stop reason, or new data was sent over the connection
+        /// (including Ctrl-C interrupts, which are sent as `0x03` bytes).
+        ///
+        /// The specific mechanism used to race these two events is
+        /// implementation specific (e.g: a runtime's `select!` macro). Note
+        /// that the future racing against the target should read from
+        /// `conn` in a cancellation-safe manner, as no data may be lost if the
+        /// target stops first.
+        fn wait_for_stop_reason(
+            target: &mut Self::Target,
+            conn: &mut Self::Connection,
+        ) -> impl Future<
+            Output = Result<
+                Event<Self::StopReason>,
+                WaitForStopReasonError<
+                    <Self::Target as Target>::Error,
+                    <Self::Connection as AsyncConnection>::Error,
+                >,
+            >,
+        >;
+
+        /// Invoked when the GDB client sends a Ctrl-C interrupt.
+        ///
+        /// See [`BlockingEventLoop::on_interrupt`] for more details.
+        ///
+        /// [`BlockingEventLoop::on_interrupt`]:
+        /// super::run_blocking::BlockingEventLoop::on_interrupt
+        fn on_interrupt(
+            target: &mut Self::Target,
+        ) -> impl Future<Output = Result<Option<Self::StopReason>, <Self::Target as Target>::Error>>;
+    }
+}
+
 /// Debug a [`Target`] using the GDB Remote Serial Protocol over a given
 /// [`Connection`].
 pub struct GdbStub<'a, T: Target, C: Connection> {
     conn: C,
     packet_buffer: ManagedSlice<'a, u8>,
+    resend_buffer: ManagedSlice<'a, u8>,
     inner: core_impl::GdbStubImpl<T, C>,
 }
 
@@ -167,8 +237,21 @@ impl<'a, T: Target, C: Connection> GdbStub<'a, T, C> {
             gdb = match gdb {
                 state_machine::GdbStubStateMachine::Idle(mut gdb) => {
                     // needs more data, so perform a blocking read on the connection
-                    let byte = gdb.borrow_conn().read().map_err(InternalError::conn_read)?;
-                    gdb.incoming_data(target, byte)?
+                    let byte = match gdb.pending_ack_timeout() {
+                        Some(timeout) => gdb
+                            .borrow_conn()
+                            .read_timeout(timeout)
+                            .map_err(InternalError::conn_read)?,
+                        None => Some(gdb.borrow_conn().read().map_err(InternalError::conn_read)?),
+                    };
+
+                    match byte {
+                        Some(byte) => gdb.incoming_data(target, byte)?,
+                        None => {
+                            gdb.ack_timed_out()?;
+                            gdb.into()
+                        }
+                    }
                 }
 
                 state_machine::GdbStubStateMachine::Disconnected(gdb) => {
@@ -242,3 +325,287 @@ impl<'a, T: Target, C: Connection> GdbStub<'a, T, C> {
         Ok(state_machine::GdbStubStateMachineInner::from_plain_gdbstub(self).into())
     }
 }
+
+impl<T: Target, A: AsyncConnection> GdbStub<'_, T, BufferedAsyncConnection<'_, A>> {
+    /// (Quickstart) Start a GDB remote debugging session using an async event
+    /// loop.
+    ///
+    /// This is the async counterpart to [`GdbStub::run_blocking`], and works
+    /// with any async runtime (including `no_std` executors). The `GdbStub`
+    /// must be created using a [`BufferedAsyncConnection`], and the
+    /// implementation must provide an implementation of
+    /// [`run_async::AsyncEventLoop`].
+    ///
+    /// `GdbStub::run_async` returns once the GDB client closes the debugging
+    /// session, or if the target triggers a disconnect.
+    pub async fn run_async<E>(
+        mut self,
+        target: &mut T,
+    ) -> Result<DisconnectReason, GdbStubError<T::Error, AsyncConnectionError<A::Error>>>
+    where
+        E: run_async::AsyncEventLoop<Target = T, Connection = A>,
+    {
+        self.conn
+            .get_mut()
+            .on_session_start()
+            .await
+            .map_err(|e| InternalError::conn_init(AsyncConnectionError::Connection(e)))?;
+
+        let mut gdb = self.run_state_machine(target)?;
+        loop {
+            gdb = match gdb {
+                state_machine::GdbStubStateMachine::Idle(mut gdb) => {
+                    flush_buffered(gdb.borrow_conn()).await?;
+
+                    // needs more data, so wait for the next byte on the connection
+                    let ack_timeout = gdb.pending_ack_timeout();
+                    let conn = gdb.borrow_conn().get_mut();
+                    let byte = match ack_timeout {
+                        Some(timeout) => conn.read_timeout(timeout).await,
+                        None => conn.read().await.map(Some),
+                    }
+                    .map_err(|e| InternalError::conn_read(AsyncConnectionError::Connection(e)))?;
+
+                    match byte {
+                        Some(byte) => gdb.incoming_data(target, byte)?,
+                        None => {
+                            gdb.ack_timed_out()?;
+                            gdb.into()
+                        }
+                    }
+                }
+
+                state_machine::GdbStubStateMachine::Disconnected(mut gdb) => {
+                    flush_buffered(gdb.borrow_conn()).await?;
+                    break Ok(gdb.get_reason());
+                }
+
+                state_machine::GdbStubStateMachine::CtrlCInterrupt(gdb) => {
+                    // defer to the implementation on how it wants to handle the interrupt
+                    let stop_reason = E::on_interrupt(target)
+                        .await
+                        .map_err(InternalError::TargetError)?;
+                    gdb.interrupt_handled(target, stop_reason)?
+                }
+
+                state_machine::GdbStubStateMachine::Running(mut gdb) => {
+                    use run_async::Event as AsyncEventLoopEvent;
+                    use run_async::WaitForStopReasonError;
+
+                    flush_buffered(gdb.borrow_conn()).await?;
+
+                    // wait for the target to return a stop reason
+                    let event = E::wait_for_stop_reason(target, gdb.borrow_conn().get_mut()).await;
+                    match event {
+                        Ok(AsyncEventLoopEvent::TargetStopped(stop_reason)) => {
+                            gdb.report_stop(target, stop_reason)?
+                        }
+
+                        Ok(AsyncEventLoopEvent::IncomingData(byte)) => {
+                            gdb.incoming_data(target, byte)?
+                        }
+
+                        Err(WaitForStopReasonError::Target(e)) => {
+                            break Err(InternalError::TargetError(e).into());
+                        }
+                        Err(WaitForStopReasonError::Connection(e)) => {
+                            break Err(InternalError::conn_read(AsyncConnectionError::Connection(
+                                e,
+                            ))
+                            .into());
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Write out any responses buffered by the state machine.
+async fn flush_buffered<T, A: AsyncConnection>(
+    conn: &mut BufferedAsyncConnection<'_, A>,
+) -> Result<(), InternalError<T, AsyncConnectionError<A::Error>>> {
+    conn.flush_buffered()
+        .await
+        .map_err(|e| InternalError::conn_write(AsyncConnectionError::Connection(e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub::mock::MockTarget;
+    use alloc::collections::VecDeque;
+    use alloc::vec::Vec;
+    use core::future::Future;
+    use core::marker::PhantomData;
+    use core::pin::pin;
+    use core::task::Context;
+    use core::task::Poll;
+    use core::task::Waker;
+    use core::time::Duration;
+
+    /// Polls a future which never has to wait on any external events.
+    fn block_on<F: Future>(fut: F) -> F::Output {
+        let mut fut = pin!(fut);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = fut.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    /// `None` entries in `incoming` simulate a read timing out.
+    #[derive(Default)]
+    struct MockAsyncConnection {
+        incoming: VecDeque<Option<u8>>,
+        data: Vec<u8>,
+    }
+
+    impl MockAsyncConnection {
+        fn recv(&mut self, data: &[u8]) {
+            self.incoming.extend(data.iter().copied().map(Some))
+        }
+    }
+
+    impl AsyncConnection for MockAsyncConnection {
+        type Error = ();
+
+        async fn read(&mut self) -> Result<u8, ()> {
+            loop {
+                if let Some(byte) = self.incoming.pop_front().ok_or(())? {
+                    return Ok(byte);
+                }
+            }
+        }
+
+        async fn read_timeout(&mut self, _timeout: Duration) -> Result<Option<u8>, ()> {
+            self.incoming.pop_front().ok_or(())
+        }
+
+        async fn write_all(&mut self, buf: &[u8]) -> Result<(), ()> {
+            self.data.extend_from_slice(buf);
+            Ok(())
+        }
+
+        async fn flush(&mut self) -> Result<(), ()> {
+            Ok(())
+        }
+    }
+
+    struct MockEventLoop<'a>(PhantomData<&'a mut MockAsyncConnection>);
+
+    impl<'a> run_async::AsyncEventLoop for MockEventLoop<'a> {
+        type Target = MockTarget;
+        type Connection = &'a mut MockAsyncConnection;
+        type StopReason = SingleThreadStopReason<u32>;
+
+        async fn wait_for_stop_reason(
+            _target: &mut MockTarget,
+            conn: &mut &'a mut MockAsyncConnection,
+        ) -> Result<run_async::Event<Self::StopReason>, run_async::WaitForStopReasonError<(), ()>>
+        {
+            let byte = conn
+                .read()
+                .await
+                .map_err(run_async::WaitForStopReasonError::Connection)?;
+            Ok(run_async::Event::IncomingData(byte))
+        }
+
+        async fn on_interrupt(_target: &mut MockTarget) -> Result<Option<Self::StopReason>, ()> {
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn run_async() {
+        let mut conn = MockAsyncConnection::default();
+        conn.recv(b"$g#67+$D#44");
+
+        let mut packet_buffer = [0; 64];
+        let mut tx_buffer = [0; 64];
+        let gdb = GdbStub::builder(BufferedAsyncConnection::with_buffer(
+            &mut conn,
+            &mut tx_buffer,
+        ))
+        .with_packet_buffer(&mut packet_buffer)
+        .build()
+        .unwrap();
+
+        let reason = block_on(gdb.run_async::<MockEventLoop<'_>>(&mut MockTarget)).unwrap();
+        assert_eq!(reason, DisconnectReason::Disconnect);
+        // "00000000", run-length encoded
+        assert_eq!(conn.data, b"+$000*\"#dc+$OK#9a");
+    }
+
+    #[test]
+    fn run_async_buffer_overflow() {
+        let mut conn = MockAsyncConnection::default();
+        conn.recv(b"$g#67");
+
+        let mut tx_buffer = [0; 4];
+        let gdb = GdbStub::new(BufferedAsyncConnection::with_buffer(
+            &mut conn,
+            &mut tx_buffer,
+        ));
+
+        let err = block_on(gdb.run_async::<MockEventLoop<'_>>(&mut MockTarget)).unwrap_err();
+        assert!(matches!(
+            err.into_connection_error(),
+            Some((AsyncConnectionError::BufferOverflow, _))
+        ));
+    }
+
+    #[test]
+    fn run_async_retransmit() {
+        let mut conn = MockAsyncConnection::default();
+        // NAK'd, then timed out
+        conn.recv(b"$g#67-");
+        conn.incoming.push_back(None);
+        conn.recv(b"+$D#44");
+
+        let mut packet_buffer = [0; 64];
+        let mut resend_buffer = [0; 64];
+        let mut tx_buffer = [0; 64];
+        let gdb = GdbStub::builder(BufferedAsyncConnection::with_buffer(
+            &mut conn,
+            &mut tx_buffer,
+        ))
+        .with_packet_buffer(&mut packet_buffer)
+        .with_resend_buffer(&mut resend_buffer)
+        .ack_timeout(Duration::from_secs(1))
+        .build()
+        .unwrap();
+
+        let reason = block_on(gdb.run_async::<MockEventLoop<'_>>(&mut MockTarget)).unwrap();
+        assert_eq!(reason, DisconnectReason::Disconnect);
+        assert_eq!(conn.data, b"+$000*\"#dc$000*\"#dc$000*\"#dc+$OK#9a");
+    }
+
+    #[test]
+    fn run_async_retry_limit() {
+        let mut conn = MockAsyncConnection::default();
+        conn.recv(b"$g#67");
+        conn.incoming.extend([None, None]);
+        conn.recv(b"+$D#44");
+
+        let mut packet_buffer = [0; 64];
+        let mut resend_buffer = [0; 64];
+        let mut tx_buffer = [0; 64];
+        let gdb = GdbStub::builder(BufferedAsyncConnection::with_buffer(
+            &mut conn,
+            &mut tx_buffer,
+        ))
+        .with_packet_buffer(&mut packet_buffer)
+        .with_resend_buffer(&mut resend_buffer)
+        .ack_timeout(Duration::from_secs(1))
+        .max_retries(1)
+        .build()
+        .unwrap();
+
+        let res = block_on(gdb.run_async::<MockEventLoop<'_>>(&mut MockTarget));
+        assert!(res.is_err());
+        // the second timeout exceeds the retry limit
+        assert_eq!(conn.incoming.len(), 6);
+    }
+}
@@ -40,13 +40,12 @@ use crate::arch::Arch;
 use crate::arch::RegId;
 use crate::conn::Connection;
 use crate::protocol::recv_packet::RecvPacketStateMachine;
-use crate::protocol::Packet;
-use crate::protocol::ResponseWriter;
 use crate::stub::error::GdbStubError;
 use crate::stub::error::InternalError;
 use crate::stub::stop_reason::IntoStopReason;
 use crate::stub::BaseStopReason;
 use crate::target::Target;
+use core::time::Duration;
 use managed::ManagedSlice;
 
 /// State-machine interface to `GdbStub`.
@@ -169,10 +168,34 @@ where
 struct GdbStubStateMachineReallyInner<'a, T: Target, C: Connection> {
     conn: C,
     packet_buffer: ManagedSlice<'a, u8>,
+    resend_buffer: ManagedSlice<'a, u8>,
     recv_packet: RecvPacketStateMachine,
     inner: GdbStubImpl<T, C>,
 }
 
+impl<T: Target, C: Connection> GdbStubStateMachineReallyInner<'_, T, C> {
+    /// Pass a byte to the packet receiver, handling the packet once it has
+    /// been received in its entirety.
+    fn pump(
+        &mut self,
+        target: &mut T,
+        byte: u8,
+    ) -> Result<Option<State>, GdbStubError<T::Error, C::Error>> {
+        let packet_buffer = match self.recv_packet.pump(&mut self.packet_buffer, byte)? {
+            Some(buf) => buf,
+            None => return Ok(None),
+        };
+
+        let state = self.inner.handle_packet(
+            target,
+            &mut self.conn,
+            &mut self.resend_buffer,
+            packet_buffer,
+        )?;
+        Ok(Some(state))
+    }
+}
+
 /// Core state machine implementation that is parameterized by various
 /// [states](state). Can be converted back into the appropriate
 /// [`GdbStubStateMachine`] variant via [`Into::into`].
@@ -187,6 +210,28 @@ impl<S, T: Target, C: Connection> GdbStubStateMachineInner<'_, S, T, C> {
     pub fn borrow_conn(&mut self) -> &mut C {
         &mut self.i.conn
     }
+
+    /// If the GDB client has yet to acknowledge the last packet sent to it,
+    /// returns how long to wait for an acknowledgement before calling
+    /// [`ack_timed_out`](Self::ack_timed_out).
+    ///
+    /// Always returns `None` unless an ack timeout was configured via
+    /// [`GdbStubBuilder::ack_timeout`](crate::stub::GdbStubBuilder::ack_timeout).
+    pub fn pending_ack_timeout(&self) -> Option<Duration> {
+        self.i.inner.pending_ack_timeout(&self.i.resend_buffer)
+    }
+
+    /// Re-transmit the last packet sent to the GDB client, as it was not
+    /// acknowledged in time.
+    ///
+    /// Counts towards the limit set via
+    /// [`GdbStubBuilder::max_retries`](crate::stub::GdbStubBuilder::max_retries).
+    pub fn ack_timed_out(&mut self) -> Result<(), GdbStubError<T::Error, C::Error>> {
+        self.i
+            .inner
+            .resend_last_packet(&mut self.i.conn, &self.i.resend_buffer)?;
+        Ok(())
+    }
 }
 
 /// Methods which can only be called from the [`GdbStubStateMachine::Idle`]
@@ -200,6 +245,7 @@ impl<'a, T: Target, C: Connection> GdbStubStateMachineInner<'a, state::Idle<T>,
             i: GdbStubStateMachineReallyInner {
                 conn: stub.conn,
                 packet_buffer: stub.packet_buffer,
+                resend_buffer: stub.resend_buffer,
                 recv_packet: RecvPacketStateMachine::new(),
                 inner: stub.inner,
             },
@@ -215,16 +261,10 @@ impl<'a, T: Target, C: Connection> GdbStubStateMachineInner<'a, state::Idle<T>,
         target: &mut T,
         byte: u8,
     ) -> Result<GdbStubStateMachine<'a, T, C>, GdbStubError<T::Error, C::Error>> {
-        let packet_buffer = match self.i.recv_packet.pump(&mut self.i.packet_buffer, byte)? {
-            Some(buf) => buf,
+        let state = match self.i.pump(target, byte)? {
+            Some(state) => state,
             None => return Ok(self.into()),
         };
-
-        let packet = Packet::from_buf(target, packet_buffer).map_err(InternalError::PacketParse)?;
-        let state = self
-            .i
-            .inner
-            .handle_packet(target, &mut self.i.conn, packet)?;
         Ok(match state {
             State::Pump => self.into(),
             State::Disconnect(reason) => self.transition(state::Disconnected { reason }).into(),
@@ -299,7 +339,10 @@ impl<'a, T: Target, C: Connection> GdbStubStateMachineInner<'a, state::Running,
         regs: Option<&mut dyn Iterator<Item = (<<T as Target>::Arch as Arch>::RegId, &[u8])>>,
     ) -> Result<GdbStubStateMachine<'a, T, C>, GdbStubError<T::Error, C::Error>> {
         let reason: BaseStopReason<_, _> = reason.into();
-        let mut res = ResponseWriter::new(&mut self.i.conn, target.use_rle());
+        let mut res =
+            self.i
+                .inner
+                .response_writer(&mut self.i.conn, &mut self.i.resend_buffer, target);
         let event = self.i.inner.finish_exec(&mut res, target, reason)?;
 
         if let Some(regs) = regs {
@@ -334,16 +377,10 @@ impl<'a, T: Target, C: Connection> GdbStubStateMachineInner<'a, state::Running,
         target: &mut T,
         byte: u8,
     ) -> Result<GdbStubStateMachine<'a, T, C>, GdbStubError<T::Error, C::Error>> {
-        let packet_buffer = match self.i.recv_packet.pump(&mut self.i.packet_buffer, byte)? {
-            Some(buf) => buf,
+        let state = match self.i.pump(target, byte)? {
+            Some(state) => state,
             None => return Ok(self.into()),
         };
-
-        let packet = Packet::from_buf(target, packet_buffer).map_err(InternalError::PacketParse)?;
-        let state = self
-            .i
-            .inner
-            .handle_packet(target, &mut self.i.conn, packet)?;
         Ok(match state {
             State::Pump => self.transition(state::Running {}).into(),
             State::Disconnect(reason) => self.transition(state::Disconnected { reason }).into(),
@@ -421,3 +458,135 @@ impl<'a, T: Target, C: Connection> GdbStubStateMachineInner<'a, state::Disconnec
         .into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub::mock::MockConnection;
+    use crate::stub::mock::MockTarget;
+    use alloc::vec::Vec;
+
+    type Gdb<'a> = GdbStubStateMachine<'a, MockTarget, MockConnection>;
+
+    // "00000000", run-length encoded
+    const G_RESPONSE: &[u8] = b"$000*\"#dc";
+
+    fn start(builder: crate::stub::GdbStubBuilder<'_, MockTarget, MockConnection>) -> Gdb<'_> {
+        builder
+            .build()
+            .unwrap()
+            .run_state_machine(&mut MockTarget)
+            .unwrap()
+    }
+
+    fn send<'a>(mut gdb: Gdb<'a>, data: &[u8]) -> Result<Gdb<'a>, GdbStubError<(), ()>> {
+        for &b in data {
+            gdb = match gdb {
+                GdbStubStateMachine::Idle(gdb) => gdb.incoming_data(&mut MockTarget, b)?,
+                gdb => return Ok(gdb),
+            };
+        }
+        Ok(gdb)
+    }
+
+    fn take_output(gdb: &mut Gdb<'_>) -> Vec<u8> {
+        match gdb {
+            GdbStubStateMachine::Idle(gdb) => core::mem::take(&mut gdb.borrow_conn().data),
+            _ => Vec::new(),
+        }
+    }
+
+    fn pending_ack_timeout(gdb: &Gdb<'_>) -> Option<Duration> {
+        match gdb {
+            GdbStubStateMachine::Idle(gdb) => gdb.pending_ack_timeout(),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn nack_resends_last_packet() {
+        let mut gdb = start(GdbStub::builder(MockConnection::default()).resend_buffer_size(4096));
+
+        gdb = send(gdb, b"$g#67").unwrap();
+        assert_eq!(take_output(&mut gdb), [b"+", G_RESPONSE].concat());
+
+        gdb = send(gdb, b"-").unwrap();
+        assert_eq!(take_output(&mut gdb), G_RESPONSE);
+
+        gdb = send(gdb, b"-+$g#67").unwrap();
+        assert_eq!(
+            take_output(&mut gdb),
+            [G_RESPONSE, b"+", G_RESPONSE].concat()
+        );
+    }
+
+    #[test]
+    fn nack_bad_checksum() {
+        let mut gdb = start(GdbStub::builder(MockConnection::default()));
+
+        gdb = send(gdb, b"$g#00").unwrap();
+        assert_eq!(take_output(&mut gdb), b"-");
+
+        gdb = send(gdb, b"$g#67").unwrap();
+        assert_eq!(take_output(&mut gdb), [b"+", G_RESPONSE].concat());
+    }
+
+    #[test]
+    fn ignore_stray_bytes() {
+        let mut gdb = start(GdbStub::builder(MockConnection::default()));
+
+        // the tail end of a packet whose header was lost
+        gdb = send(gdb, b"0,4#fd").unwrap();
+        assert_eq!(take_output(&mut gdb), b"");
+
+        // a packet whose checksum was lost
+        gdb = send(gdb, b"$m0,4$g#67").unwrap();
+        assert_eq!(take_output(&mut gdb), [b"+", G_RESPONSE].concat());
+    }
+
+    #[test]
+    fn retry_limit() {
+        let gdb = start(
+            GdbStub::builder(MockConnection::default())
+                .resend_buffer_size(4096)
+                .max_retries(2),
+        );
+
+        let gdb = send(gdb, b"$g#67--").unwrap();
+        assert!(send(gdb, b"-").is_err());
+
+        let gdb = start(GdbStub::builder(MockConnection::default()).max_retries(2));
+        let gdb = send(gdb, b"$g#00$g#00").unwrap();
+        assert!(send(gdb, b"$g#00").is_err());
+    }
+
+    #[test]
+    fn retransmission_disabled() {
+        let gdb = start(GdbStub::builder(MockConnection::default()));
+        let gdb = send(gdb, b"$g#67").unwrap();
+        assert!(send(gdb, b"-").is_err());
+    }
+
+    #[test]
+    fn ack_timeout() {
+        let timeout = Duration::from_millis(100);
+        let mut gdb = start(
+            GdbStub::builder(MockConnection::default())
+                .resend_buffer_size(4096)
+                .ack_timeout(timeout),
+        );
+        assert_eq!(pending_ack_timeout(&gdb), None);
+
+        gdb = send(gdb, b"$g#67").unwrap();
+        assert_eq!(take_output(&mut gdb), [b"+", G_RESPONSE].concat());
+        assert_eq!(pending_ack_timeout(&gdb), Some(timeout));
+
+        if let GdbStubStateMachine::Idle(gdb) = &mut gdb {
+            gdb.ack_timed_out().unwrap();
+        }
+        assert_eq!(take_output(&mut gdb), G_RESPONSE);
+
+        gdb = send(gdb, b"+").unwrap();
+        assert_eq!(pending_ack_timeout(&gdb), None);
+    }
+}
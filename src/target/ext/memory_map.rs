@@ -0,0 +1,47 @@
+//! Provide a memory map for the target.
+use crate::arch::Arch;
+use crate::target::Target;
+use crate::target::TargetResult;
+
+/// Target Extension - Read the target's memory map.
+pub trait MemoryMap: Target {
+    /// Get memory map XML file from the target.
+    ///
+    /// See the [GDB Documentation] for a description of the format.
+    ///
+    /// [GDB Documentation]: https://sourceware.org/gdb/onlinedocs/gdb/Memory-Map-Format.html
+    ///
+    /// Return the number of bytes written into `buf` (which may be less than
+    /// `length`).
+    ///
+    /// If `offset` is greater than the length of the underlying data, return
+    /// `Ok(0)`.
+    fn memory_map_xml(
+        &self,
+        offset: u64,
+        length: usize,
+        buf: &mut [u8],
+    ) -> TargetResult<usize, Self>;
+
+    /// Check whether `addr` resides in a memory region which supports memory
+    /// tagging (e.g: AArch64 MTE).
+    ///
+    /// GDB's memory map XML format has no way to mark tagged regions, so on
+    /// targets which implement
+    /// [`MemoryTagging`](crate::target::ext::memory_tagging::MemoryTagging),
+    /// `gdbstub` uses this method to answer the GDB client's queries about
+    /// which memory ranges are tagged. Returning `None` defers to
+    /// [`MemoryTagging::is_address_tagged`].
+    ///
+    /// **By default, this method returns `None`.**
+    ///
+    /// [`MemoryTagging::is_address_tagged`]:
+    ///     crate::target::ext::memory_tagging::MemoryTagging::is_address_tagged
+    #[inline(always)]
+    fn is_region_tagged(&self, addr: <Self::Arch as Arch>::Usize) -> Option<bool> {
+        let _ = addr;
+        None
+    }
+}
+
+define_ext!(MemoryMapOps, MemoryMap);
@@ -0,0 +1,75 @@
+//! Read / write memory tags (e.g: AArch64 MTE allocation tags).
+use crate::arch::Arch;
+use crate::target::Target;
+use crate::target::TargetResult;
+
+/// Target Extension - Read / write memory tags.
+///
+/// Enables GDB's `memory-tag` family of commands (e.g: `memory-tag
+/// print-allocation-tag`, `memory-tag check`), and allows GDB to validate
+/// tagged pointers when reporting tag-check faults.
+///
+/// Memory tags are stored at the granularity of a _tag granule_ (e.g: 16
+/// bytes on AArch64), with each tag occupying a single byte on the wire. The
+/// meaning of the `tag_type` argument is architecture-specific. On AArch64,
+/// GDB only ever requests allocation tags (`tag_type == 1`).
+///
+/// _Note:_ At the time of writing, the GDB client only supports memory tagging
+/// on AArch64 GNU/Linux targets whose target description includes the
+/// `org.gnu.gdb.aarch64.mte` feature.
+///
+/// See the [GDB Documentation] for more details.
+///
+/// [GDB Documentation]: https://sourceware.org/gdb/current/onlinedocs/gdb.html/Memory-Tagging.html
+pub trait MemoryTagging: Target {
+    /// Read the tags of all the tag granules overlapping the memory range
+    /// `start_addr..start_addr + len` into `data`.
+    ///
+    /// Each tag should be written as a single byte, in order of increasing
+    /// address.
+    ///
+    /// Return the number of tags read, which must be no greater than
+    /// `data.len()`. If the tags of the entire range do not fit into `data`,
+    /// an error should be returned.
+    fn read_memory_tags(
+        &mut self,
+        start_addr: <Self::Arch as Arch>::Usize,
+        len: usize,
+        tag_type: i32,
+        data: &mut [u8],
+    ) -> TargetResult<usize, Self>;
+
+    /// Write the tags of all the tag granules overlapping the memory range
+    /// `start_addr..start_addr + len`.
+    ///
+    /// If fewer `tags` are provided than there are granules in the range, the
+    /// provided tags should be repeated (in order) until the entire range has
+    /// been tagged. e.g: a single tag may be used to tag an entire range.
+    fn write_memory_tags(
+        &mut self,
+        start_addr: <Self::Arch as Arch>::Usize,
+        len: usize,
+        tag_type: i32,
+        tags: &[u8],
+    ) -> TargetResult<(), Self>;
+
+    /// Check whether `addr` resides in a memory region which supports memory
+    /// tagging.
+    ///
+    /// The GDB client uses this method to determine which memory ranges are
+    /// tagged. Targets which implement
+    /// [`MemoryMap`](crate::target::ext::memory_map::MemoryMap) can instead
+    /// mark tagged regions via [`MemoryMap::is_region_tagged`], in which case
+    /// this method is only called for addresses the memory map doesn't cover.
+    ///
+    /// **By default, this method returns `false`.**
+    ///
+    /// [`MemoryMap::is_region_tagged`]:
+    ///     crate::target::ext::memory_map::MemoryMap::is_region_tagged
+    fn is_address_tagged(&mut self, addr: <Self::Arch as Arch>::Usize) -> TargetResult<bool, Self> {
+        let _ = addr;
+        Ok(false)
+    }
+}
+
+define_ext!(MemoryTaggingOps, MemoryTagging);
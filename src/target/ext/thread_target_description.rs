@@ -0,0 +1,57 @@
+//! Report a different target description for each thread.
+use crate::target::Target;
+
+/// Target Extension - Report a different target description (i.e: a
+/// different architecture and register set) for each thread.
+///
+/// This enables debugging targets which run code in multiple architectures
+/// or execution modes at once, such as AArch64 hosts running AArch32
+/// processes, or x86-64 guests switching between real mode, 32-bit, and
+/// 64-bit code.
+///
+/// Each thread's target description is identified by an _annex_, which the
+/// GDB client fetches via the `qXfer:features:read` packet. As such, targets
+/// implementing this IDET must also implement
+/// [`TargetDescriptionXmlOverride`] and serve every annex reported by
+/// [`thread_target_description`](Self::thread_target_description).
+///
+/// Whenever the target stops (and in response to the `?` packet), `gdbstub`
+/// will include a `tdesc:<annex>;` field in the stop reply, informing the GDB
+/// client of the stopped thread's current target description. Single threaded
+/// targets can use this IDET to switch target descriptions at runtime (e.g:
+/// when switching between execution modes).
+///
+/// _Note:_ The `S` stop reply used by the `DoneStep` and `Signal` stop reasons
+/// cannot carry a `tdesc` field, so targets should report
+/// `SignalWithThread` (or another thread-specific stop reason) instead.
+///
+/// As the "g" packet register layout varies between threads, the target's
+/// `Arch::Registers` type must be able to represent all possible register
+/// sets (e.g: by using [`gdbstub_arch::dynamic::DynamicArch`], or an `enum`
+/// of fixed register structs), with `read_registers` / `write_registers`
+/// using the layout of the specified thread (or the current execution mode).
+///
+/// [`TargetDescriptionXmlOverride`]:
+///     crate::target::ext::target_description_xml_override::TargetDescriptionXmlOverride
+/// [`gdbstub_arch::dynamic::DynamicArch`]:
+///     https://docs.rs/gdbstub_arch/latest/gdbstub_arch/dynamic/enum.DynamicArch.html
+pub trait ThreadTargetDescription<Tid>: Target
+where
+    Tid: crate::is_valid_tid::IsValidTid,
+{
+    /// Return the annex of the target description currently used by `tid`
+    /// (e.g: `"aarch32.xml"`), or `None` to use the default `"target.xml"`.
+    ///
+    /// On single threaded targets, `tid` is set to `()` and can be ignored.
+    ///
+    /// The annex must be a plain file name, and must not contain any of the
+    /// `:`, `;`, `#`, `$`, `}`, or `*` characters.
+    fn thread_target_description(&mut self, tid: Tid) -> Result<Option<&str>, Self::Error>;
+}
+
+/// See [`ThreadTargetDescription`]
+pub type ThreadTargetDescriptionOps<'a, Tid, T> = &'a mut dyn ThreadTargetDescription<
+    Tid,
+    Arch = <T as Target>::Arch,
+    Error = <T as Target>::Error,
+>;